@@ -0,0 +1,67 @@
+use crate::commands::diff::section_heading;
+use crate::models::history::RecordHistory;
+use crate::models::Graph;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+    let record = graph
+        .get(id)
+        .ok_or_else(|| anyhow!("Record not found: {}", id))?;
+    let history = RecordHistory::load(docs_path, record)?;
+    let blame = history.blame(&record.content);
+
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "id": record.id(),
+                "sections": blame,
+            }))?
+        );
+        return Ok(());
+    }
+
+    let author_width = blame
+        .iter()
+        .filter_map(|b| b.author.as_deref())
+        .map(|a| a.chars().count())
+        .chain(["Not committed yet".len()])
+        .max()
+        .unwrap_or(0);
+    let heading_width = blame
+        .iter()
+        .map(|b| section_heading(&b.heading).chars().count())
+        .max()
+        .unwrap_or(0);
+    for section in &blame {
+        let heading = format!(
+            "{:<width$}",
+            section_heading(&section.heading),
+            width = heading_width
+        );
+        match (&section.commit, &section.author, &section.date) {
+            (Some(commit), Some(author), Some(date)) => println!(
+                "{} {} {:<width$}  {}  {}",
+                commit[..7.min(commit.len())].yellow(),
+                date.format("%Y-%m-%d").to_string().dimmed(),
+                author,
+                heading.bold(),
+                section.message.as_deref().unwrap_or("").dimmed(),
+                width = author_width
+            ),
+            _ => println!(
+                "{} {:10} {}  {}",
+                "0000000".dimmed(),
+                "",
+                format!("{:<width$}", "Not committed yet", width = author_width).yellow(),
+                heading.bold()
+            ),
+        }
+    }
+
+    Ok(())
+}
@@ -57,7 +57,7 @@ pub fn run(
         .collect();
 
     // Sort by created date (newest first)
-    records.sort_by(|a, b| b.frontmatter.created.cmp(&a.frontmatter.created));
+    records.sort_by_key(|r| std::cmp::Reverse(r.frontmatter.created));
 
     if records.is_empty() {
         if format == "json" {
@@ -0,0 +1,49 @@
+use crate::git::GitHistory;
+use crate::models::Graph;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+    let record = graph
+        .get(id)
+        .ok_or_else(|| anyhow!("Record not found: {}", id))?;
+    let commits = GitHistory::new(docs_path)?.commits_for(record.id())?;
+
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "id": record.id(),
+                "commits": commits,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if commits.is_empty() {
+        println!(
+            "No commits name {} in a Decision: or Implements: trailer.",
+            record.id()
+        );
+        return Ok(());
+    }
+
+    for commit in &commits {
+        println!(
+            "{} {} {}",
+            commit.short().yellow(),
+            commit.date.format("%Y-%m-%d").to_string().dimmed(),
+            commit.author.cyan()
+        );
+        println!("    {}", commit.message);
+        for file in &commit.files {
+            println!("      {}", file.dimmed());
+        }
+        println!();
+    }
+
+    Ok(())
+}
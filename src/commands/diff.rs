@@ -1,158 +1,398 @@
+use crate::git::GitHistory;
+use crate::models::code_refs::project_root;
+use crate::models::diff::{FieldChange, GraphDiff, LinkChange, RecordDiff, SectionStatus};
+use crate::models::governance::Governance;
 use crate::models::Graph;
-use anyhow::{bail, Result};
+use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::Path;
-use std::process::Command;
 
-pub fn run(docs_dir: &str, base_ref: Option<&str>) -> Result<()> {
+/// A record that governs code changed between the two refs
+#[derive(Debug, Serialize)]
+struct GoverningRecord {
+    id: String,
+    title: Option<String>,
+    status: Option<String>,
+    /// Changed paths it governs, relative to the project root
+    paths: Vec<String>,
+}
+
+pub fn run(
+    docs_dir: &str,
+    base_ref: Option<&str>,
+    head_ref: Option<&str>,
+    format: &str,
+) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let base = base_ref.unwrap_or("HEAD");
 
-    // Get the decisions directory relative path
-    let decisions_dir = docs_path.join("decisions");
-    if !decisions_dir.exists() {
-        bail!("No decisions directory found");
-    }
-
-    // Get list of changed files from git
-    let output = Command::new("git")
-        .args([
-            "diff",
-            "--name-status",
-            base,
-            "--",
-            decisions_dir.to_str().unwrap(),
-        ])
-        .output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("git diff failed: {}", stderr);
-    }
-
-    let diff_output = String::from_utf8_lossy(&output.stdout);
-
-    // Also get untracked files
-    let untracked_output = Command::new("git")
-        .args([
-            "ls-files",
-            "--others",
-            "--exclude-standard",
-            decisions_dir.to_str().unwrap(),
-        ])
-        .output()?;
-
-    let untracked = String::from_utf8_lossy(&untracked_output.stdout);
-
-    // Parse changes
-    let mut added: Vec<String> = Vec::new();
-    let mut modified: Vec<String> = Vec::new();
-    let mut deleted: Vec<String> = Vec::new();
-
-    for line in diff_output.lines() {
-        if line.is_empty() {
-            continue;
+    // The head side defaults to the working tree, untracked records included
+    let before = Graph::load_at(docs_path, base)?;
+    let after = Graph::load_as_of(docs_path, head_ref)?;
+    let diff = GraphDiff::compare(&before, &after);
+    let governing = governing_records(docs_path, &after, base, head_ref)?;
+    let head = head_ref.unwrap_or("working tree");
+
+    match format {
+        "json" => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "base": base,
+                    "head": head,
+                    "diff": diff,
+                    "governing": governing,
+                }))?
+            );
+        }
+        "markdown" | "md" => {
+            let mut out = to_markdown(&diff, base, head);
+            if !governing.is_empty() {
+                if !out.ends_with("\n\n") {
+                    out.push('\n');
+                }
+                out.push_str(&governing_markdown(&governing));
+            }
+            print!("{}", out);
         }
-        let parts: Vec<&str> = line.splitn(2, '\t').collect();
-        if parts.len() != 2 {
-            continue;
+        _ => {
+            print_text(&diff, base, head);
+            print_governing(&governing);
         }
-        let status = parts[0];
-        let file = parts[1];
+    }
+
+    Ok(())
+}
 
-        // Extract record ID from filename
-        let record_id = extract_record_id(file);
+/// Records whose governance patterns cover the changed files
+fn governing_records(
+    docs_path: &Path,
+    graph: &Graph,
+    base: &str,
+    head: Option<&str>,
+) -> Result<Vec<GoverningRecord>> {
+    let governance = Governance::load(docs_path)?;
+    if governance.is_empty() {
+        return Ok(Vec::new());
+    }
+    let history = GitHistory::new(docs_path)?;
+    let root = project_root(docs_path);
 
-        match status {
-            "A" => added.push(record_id),
-            "M" => modified.push(record_id),
-            "D" => deleted.push(record_id),
-            _ => {}
+    let mut paths_by_record: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for changed in history.changed_paths(base, head)? {
+        let path = history
+            .root()
+            .join(&changed)
+            .strip_prefix(&root)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or(changed);
+        for id in governance.records_for(&path) {
+            paths_by_record
+                .entry(id.to_string())
+                .or_default()
+                .push(path.clone());
         }
     }
 
-    // Add untracked files as "added"
-    for line in untracked.lines() {
-        if line.is_empty() || !line.ends_with(".md") {
-            continue;
-        }
-        let record_id = extract_record_id(line);
-        if !added.contains(&record_id) {
-            added.push(record_id);
+    Ok(paths_by_record
+        .into_iter()
+        .map(|(id, paths)| {
+            let record = graph.get(&id);
+            GoverningRecord {
+                title: record.map(|r| r.title().to_string()),
+                status: record.map(|r| r.status().to_string()),
+                id,
+                paths,
+            }
+        })
+        .collect())
+}
+
+fn print_governing(governing: &[GoverningRecord]) {
+    if governing.is_empty() {
+        return;
+    }
+    println!("\n{}", "Governing records for changed code:".bold());
+    for record in governing {
+        println!(
+            "  {} {} {}",
+            record.id.cyan(),
+            record.title.as_deref().unwrap_or("[not found]"),
+            record
+                .status
+                .as_deref()
+                .map(|s| format!("({})", s))
+                .unwrap_or_default()
+                .dimmed()
+        );
+        for path in &record.paths {
+            println!("      {}", path.dimmed());
         }
     }
+}
+
+fn governing_markdown(governing: &[GoverningRecord]) -> String {
+    let mut out = String::from("### Governing records\n\nChanged code falls under:\n\n");
+    for record in governing {
+        let files: Vec<String> = record.paths.iter().map(|p| format!("`{}`", p)).collect();
+        out.push_str(&format!(
+            "- **{}** {} ({})\n",
+            record.id,
+            record.title.as_deref().unwrap_or("_not found_"),
+            files.join(", ")
+        ));
+    }
+    out
+}
+
+fn plural(n: usize, word: &str) -> String {
+    format!("{} {}{}", n, word, if n == 1 { "" } else { "s" })
+}
+
+fn field_value(value: &Option<String>) -> &str {
+    match value.as_deref() {
+        None => "(none)",
+        Some("") => "(empty)",
+        Some(v) => v,
+    }
+}
 
-    // Load current graph to get titles
-    let graph = Graph::load(docs_path).ok();
+fn link_text(link: &LinkChange) -> String {
+    format!("{} {} {}", link.from, link.link_type, link.to)
+}
 
-    // Print summary
-    let total = added.len() + modified.len() + deleted.len();
-    if total == 0 {
-        println!("No changes since {}", base);
-        return Ok(());
+fn print_text(diff: &GraphDiff, base: &str, head: &str) {
+    if diff.is_empty() {
+        println!("No record changes between {} and {}", base, head);
+        return;
     }
 
+    let total = diff.added.len() + diff.changed.len() + diff.removed.len();
     println!(
-        "{} since {}:\n",
-        format!(
-            "{} record change{}",
-            total,
-            if total == 1 { "" } else { "s" }
-        )
-        .bold(),
-        base.cyan()
+        "{} between {} and {}:\n",
+        plural(total, "record change").bold(),
+        base.cyan(),
+        head.cyan()
     );
 
-    if !added.is_empty() {
+    if !diff.added.is_empty() {
         println!("{}", "Added:".green().bold());
-        for id in &added {
-            let title = graph
-                .as_ref()
-                .and_then(|g| g.get(id))
-                .map(|r| r.title().to_string())
-                .unwrap_or_default();
-            println!("  {} {}", format!("+{}", id).green(), title.dimmed());
+        for record in &diff.added {
+            println!(
+                "  {} {} {}",
+                format!("+{}", record.id).green(),
+                record.title,
+                format!("({}, {})", record.record_type, record.status).dimmed()
+            );
         }
         println!();
     }
 
-    if !modified.is_empty() {
+    if !diff.changed.is_empty() {
         println!("{}", "Modified:".yellow().bold());
-        for id in &modified {
-            let title = graph
-                .as_ref()
-                .and_then(|g| g.get(id))
-                .map(|r| r.title().to_string())
-                .unwrap_or_default();
-            println!("  {} {}", format!("~{}", id).yellow(), title.dimmed());
+        for record in &diff.changed {
+            println!(
+                "  {} {}",
+                format!("~{}", record.id).yellow(),
+                record.title.dimmed()
+            );
+            print_record_diff(record);
         }
         println!();
     }
 
-    if !deleted.is_empty() {
+    if !diff.removed.is_empty() {
         println!("{}", "Deleted:".red().bold());
-        for id in &deleted {
-            println!("  {}", format!("-{}", id).red());
+        for record in &diff.removed {
+            println!(
+                "  {} {}",
+                format!("-{}", record.id).red(),
+                record.title.dimmed()
+            );
         }
         println!();
     }
 
-    Ok(())
+    if diff.edges_added.is_empty()
+        && diff.edges_removed.is_empty()
+        && diff.broken_links.is_empty()
+        && diff.newly_orphaned.is_empty()
+    {
+        return;
+    }
+    println!("{}", "Graph:".bold());
+    for edge in &diff.edges_added {
+        println!("  {} {}", "+".green(), link_text(edge));
+    }
+    for edge in &diff.edges_removed {
+        println!("  {} {}", "-".red(), link_text(edge));
+    }
+    for link in &diff.broken_links {
+        println!(
+            "  {} {} {}",
+            "✗".red(),
+            link_text(link),
+            "(target missing)".red()
+        );
+    }
+    for id in &diff.newly_orphaned {
+        println!("  {} {} {}", "○".yellow(), id, "is now orphaned".yellow());
+    }
 }
 
-fn extract_record_id(path: &str) -> String {
-    // Extract ID from path like "docs/decisions/DEC-001-some-title.md"
-    Path::new(path)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| {
-            // Take just the ID part (e.g., "DEC-001" from "DEC-001-some-title")
-            let parts: Vec<&str> = s.splitn(3, '-').collect();
-            if parts.len() >= 2 {
-                format!("{}-{}", parts[0], parts[1])
-            } else {
-                s.to_string()
+pub(crate) fn print_record_diff(record: &RecordDiff) {
+    for FieldChange {
+        field,
+        before,
+        after,
+    } in &record.fields
+    {
+        println!(
+            "      {}: {} → {}",
+            field,
+            field_value(before).red(),
+            field_value(after).green()
+        );
+    }
+    for tag in &record.tags_added {
+        println!("      tags: {}", format!("+{}", tag).green());
+    }
+    for tag in &record.tags_removed {
+        println!("      tags: {}", format!("-{}", tag).red());
+    }
+    for link in &record.links_added {
+        println!(
+            "      links: {}",
+            format!("+{} {}", link.link_type, link.to).green()
+        );
+    }
+    for link in &record.links_removed {
+        println!(
+            "      links: {}",
+            format!("-{} {}", link.link_type, link.to).red()
+        );
+    }
+    for section in &record.sections {
+        let heading = section_heading(&section.heading);
+        let line = match section.change {
+            SectionStatus::Added => format!("+{}", heading).green(),
+            SectionStatus::Removed => format!("-{}", heading).red(),
+            SectionStatus::Modified => format!("~{}", heading).yellow(),
+        };
+        println!("      section: {}", line);
+    }
+}
+
+pub(crate) fn section_heading(heading: &str) -> &str {
+    if heading.is_empty() {
+        "(intro)"
+    } else {
+        heading
+    }
+}
+
+/// A summary to paste into a pull request description
+fn to_markdown(diff: &GraphDiff, base: &str, head: &str) -> String {
+    let mut out = String::from("## Decision record changes\n\n");
+    if diff.is_empty() {
+        out.push_str(&format!(
+            "No record changes between `{}` and {}.\n",
+            base, head
+        ));
+        return out;
+    }
+
+    if !diff.added.is_empty() {
+        out.push_str("### Added\n\n");
+        for record in &diff.added {
+            out.push_str(&format!(
+                "- **{}** {} _({}, {})_\n",
+                record.id, record.title, record.record_type, record.status
+            ));
+        }
+        out.push('\n');
+    }
+
+    if !diff.changed.is_empty() {
+        out.push_str("### Changed\n\n");
+        for record in &diff.changed {
+            out.push_str(&format!("- **{}** {}\n", record.id, record.title));
+            for change in &record.fields {
+                out.push_str(&format!(
+                    "  - {}: `{}` → `{}`\n",
+                    change.field,
+                    field_value(&change.before),
+                    field_value(&change.after)
+                ));
             }
-        })
-        .unwrap_or_else(|| path.to_string())
+            let tags: Vec<String> = record
+                .tags_added
+                .iter()
+                .map(|t| format!("+{}", t))
+                .chain(record.tags_removed.iter().map(|t| format!("-{}", t)))
+                .collect();
+            if !tags.is_empty() {
+                out.push_str(&format!("  - tags: {}\n", tags.join(", ")));
+            }
+            for link in &record.links_added {
+                out.push_str(&format!(
+                    "  - added link: {} **{}**\n",
+                    link.link_type, link.to
+                ));
+            }
+            for link in &record.links_removed {
+                out.push_str(&format!(
+                    "  - removed link: {} **{}**\n",
+                    link.link_type, link.to
+                ));
+            }
+            for section in &record.sections {
+                let verb = match section.change {
+                    SectionStatus::Added => "added",
+                    SectionStatus::Removed => "removed",
+                    SectionStatus::Modified => "edited",
+                };
+                out.push_str(&format!(
+                    "  - {} section _{}_\n",
+                    verb,
+                    section_heading(&section.heading)
+                ));
+            }
+        }
+        out.push('\n');
+    }
+
+    if !diff.removed.is_empty() {
+        out.push_str("### Removed\n\n");
+        for record in &diff.removed {
+            out.push_str(&format!("- ~~**{}** {}~~\n", record.id, record.title));
+        }
+        out.push('\n');
+    }
+
+    if !diff.edges_added.is_empty()
+        || !diff.edges_removed.is_empty()
+        || !diff.broken_links.is_empty()
+        || !diff.newly_orphaned.is_empty()
+    {
+        out.push_str("### Graph\n\n");
+        out.push_str(&format!(
+            "{} added, {} removed\n\n",
+            plural(diff.edges_added.len(), "edge"),
+            diff.edges_removed.len()
+        ));
+        for link in &diff.broken_links {
+            out.push_str(&format!(
+                "- :warning: broken link: {} (target missing)\n",
+                link_text(link)
+            ));
+        }
+        for id in &diff.newly_orphaned {
+            out.push_str(&format!("- :warning: **{}** is now orphaned\n", id));
+        }
+    }
+
+    out
 }
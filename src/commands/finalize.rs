@@ -1,16 +1,96 @@
 //! Finalize draft records by converting temporary IDs to permanent incremental IDs
+//!
+//! IDs already taken on the base ref (`[git] base`, default `origin/main`)
+//! and on local branches are skipped, so branches finalized independently
+//! don't mint the same ID.
 
 use crate::commands::new::is_draft_id;
+use crate::git::history::GitHistory;
 use crate::models::{Graph, Record};
+use crate::serve::config::DgConfig;
 use anyhow::{anyhow, Result};
 use colored::Colorize;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use walkdir::WalkDir;
 
-/// Finalize all draft records, converting them to permanent IDs
-pub fn run(docs_dir: &str, dry_run: bool) -> Result<()> {
+/// Permanent IDs already claimed outside the working tree
+#[derive(Default)]
+struct SharedIds {
+    /// The base ref, when it resolved
+    base: Option<String>,
+    /// Record filenames on the base ref, keyed by ID
+    on_base: HashMap<String, Vec<String>>,
+    /// IDs that existed where this branch forked from the base ref
+    at_fork: HashSet<String>,
+    /// IDs taken on the base ref or any local branch
+    taken: Vec<String>,
+}
+
+impl SharedIds {
+    /// Read record filenames from the base ref and every local branch.
+    /// Outside a git repository, or when the base ref does not resolve,
+    /// only what can be read is used.
+    fn load(docs_path: &Path, base: &str) -> Self {
+        let mut shared = SharedIds::default();
+        let Ok(history) = GitHistory::new(docs_path) else {
+            return shared;
+        };
+        let ids_at = |rev: &str| -> Vec<(String, String)> {
+            history
+                .record_paths_at(rev)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|path| {
+                    let filename = file_name(Path::new(path));
+                    Some((id_from_filename(&filename)?, filename))
+                })
+                .collect()
+        };
+
+        let mut taken = HashSet::new();
+        if let Ok(revision) = history.resolve(base) {
+            for (id, filename) in ids_at(&revision.commit) {
+                taken.insert(id.clone());
+                shared.on_base.entry(id).or_default().push(filename);
+            }
+            if let Ok(fork) = history.merge_base("HEAD", &revision.commit) {
+                shared.at_fork = ids_at(&fork).into_iter().map(|(id, _)| id).collect();
+            }
+            shared.base = Some(base.to_string());
+        }
+        for branch in history.local_branches().unwrap_or_default() {
+            taken.extend(ids_at(&branch).into_iter().map(|(id, _)| id));
+        }
+
+        shared.taken = taken.into_iter().collect();
+        shared.taken.sort();
+        shared
+    }
+
+    fn is_on_base(&self, filename: &str) -> bool {
+        id_from_filename(filename)
+            .and_then(|id| self.on_base.get(&id))
+            .is_some_and(|names| names.iter().any(|n| n == filename))
+    }
+}
+
+/// A permanent ID claimed by more than one record
+struct Duplicate {
+    id: String,
+    /// The record that keeps the ID: the one on the base ref, else the oldest
+    keeper: String,
+    /// Working tree records that need a new ID
+    newer: Vec<Record>,
+}
+
+/// Finalize all draft records, converting them to permanent IDs. IDs taken
+/// on the base ref and on local branches are never minted, and duplicate
+/// permanent IDs are reported, or renumbered with `rebase_ids`.
+pub fn run(docs_dir: &str, dry_run: bool, base: Option<&str>, rebase_ids: bool) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let decisions_path = docs_path.join("decisions");
 
@@ -21,6 +101,58 @@ pub fn run(docs_dir: &str, dry_run: bool) -> Result<()> {
     }
 
     let graph = Graph::load(docs_path)?;
+    let base = match base {
+        Some(base) => base.to_string(),
+        None => DgConfig::load(docs_path).unwrap_or_default().git.base,
+    };
+    let shared = SharedIds::load(docs_path, &base);
+    let duplicates = find_duplicates(&decisions_path, &shared)?;
+
+    // IDs that must not be minted, growing as new ones are assigned
+    let mut exclude = shared.taken.clone();
+    // Renames by path: a duplicated ID names two files
+    let mut renames: Vec<(PathBuf, String, String)> = Vec::new();
+
+    if !duplicates.is_empty() {
+        println!(
+            "Found {} duplicate permanent ID(s):",
+            duplicates.len().to_string().red()
+        );
+        for duplicate in &duplicates {
+            println!(
+                "  {} kept by {}",
+                duplicate.id.red(),
+                duplicate.keeper.dimmed()
+            );
+            for record in &duplicate.newer {
+                let name = file_name(&record.path);
+                if !rebase_ids {
+                    println!("    {} {}", "also used by".yellow(), name);
+                    continue;
+                }
+                let new_id = graph.next_id_excluding(record.record_type(), &exclude);
+                exclude.push(new_id.clone());
+                println!(
+                    "    {} {} → {}",
+                    if dry_run {
+                        "Would renumber:".yellow()
+                    } else {
+                        "Renumbering:".green()
+                    },
+                    name.dimmed(),
+                    new_id.green()
+                );
+                renames.push((record.path.clone(), duplicate.id.clone(), new_id));
+            }
+        }
+        if !rebase_ids {
+            println!(
+                "{}",
+                "Run 'dg finalize --rebase-ids' to renumber the newer records.".dimmed()
+            );
+        }
+        println!();
+    }
 
     // Find all draft records
     let draft_records: Vec<&Record> = graph
@@ -30,21 +162,20 @@ pub fn run(docs_dir: &str, dry_run: bool) -> Result<()> {
 
     if draft_records.is_empty() {
         println!("{}", "No draft records found.".dimmed());
-        return Ok(());
+    } else {
+        println!(
+            "Found {} draft record(s):",
+            draft_records.len().to_string().cyan()
+        );
     }
 
-    println!(
-        "Found {} draft record(s):",
-        draft_records.len().to_string().cyan()
-    );
-
     // Group by record type and assign new IDs
     let mut id_mapping: HashMap<String, String> = HashMap::new();
 
     for record in &draft_records {
         let rt = record.record_type();
-        let exclude: Vec<String> = id_mapping.values().cloned().collect();
         let new_id = graph.next_id_excluding(rt, &exclude);
+        exclude.push(new_id.clone());
         id_mapping.insert(record.id().to_string(), new_id.clone());
 
         if dry_run {
@@ -64,95 +195,218 @@ pub fn run(docs_dir: &str, dry_run: bool) -> Result<()> {
         }
     }
 
+    if id_mapping.is_empty() && renames.is_empty() {
+        return Ok(());
+    }
+
     if dry_run {
         println!("\n{}", "Dry run - no changes made.".yellow());
         return Ok(());
     }
 
+    // Renumber duplicates. Records that still hold the ID keep their
+    // references, and so do records on the base ref, since merged records
+    // meant the record that kept it.
+    for (path, old_id, new_id) in &renames {
+        let renamed = rename_record(path, old_id, new_id)?;
+        let mapping = HashMap::from([(old_id.clone(), new_id.clone())]);
+        let untouched = update_all_references(docs_path, &mapping, |other| {
+            let name = file_name(other);
+            other != renamed
+                && (id_from_filename(&name).as_ref() == Some(old_id) || shared.is_on_base(&name))
+        })?;
+        for other in untouched {
+            let name = file_name(&other);
+            if id_from_filename(&name).as_ref() == Some(old_id) {
+                continue;
+            }
+            println!(
+                "  {} {} mentions {}; check whether it meant {}",
+                "Review:".yellow(),
+                name,
+                old_id,
+                new_id
+            );
+        }
+    }
+
     // Apply renames
     for (old_id, new_id) in &id_mapping {
-        rename_record(docs_path, old_id, new_id)?;
+        let path = find_record_file(&decisions_path, old_id)?;
+        rename_record(&path, old_id, new_id)?;
     }
 
     // Update all references in all documents
-    update_all_references(docs_path, &id_mapping)?;
+    update_all_references(docs_path, &id_mapping, |_| false)?;
 
     // Reload and update index
     let graph = Graph::load(docs_path)?;
     let _ = graph.save_index();
 
-    println!(
-        "\n{} Finalized {} record(s)",
-        "OK".green().bold(),
-        id_mapping.len()
-    );
+    if !renames.is_empty() {
+        println!(
+            "\n{} Renumbered {} record(s)",
+            "OK".green().bold(),
+            renames.len()
+        );
+    }
+    if !id_mapping.is_empty() {
+        println!(
+            "\n{} Finalized {} record(s)",
+            "OK".green().bold(),
+            id_mapping.len()
+        );
+    }
 
     Ok(())
 }
 
-/// Rename a record file and update its ID in frontmatter
-fn rename_record(docs_path: &Path, old_id: &str, new_id: &str) -> Result<()> {
-    let decisions_path = docs_path.join("decisions");
+/// Permanent IDs used by more than one record file in the working tree, or
+/// minted both here and on the base ref since this branch forked from it
+fn find_duplicates(decisions_path: &Path, shared: &SharedIds) -> Result<Vec<Duplicate>> {
+    let mut by_id: BTreeMap<String, Vec<Record>> = BTreeMap::new();
+    for path in record_files(decisions_path)? {
+        if let Ok(record) = Record::parse(&path) {
+            if !is_draft_id(record.id()) {
+                by_id
+                    .entry(record.id().to_string())
+                    .or_default()
+                    .push(record);
+            }
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for (id, mut records) in by_id {
+        records.sort_by(|a, b| {
+            (a.frontmatter.created, file_name(&a.path))
+                .cmp(&(b.frontmatter.created, file_name(&b.path)))
+        });
+        let keeper = match records
+            .iter()
+            .position(|r| shared.is_on_base(&file_name(&r.path)))
+        {
+            Some(index) => file_name(&records.remove(index).path),
+            None => match shared.on_base.get(&id) {
+                // The base ref has its own record under this ID; if the ID
+                // predates the fork, the local file is the same record renamed
+                Some(names) if !shared.at_fork.contains(&id) => {
+                    format!("{} on {}", names[0], shared.base.as_deref().unwrap_or(""))
+                }
+                _ if records.len() > 1 => file_name(&records.remove(0).path),
+                _ => continue,
+            },
+        };
+        if !records.is_empty() {
+            duplicates.push(Duplicate {
+                id,
+                keeper,
+                newer: records,
+            });
+        }
+    }
+    Ok(duplicates)
+}
+
+/// The permanent ID at the start of a record filename, e.g. DEC-006
+fn id_from_filename(filename: &str) -> Option<String> {
+    static ID: OnceLock<Regex> = OnceLock::new();
+    ID.get_or_init(|| Regex::new(r"^([A-Z][A-Z0-9]*-\d+)(?:-|\.md$)").unwrap())
+        .captures(filename)
+        .map(|c| c[1].to_string())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Every record file under the decisions directory, including nested ones
+fn record_files(decisions_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(decisions_path).follow_links(true) {
+        let path = entry?.into_path();
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
 
-    // Find the file with the old ID
-    let old_file = find_record_file(&decisions_path, old_id)?;
-    let content = fs::read_to_string(&old_file)?;
+/// Rename a record file in place and update its ID in frontmatter,
+/// returning the new path
+fn rename_record(old_file: &Path, old_id: &str, new_id: &str) -> Result<PathBuf> {
+    let content = fs::read_to_string(old_file)?;
 
     // Update ID in frontmatter
-    let id_pattern = Regex::new(&format!(r"^id:\s*{}", regex::escape(old_id)))?;
+    let id_pattern = Regex::new(&format!(r"(?m)^id:\s*{}", regex::escape(old_id)))?;
     let new_content = id_pattern.replace(&content, format!("id: {}", new_id));
 
     // Generate new filename
     let old_filename = old_file.file_name().unwrap().to_string_lossy();
-    let new_filename = old_filename.replace(old_id, new_id);
-    let new_file = decisions_path.join(&*new_filename);
+    let new_filename = old_filename.replacen(old_id, new_id, 1);
+    let new_file = old_file.with_file_name(&*new_filename);
 
     // Write new file
     fs::write(&new_file, new_content.as_ref())?;
 
     // Remove old file if different
     if old_file != new_file {
-        fs::remove_file(&old_file)?;
+        fs::remove_file(old_file)?;
     }
 
-    Ok(())
+    Ok(new_file)
 }
 
 /// Find the record file by ID
 fn find_record_file(decisions_path: &Path, id: &str) -> Result<std::path::PathBuf> {
-    for entry in fs::read_dir(decisions_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
-            let filename = path.file_name().unwrap().to_string_lossy();
-            if filename.starts_with(id) {
-                return Ok(path);
-            }
-        }
-    }
-    Err(anyhow!("Record file not found for ID: {}", id))
+    record_files(decisions_path)?
+        .into_iter()
+        .find(|path| file_name(path).starts_with(id))
+        .ok_or_else(|| anyhow!("Record file not found for ID: {}", id))
 }
 
-/// Update all references to old IDs in all documents
-fn update_all_references(docs_path: &Path, id_mapping: &HashMap<String, String>) -> Result<()> {
-    let decisions_path = docs_path.join("decisions");
-
-    for entry in fs::read_dir(&decisions_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
-            let content = fs::read_to_string(&path)?;
-            let mut new_content = content.clone();
+/// Update all references to old IDs in all documents except those `keep`
+/// selects, returning the kept documents that mention an old ID. IDs match
+/// whole words only, so DEC-006 leaves DEC-0061 alone.
+fn update_all_references(
+    docs_path: &Path,
+    id_mapping: &HashMap<String, String>,
+    keep: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>> {
+    let patterns: Vec<(Regex, &String)> = id_mapping
+        .iter()
+        .map(|(old_id, new_id)| {
+            Ok((
+                Regex::new(&format!(r"\b{}\b", regex::escape(old_id)))?,
+                new_id,
+            ))
+        })
+        .collect::<Result<_>>()?;
 
-            for (old_id, new_id) in id_mapping {
-                new_content = new_content.replace(old_id, new_id);
+    let mut kept = Vec::new();
+    for path in record_files(&docs_path.join("decisions"))? {
+        let content = fs::read_to_string(&path)?;
+        if keep(&path) {
+            if patterns.iter().any(|(re, _)| re.is_match(&content)) {
+                kept.push(path);
             }
+            continue;
+        }
 
-            if new_content != content {
-                fs::write(&path, new_content)?;
-            }
+        let mut new_content = content.clone();
+        for (re, new_id) in &patterns {
+            new_content = re
+                .replace_all(&new_content, regex::NoExpand(new_id))
+                .into_owned();
+        }
+
+        if new_content != content {
+            fs::write(&path, new_content)?;
         }
     }
 
-    Ok(())
+    Ok(kept)
 }
@@ -1,12 +1,24 @@
-use crate::models::Record;
+use crate::models::{frontmatter, FrontmatterFormat, Record};
 use anyhow::{anyhow, Result};
 use colored::Colorize;
-use regex::Regex;
 use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
-pub fn run(docs_dir: &str, check: bool, files: Option<Vec<String>>, quiet: bool) -> Result<()> {
+pub fn run(
+    docs_dir: &str,
+    check: bool,
+    convert: Option<String>,
+    files: Option<Vec<String>>,
+    quiet: bool,
+) -> Result<()> {
+    let convert =
+        match convert {
+            Some(name) => Some(FrontmatterFormat::from_str(&name).ok_or_else(|| {
+                anyhow!("Unknown frontmatter format: {} (use toml or yaml)", name)
+            })?),
+            None => None,
+        };
     let docs_path = Path::new(docs_dir);
     let decisions_dir = docs_path.join("decisions");
 
@@ -38,13 +50,15 @@ pub fn run(docs_dir: &str, check: bool, files: Option<Vec<String>>, quiet: bool)
 
         total += 1;
 
-        match format_file(&path, check) {
+        match format_file(&path, check, convert) {
             Ok(was_formatted) => {
                 if was_formatted {
                     formatted += 1;
                     if !quiet {
                         if check {
                             println!("{} {}", "NEEDS FMT".yellow(), path.display());
+                        } else if let Some(format) = convert {
+                            println!("{} {} ({})", "Converted".green(), path.display(), format);
                         } else {
                             println!("{} {}", "Formatted".green(), path.display());
                         }
@@ -92,9 +106,9 @@ pub fn run(docs_dir: &str, check: bool, files: Option<Vec<String>>, quiet: bool)
     Ok(())
 }
 
-fn format_file(path: &Path, check_only: bool) -> Result<bool> {
+fn format_file(path: &Path, check_only: bool, convert: Option<FrontmatterFormat>) -> Result<bool> {
     let content = fs::read_to_string(path)?;
-    let formatted = format_markdown(&content)?;
+    let formatted = format_markdown(&content, convert)?;
 
     if content == formatted {
         return Ok(false);
@@ -107,34 +121,31 @@ fn format_file(path: &Path, check_only: bool) -> Result<bool> {
     Ok(true)
 }
 
-fn format_markdown(content: &str) -> Result<String> {
-    // Parse frontmatter and body
-    let re = Regex::new(r"(?s)^---\n(.*?)\n---\n(.*)$")?;
-    let caps = re
-        .captures(content)
-        .ok_or_else(|| anyhow!("Invalid frontmatter format"))?;
-
-    // Safe: regex pattern guarantees groups 1 and 2 exist when captures succeeds
-    let yaml_str = caps
-        .get(1)
-        .ok_or_else(|| anyhow!("Missing YAML frontmatter"))?
-        .as_str();
-    let body = caps
-        .get(2)
-        .ok_or_else(|| anyhow!("Missing content body"))?
-        .as_str();
-
-    // Parse and reserialize YAML for consistent formatting
-    let record: Result<serde_yaml::Value, _> = serde_yaml::from_str(yaml_str);
-    let formatted_yaml = match record {
-        Ok(value) => format_yaml(&value)?,
-        Err(_) => yaml_str.to_string(), // Keep original if parse fails
+/// Format a record file, keeping its frontmatter syntax unless `convert` asks
+/// for another one
+fn format_markdown(content: &str, convert: Option<FrontmatterFormat>) -> Result<String> {
+    let (format, raw, body) =
+        frontmatter::split(content).map_err(|_| anyhow!("Invalid frontmatter format"))?;
+    let target = convert.unwrap_or(format);
+
+    // Parse and reserialize for consistent formatting
+    let formatted = match frontmatter::parse_value(format, raw) {
+        Ok(value) => match target {
+            FrontmatterFormat::Yaml => format_yaml(&value)?,
+            FrontmatterFormat::Toml => frontmatter::to_toml(&value)?.trim_end().to_string(),
+        },
+        Err(e) if target != format => return Err(e),
+        Err(_) => raw.to_string(), // Keep original if parse fails
     };
 
     // Format body
     let formatted_body = format_body(body);
 
-    Ok(format!("---\n{}\n---\n{}", formatted_yaml, formatted_body))
+    let delimiter = target.delimiter();
+    Ok(format!(
+        "{}\n{}\n{}\n{}",
+        delimiter, formatted, delimiter, formatted_body
+    ))
 }
 
 fn format_yaml(value: &serde_yaml::Value) -> Result<String> {
@@ -272,7 +283,5 @@ fn format_body(body: &str) -> String {
 /// Format a record in-memory and return the formatted content
 #[allow(dead_code)]
 pub fn format_record(record: &Record) -> Result<String> {
-    let yaml = serde_yaml::to_string(&record.frontmatter)?;
-    let content = format!("---\n{}---\n{}", yaml, record.content);
-    format_markdown(&content)
+    format_markdown(&record.to_file_content()?, None)
 }
@@ -0,0 +1,92 @@
+use crate::commands::show::stale_note;
+use crate::models::code_refs::project_root;
+use crate::models::governance::Governance;
+use crate::models::Graph;
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, path: &str, format: &str) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+    let governance = Governance::load(docs_path)?;
+    let path = project_path(docs_path, path);
+    let rules = governance.rules_for(&path);
+
+    if format == "json" {
+        let records: Vec<_> = governance
+            .records_for(&path)
+            .into_iter()
+            .map(|id| {
+                let record = graph.get(id);
+                serde_json::json!({
+                    "id": id,
+                    "title": record.map(|r| r.title()),
+                    "status": record.map(|r| r.status().to_string()),
+                    "patterns": rules
+                        .iter()
+                        .filter(|r| r.records.iter().any(|r| r == id))
+                        .map(|r| r.pattern.as_str())
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "path": path,
+                "records": records,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if governance.is_empty() {
+        println!(
+            "No governance rules. Map paths to records under [governance] in dg.toml or in {}.",
+            docs_path.join("GOVERNANCE").display()
+        );
+        return Ok(());
+    }
+    if rules.is_empty() {
+        println!("No records govern {}.", path);
+        return Ok(());
+    }
+
+    println!("{} is governed by:\n", path.bold());
+    for rule in &rules {
+        println!(
+            "  {} {}",
+            rule.pattern.yellow(),
+            format!("({})", rule.source).dimmed()
+        );
+        for id in &rule.records {
+            match graph.get(id) {
+                Some(record) => {
+                    println!("    {} {} [{}]", id.cyan(), record.title(), record.status());
+                    if let Some(note) = stale_note(&graph, id) {
+                        println!("      {}", note.yellow());
+                    }
+                }
+                None => println!("    {} {}", id.cyan(), "[not found]".red()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A path relative to the project root that governance patterns use.
+/// Paths that exist are resolved from the current directory; anything else
+/// is taken as already relative to the root.
+pub(crate) fn project_path(docs_path: &Path, path: &str) -> String {
+    let root = project_root(docs_path);
+    std::fs::canonicalize(path)
+        .ok()
+        .and_then(|abs| {
+            abs.strip_prefix(&root)
+                .ok()
+                .map(|p| p.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| path.trim_start_matches("./").to_string())
+}
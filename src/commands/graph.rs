@@ -11,9 +11,10 @@ pub fn run(
     format: &str,
     render: Option<&str>,
     output: Option<&str>,
+    at: Option<&str>,
 ) -> Result<()> {
     let docs_path = Path::new(docs_dir);
-    let graph = Graph::load(docs_path)?;
+    let graph = Graph::load_as_of(docs_path, at)?;
 
     let subset: Option<HashSet<String>> = id.map(|id| graph.neighbors(id, depth));
 
@@ -58,7 +59,7 @@ pub fn run(
                 .collect();
 
             let edges: Vec<_> = graph
-                .edges
+                .edges()
                 .iter()
                 .filter(|e| {
                     subset
@@ -70,6 +71,9 @@ pub fn run(
                         "from": e.from,
                         "to": e.to,
                         "type": e.link_type,
+                        "note": e.meta.note,
+                        "since": e.meta.since,
+                        "strength": e.meta.strength,
                     })
                 })
                 .collect();
@@ -101,7 +105,7 @@ pub fn run(
 
 fn print_graph_summary(graph: &Graph) {
     let total_records = graph.records.len();
-    let total_edges = graph.edges.len();
+    let total_edges = graph.edges().len();
 
     // Header
     println!("{}", "═".repeat(60).dimmed());
@@ -142,7 +146,7 @@ fn print_graph_summary(graph: &Graph) {
             (r, out + inc, out, inc)
         })
         .collect();
-    connectivity.sort_by(|a, b| b.1.cmp(&a.1));
+    connectivity.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     println!("{}", "TOP CONNECTED".bold());
     for (record, total, out, inc) in connectivity.iter().take(5) {
@@ -186,7 +190,7 @@ fn print_graph_summary(graph: &Graph) {
             .or_default() += 1;
     }
     let mut type_vec: Vec<_> = type_counts.into_iter().collect();
-    type_vec.sort_by(|a, b| b.1.cmp(&a.1));
+    type_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     for (record_type, count) in type_vec {
         let bar_len = (count as f32 / total_records as f32 * 30.0) as usize;
@@ -199,11 +203,11 @@ fn print_graph_summary(graph: &Graph) {
     println!("{}", "LINK TYPES".bold());
     let mut link_counts: std::collections::HashMap<String, usize> =
         std::collections::HashMap::new();
-    for edge in &graph.edges {
+    for edge in graph.edges() {
         *link_counts.entry(edge.link_type.clone()).or_default() += 1;
     }
     let mut link_vec: Vec<_> = link_counts.into_iter().collect();
-    link_vec.sort_by(|a, b| b.1.cmp(&a.1));
+    link_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     for (link_type, count) in link_vec {
         println!("  {:15} {}", link_type.dimmed(), count);
@@ -1,3 +1,5 @@
+use crate::models::code_refs::{CodeIndex, IMPLEMENTED_IN};
+use crate::models::references::MENTIONS;
 use crate::models::Graph;
 use anyhow::{anyhow, Result};
 use colored::Colorize;
@@ -13,6 +15,17 @@ pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
         .ok_or_else(|| anyhow!("Record not found: {}", id))?;
 
     let paths = graph.trace_dependents(id);
+    let mut mentioned_in: Vec<&str> = graph
+        .incoming_edges(id)
+        .into_iter()
+        .filter(|e| e.link_type == MENTIONS)
+        .map(|e| e.from.as_str())
+        .collect();
+    mentioned_in.sort();
+
+    // Code referencing the record, as implemented_in pseudo-edges
+    let code = CodeIndex::scan(docs_path)?;
+    let implemented_in = code.files_for(id);
 
     match format {
         "json" => {
@@ -24,8 +37,11 @@ pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
                     serde_json::json!({
                         "path": p.nodes,
                         "link_types": p.link_types,
+                        "link_meta": p.link_meta,
                     })
                 }).collect::<Vec<_>>(),
+                "mentioned_in": mentioned_in,
+                IMPLEMENTED_IN: implemented_in,
             });
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
@@ -46,16 +62,44 @@ pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
 
             if paths.is_empty() {
                 println!("  No records depend on this (via depends_on links)");
-                return Ok(());
+            } else {
+                println!(
+                    "  {} records would be affected:\n",
+                    paths.len().to_string().red()
+                );
+
+                for path in &paths {
+                    print_path(&graph, path);
+                }
             }
 
-            println!(
-                "  {} records would be affected:\n",
-                paths.len().to_string().red()
-            );
+            if !mentioned_in.is_empty() {
+                println!("\n  {} in the body of:\n", "Mentioned".yellow());
+                for from in &mentioned_in {
+                    let title = graph.get(from).map(|r| r.title()).unwrap_or("");
+                    println!("  - {} {}", from.cyan(), title.dimmed());
+                }
+            }
 
-            for path in &paths {
-                print_path(&graph, path);
+            if !implemented_in.is_empty() {
+                println!(
+                    "\n  {} {} file(s) that may need attention:\n",
+                    "Implemented in".yellow(),
+                    implemented_in.len()
+                );
+                for file in &implemented_in {
+                    let lines: Vec<String> = code
+                        .for_record(id)
+                        .iter()
+                        .filter(|r| r.file == *file)
+                        .map(|r| r.line.to_string())
+                        .collect();
+                    println!(
+                        "  - {} {}",
+                        file,
+                        format!("line {}", lines.join(", ")).dimmed()
+                    );
+                }
             }
         }
     }
@@ -68,7 +112,17 @@ fn print_path(graph: &Graph, path: &crate::models::DependencyPath) {
     for (i, node_id) in path.nodes.iter().skip(1).enumerate() {
         let indent = "  ".repeat(i + 1);
         if let Some(record) = graph.get(node_id) {
-            println!("{}← {} {}", indent, node_id.cyan(), record.title().dimmed());
+            let summary = path.link_meta[i]
+                .summary()
+                .map(|s| format!("  [{}]", s))
+                .unwrap_or_default();
+            println!(
+                "{}← {} {}{}",
+                indent,
+                node_id.cyan(),
+                record.title().dimmed(),
+                summary.dimmed()
+            );
         }
     }
 }
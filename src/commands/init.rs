@@ -1,10 +1,12 @@
+use crate::commands::merge_driver;
 use crate::templates;
 use anyhow::Result;
 use colored::Colorize;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::Path;
 
-pub fn run(docs_dir: &str) -> Result<()> {
+pub fn run(docs_dir: &str, register_merge_driver: bool) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let decisions_path = docs_path.join("decisions");
     let templates_path = docs_path.join(".templates");
@@ -37,6 +39,25 @@ pub fn run(docs_dir: &str) -> Result<()> {
         fs::write(&gitkeep, "")?;
     }
 
+    // Offer the merge driver so concurrent link and tag edits merge cleanly
+    if merge_driver::in_git_repo(docs_path) && !merge_driver::is_registered(docs_path) {
+        let register = register_merge_driver
+            || (io::stdin().is_terminal() && {
+                print!(
+                    "\n{} Register the dg merge driver for records in this repository? [y/N] ",
+                    "?".cyan().bold()
+                );
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                input.trim().eq_ignore_ascii_case("y")
+            });
+        if register {
+            merge_driver::register(docs_path)?;
+            println!("{} merge driver in .gitattributes", "Registered".green());
+        }
+    }
+
     println!("\n{}", "Decision Graph initialized!".green().bold());
     println!(
         "Create your first record with: {} new decision \"Your Decision Title\"",
@@ -1,13 +1,22 @@
-use crate::models::Graph;
+use crate::models::{Graph, LinkMeta, LinkType};
 use anyhow::{anyhow, Result};
 use chrono::Local;
 use colored::Colorize;
 use std::path::Path;
 
-pub fn run(docs_dir: &str, from: &str, link_type: &str, to: &str) -> Result<()> {
+pub fn run(docs_dir: &str, from: &str, link_type: &str, to: &str, meta: LinkMeta) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let mut graph = Graph::load(docs_path)?;
 
+    let link_def = LinkType::lookup(link_type).ok_or_else(|| {
+        let known: Vec<_> = LinkType::all().into_iter().map(|t| t.name).collect();
+        anyhow!(
+            "Unknown link type: {} (known types: {})",
+            link_type,
+            known.join(", ")
+        )
+    })?;
+
     // Validate both records exist
     if graph.get(from).is_none() {
         return Err(anyhow!("Source record not found: {}", from));
@@ -18,26 +27,28 @@ pub fn run(docs_dir: &str, from: &str, link_type: &str, to: &str) -> Result<()>
 
     // Add the link
     {
-        let record = graph
+        let mut record = graph
             .get_mut(from)
             .ok_or_else(|| anyhow!("Source record not found: {}", from))?;
-        record.frontmatter.links.add_link(link_type, to)?;
+        record
+            .frontmatter
+            .links
+            .add_link_with_meta(link_type, to, meta.clone())?;
         record.frontmatter.updated = Local::now().date_naive();
         record.save()?;
     }
 
-    // Add inverse link for certain types
-    let inverse_type = match link_type {
-        "supersedes" => Some("superseded_by"),
-        "superseded_by" => Some("supersedes"),
-        _ => None,
-    };
+    // Add the declared inverse (or mirror a symmetric link) on the target
+    let inverse_type = link_def.inverse_name();
 
     if let Some(inv) = inverse_type {
-        let record = graph
+        let mut record = graph
             .get_mut(to)
             .ok_or_else(|| anyhow!("Target record not found: {}", to))?;
-        record.frontmatter.links.add_link(inv, from)?;
+        record
+            .frontmatter
+            .links
+            .add_link_with_meta(inv, from, meta)?;
         record.frontmatter.updated = Local::now().date_naive();
         record.save()?;
         println!(
@@ -1,8 +1,13 @@
-use crate::models::{validation, Graph, Record, ValidationError, ValidationOptions};
+use crate::commands::show;
+use crate::git::GitHistory;
+use crate::models::code_refs::CodeIndex;
+use crate::models::{validation, Graph, Record, Status, ValidationError, ValidationOptions};
 use crate::serve::config::DgConfig;
 use anyhow::Result;
 use colored::Colorize;
+use std::collections::HashMap;
 use std::path::Path;
+use std::process::Command;
 
 /// Semantic validation errors that become warnings in non-strict mode
 fn is_semantic_warning(error: &ValidationError) -> bool {
@@ -39,7 +44,14 @@ impl LintError {
     fn is_warning(&self) -> bool {
         matches!(
             self.error,
-            ValidationError::OrphanedRecord { .. } | ValidationError::PrincipleConflict { .. }
+            ValidationError::OrphanedRecord { .. }
+                | ValidationError::PrincipleConflict { .. }
+                | ValidationError::UnknownLinkType { .. }
+                | ValidationError::BrokenReference { .. }
+                | ValidationError::BrokenAnchor { .. }
+                | ValidationError::StaleLink { .. }
+                | ValidationError::UnknownCodeAnnotation { .. }
+                | ValidationError::StaleCodeAnnotation { .. }
         )
     }
 
@@ -81,20 +93,16 @@ pub fn run(
     };
 
     // Get validation config for semantic rules
-    let validation_config = config.validation_config().clone();
-    let has_semantic_rules = validation_config.adr.is_some()
-        || validation_config.incident.is_some()
-        || validation_config.process.is_some()
-        || validation_config.decision.is_some()
-        || validation_config.strategy.is_some()
-        || validation_config.policy.is_some()
-        || validation_config.customer.is_some()
-        || validation_config.opportunity.is_some()
-        || validation_config.hiring.is_some()
-        || validation_config.runbook.is_some()
-        || validation_config.meeting.is_some()
-        || validation_config.feedback.is_some()
-        || validation_config.legal.is_some();
+    let validation_config = config.validation_config();
+    let has_semantic_rules = validation_config.has_rules();
+
+    // Status changes since the branch forked from the base ref are checked
+    // against workflows
+    let committed_statuses = if config.workflows.is_empty() {
+        HashMap::new()
+    } else {
+        committed_statuses(docs_path, &config.git.base)
+    };
 
     let opts = if strict {
         ValidationOptions {
@@ -110,6 +118,8 @@ pub fn run(
             teams_config: teams_config.clone(),
             validation_config: Some(validation_config.clone()),
             check_semantic: has_semantic_rules,
+            workflows: config.workflows.clone(),
+            committed_statuses,
         }
     } else {
         ValidationOptions {
@@ -122,6 +132,8 @@ pub fn run(
             teams_config,
             validation_config: Some(validation_config),
             check_semantic: has_semantic_rules,
+            workflows: config.workflows.clone(),
+            committed_statuses,
             ..ValidationOptions::basic()
         }
     };
@@ -129,7 +141,9 @@ pub fn run(
     let mut lint_errors = if let Some(file_list) = files {
         lint_files(&graph, docs_path, &file_list, &opts)
     } else {
-        lint_all(&graph, &opts)
+        let mut errors = lint_all(&graph, &opts);
+        errors.extend(lint_code_annotations(&graph, docs_path));
+        errors
     };
 
     // Add config validation errors
@@ -154,7 +168,7 @@ pub fn run(
     };
 
     // Show warnings
-    if !warnings.is_empty() && (warn_orphans || check_principles || has_semantic_rules) && !quiet {
+    if !warnings.is_empty() && !quiet {
         println!("{} {} warnings:\n", "WARN".yellow().bold(), warnings.len());
         for warn in &warnings {
             println!("  {} {}", "⚠".yellow(), warn);
@@ -185,6 +199,48 @@ pub fn run(
     }
 }
 
+/// Statuses where this branch forked from the base ref, for records changed
+/// since, keyed by ID. Falls back to HEAD when the base ref doesn't resolve.
+/// Empty outside a git repository.
+fn committed_statuses(docs_path: &Path, base: &str) -> HashMap<String, Status> {
+    let mut statuses = HashMap::new();
+    let Ok(history) = GitHistory::new(docs_path) else {
+        return statuses;
+    };
+    let since = history
+        .resolve(base)
+        .and_then(|revision| history.merge_base("HEAD", &revision.commit))
+        .unwrap_or_else(|_| "HEAD".to_string());
+
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "--relative", &since, "--", "."])
+        .current_dir(docs_path)
+        .output();
+    let changed = match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).to_string(),
+        _ => return statuses,
+    };
+
+    for file in changed.lines().filter(|f| f.ends_with(".md")) {
+        let Ok(output) = Command::new("git")
+            .args(["show", &format!("{}:./{}", since, file)])
+            .current_dir(docs_path)
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        let content = String::from_utf8_lossy(&output.stdout);
+        if let Ok(record) = Record::parse_content(&content, docs_path.join(file)) {
+            statuses.insert(record.id().to_string(), record.status().clone());
+        }
+    }
+
+    statuses
+}
+
 fn lint_files(
     graph: &Graph,
     docs_path: &Path,
@@ -231,6 +287,34 @@ fn lint_all(graph: &Graph, opts: &ValidationOptions) -> Vec<LintError> {
         .collect()
 }
 
+/// `@decision ID` annotations in source files that name a missing record,
+/// or one that has been superseded or deprecated
+fn lint_code_annotations(graph: &Graph, docs_path: &Path) -> Vec<LintError> {
+    let Ok(index) = CodeIndex::scan(docs_path) else {
+        return Vec::new();
+    };
+    index
+        .annotations()
+        .filter_map(|r| {
+            let keyword = r.annotation.clone().unwrap_or_default();
+            let error = match graph.get(&r.id) {
+                None => ValidationError::UnknownCodeAnnotation {
+                    keyword,
+                    target: r.id.clone(),
+                },
+                Some(target) if graph.is_stale(&r.id) => ValidationError::StaleCodeAnnotation {
+                    keyword,
+                    target: r.id.clone(),
+                    target_status: target.status().to_string(),
+                    successors: show::stale_successors(graph, &r.id),
+                },
+                Some(_) => return None,
+            };
+            Some(LintError::new(&format!("{}:{}", r.file, r.line), error))
+        })
+        .collect()
+}
+
 /// Validate that new files being committed have proper links
 #[allow(dead_code)]
 pub fn lint_new_files(_graph: &Graph, new_files: &[String], _docs_path: &Path) -> Vec<LintError> {
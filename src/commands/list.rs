@@ -1,3 +1,4 @@
+use crate::models::query::Query;
 use crate::models::{Graph, RecordType, Status};
 use anyhow::Result;
 use colored::Colorize;
@@ -5,32 +6,34 @@ use std::path::Path;
 
 pub fn run(
     docs_dir: &str,
+    query: Option<&str>,
     type_filter: Option<String>,
     status_filter: Option<String>,
     tag_filter: Option<String>,
     format: &str,
+    at: Option<&str>,
 ) -> Result<()> {
     let docs_path = Path::new(docs_dir);
-    let graph = Graph::load(docs_path)?;
+    let mut query = Query::parse(query.unwrap_or(""))?;
+    if let Some(t) = type_filter {
+        query.and_filter("type", &t)?;
+    }
+    if let Some(s) = status_filter {
+        query.and_filter("status", &s)?;
+    }
+    if let Some(tag) = tag_filter {
+        query.and_filter("tag", &tag)?;
+    }
+
+    let graph = Graph::load_as_of(docs_path, at)?;
 
     // Check for open incidents and warn
     warn_open_incidents(&graph);
 
-    let type_filter = type_filter.and_then(|t| RecordType::from_str(&t));
-    let status_filter = status_filter.and_then(|s| Status::from_str(&s));
-
-    let mut records: Vec<_> = graph
-        .all_records()
-        .filter(|r| type_filter.as_ref().is_none_or(|t| r.record_type() == t))
-        .filter(|r| status_filter.as_ref().is_none_or(|s| r.status() == s))
-        .filter(|r| {
-            tag_filter.as_ref().is_none_or(|tag| {
-                r.frontmatter
-                    .tags
-                    .iter()
-                    .any(|t| t.to_lowercase().contains(&tag.to_lowercase()))
-            })
-        })
+    let mut records: Vec<_> = query
+        .run(&graph, false)
+        .into_iter()
+        .map(|hit| hit.record)
         .collect();
 
     // Sort by ID
@@ -0,0 +1,73 @@
+use crate::commands::diff::print_record_diff;
+use crate::models::history::RecordHistory;
+use crate::models::Graph;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, id: &str, patch: bool, format: &str) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+    let record = graph
+        .get(id)
+        .ok_or_else(|| anyhow!("Record not found: {}", id))?;
+    let history = RecordHistory::load(docs_path, record)?;
+
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "id": record.id(),
+                "versions": history.versions,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if history.versions.is_empty() {
+        println!("{} has no committed history yet.", record.id());
+        return Ok(());
+    }
+
+    for (index, version) in history.versions.iter().enumerate() {
+        println!(
+            "{} {} {}",
+            version.short().yellow(),
+            version.date.format("%Y-%m-%d").to_string().dimmed(),
+            version.author.cyan()
+        );
+        println!("    {}", version.message);
+
+        let previous = history.versions.get(index + 1);
+        if let Some(from) = version.renamed_from(previous) {
+            println!("      renamed from {}", from.dimmed());
+        }
+        match &version.changes {
+            Some(changes) => print_record_diff(changes),
+            None => match (&version.id, &version.status) {
+                (Some(id), Some(status)) => {
+                    println!("      {} as {} ({})", "created".green(), id, status);
+                }
+                _ => println!("      {}", "frontmatter could not be parsed".yellow()),
+            },
+        }
+
+        if patch && !version.patch.is_empty() {
+            println!();
+            for line in version.patch.lines() {
+                if line.starts_with('+') {
+                    println!("    {}", line.green());
+                } else if line.starts_with('-') {
+                    println!("    {}", line.red());
+                } else if line.starts_with("@@") {
+                    println!("    {}", line.cyan());
+                } else {
+                    println!("    {}", line);
+                }
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::models::merge::merge_records;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Name of the merge driver in git config and .gitattributes
+const DRIVER: &str = "dg";
+
+/// Merge the three versions git hands a merge driver, writing the result
+/// over our version. Exits with status 1 when conflicts remain.
+pub fn run(base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let read = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    let our_content = read(ours)?;
+
+    // The index is derived from the records, so keep ours until it is rebuilt
+    if serde_json::from_str::<serde_json::Value>(&our_content)
+        .is_ok_and(|index| index.get("records").is_some())
+    {
+        eprintln!(
+            "{} kept our .index.json; run 'dg reindex' once the merge is done",
+            "dg merge:".yellow()
+        );
+        return Ok(());
+    }
+
+    let result = merge_records(&read(base)?, &our_content, &read(theirs)?)?;
+    fs::write(ours, &result.content)
+        .with_context(|| format!("Failed to write {}", ours.display()))?;
+
+    if !result.is_clean() {
+        eprintln!(
+            "{} conflicting changes to {}",
+            "dg merge:".yellow(),
+            result.conflicts.join(", ")
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Run git in `dir` and return its trimmed output, or None if it failed
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether the docs directory is inside a git work tree
+pub fn in_git_repo(docs_path: &Path) -> bool {
+    git(docs_path, &["rev-parse", "--is-inside-work-tree"]).is_some_and(|out| out == "true")
+}
+
+/// Whether this clone already has the merge driver configured
+pub fn is_registered(docs_path: &Path) -> bool {
+    git(
+        docs_path,
+        &["config", "--get", &format!("merge.{}.driver", DRIVER)],
+    )
+    .is_some()
+}
+
+/// Configure the merge driver for this clone and route record files to it
+/// through .gitattributes at the repository root
+pub fn register(docs_path: &Path) -> Result<()> {
+    let root = git(docs_path, &["rev-parse", "--show-toplevel"]).context("Not a git repository")?;
+    // Git runs merge drivers from the repository root
+    let prefix = git(docs_path, &["rev-parse", "--show-prefix"]).unwrap_or_default();
+    let docs_rel = prefix.trim_end_matches('/');
+    let docs_arg = if docs_rel.is_empty() { "." } else { docs_rel };
+
+    let set = |key: &str, value: &str| -> Result<()> {
+        git(
+            docs_path,
+            &["config", &format!("merge.{}.{}", DRIVER, key), value],
+        )
+        .map(|_| ())
+        .with_context(|| format!("Failed to set git config merge.{}.{}", DRIVER, key))
+    };
+    set("name", "dg record merge")?;
+    set(
+        "driver",
+        &format!("dg -D '{}' merge-driver %O %A %B", docs_arg),
+    )?;
+
+    let attributes_path = Path::new(&root).join(".gitattributes");
+    let existing = fs::read_to_string(&attributes_path).unwrap_or_default();
+    let mut content = existing.clone();
+    for pattern in ["decisions/**/*.md", ".index.json"] {
+        let line = format!("{}{} merge={}", prefix, pattern, DRIVER);
+        if !content.lines().any(|l| l.trim() == line) {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&line);
+            content.push('\n');
+        }
+    }
+    if content != existing {
+        fs::write(&attributes_path, content)?;
+    }
+    Ok(())
+}
@@ -1,11 +1,14 @@
+pub mod blame;
 pub mod build;
 pub mod changelog;
+pub mod commits;
 pub mod context;
 pub mod diff;
 pub mod edit;
 pub mod export;
 pub mod finalize;
 pub mod fmt;
+pub mod governs;
 pub mod graph;
 pub mod history;
 pub mod impact;
@@ -13,11 +16,18 @@ pub mod init;
 pub mod link;
 pub mod lint;
 pub mod list;
+pub mod log;
+pub mod merge_driver;
 pub mod new;
+pub mod order;
 pub mod orphans;
+pub mod path;
 pub mod principles;
+pub mod query;
+pub mod refs;
 pub mod reindex;
 pub mod resolve;
+pub mod retarget;
 pub mod retype;
 pub mod search;
 pub mod serve;
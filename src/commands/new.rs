@@ -7,7 +7,7 @@ use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{self, IsTerminal, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Generate a draft ID with timestamp (for multi-player mode)
 pub fn draft_id(record_type: &RecordType) -> String {
@@ -16,6 +16,18 @@ pub fn draft_id(record_type: &RecordType) -> String {
     format!("{}-NEW-{}", prefix, timestamp)
 }
 
+/// A draft ID that is not in `taken`. Drafts minted within the same second
+/// are told apart by counting the timestamp up.
+pub fn unique_draft_id(record_type: &RecordType, taken: &HashSet<String>) -> String {
+    let mut id = draft_id(record_type);
+    while taken.contains(&id) {
+        let (prefix, stamp) = id.rsplit_once('-').unwrap_or((&id, "0"));
+        let next = stamp.parse::<u64>().unwrap_or(0) + 1;
+        id = format!("{}-{}", prefix, next);
+    }
+    id
+}
+
 /// Check if an ID is a draft ID
 pub fn is_draft_id(id: &str) -> bool {
     id.contains("-NEW-")
@@ -86,7 +98,6 @@ pub fn run(
 ) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let decisions_path = docs_path.join("decisions");
-    let templates_path = docs_path.join(".templates");
 
     if !decisions_path.exists() {
         return Err(anyhow!(
@@ -94,8 +105,14 @@ pub fn run(
         ));
     }
 
-    let rt = RecordType::from_str(record_type)
-        .ok_or_else(|| anyhow!("Unknown record type: {}", record_type))?;
+    let rt = RecordType::from_str(record_type).ok_or_else(|| {
+        let known: Vec<_> = RecordType::all().iter().map(|t| t.name()).collect();
+        anyhow!(
+            "Unknown record type: {} (known types: {})",
+            record_type,
+            known.join(", ")
+        )
+    })?;
 
     // Load graph to get next ID
     let graph = Graph::load(docs_path)?;
@@ -105,12 +122,43 @@ pub fn run(
         graph.next_id(&rt)
     };
 
+    let file_path = create_record(docs_path, &rt, &new_id, title, &vars)?;
+
+    println!("{} {}", "Created".green().bold(), file_path.display());
+    if draft {
+        println!(
+            "Draft ID: {} (use 'dg finalize' before merging)",
+            new_id.yellow()
+        );
+    } else {
+        println!("ID: {}", new_id.cyan());
+    }
+
+    // Reload and update index
+    let graph = Graph::load(docs_path)?;
+    let _ = graph.save_index();
+
+    Ok(())
+}
+
+/// Render a record of type `rt` from its template and write it to the
+/// decisions directory, returning the new file's path
+pub fn create_record(
+    docs_path: &Path,
+    rt: &RecordType,
+    new_id: &str,
+    title: &str,
+    vars: &[String],
+) -> Result<PathBuf> {
+    let decisions_path = docs_path.join("decisions");
+    let templates_path = docs_path.join(".templates");
+
     // Load template
     let template_path = templates_path.join(format!("{}.md", rt.template_name()));
     let template = if template_path.exists() {
         fs::read_to_string(&template_path)?
     } else {
-        default_template(&rt)
+        default_template(rt)
     };
 
     // Generate filename
@@ -124,7 +172,7 @@ pub fn run(
     let file_path = decisions_path.join(&filename);
 
     // Parse user-provided variables
-    let mut user_vars = parse_vars(&vars)?;
+    let mut user_vars = parse_vars(vars)?;
 
     // Prepare built-in variables
     let today = Local::now().format("%Y-%m-%d").to_string();
@@ -138,7 +186,7 @@ pub fn run(
     };
 
     // Extract number for non-draft IDs
-    let number_str = if draft {
+    let number_str = if is_draft_id(new_id) {
         "NEW".to_string()
     } else {
         new_id.split('-').nth(1).unwrap_or("001").to_string()
@@ -146,7 +194,7 @@ pub fn run(
 
     // Built-in variables (these take precedence for backwards compatibility)
     let mut builtin_vars: HashMap<String, String> = HashMap::new();
-    builtin_vars.insert("id".to_string(), new_id.clone());
+    builtin_vars.insert("id".to_string(), new_id.to_string());
     builtin_vars.insert("title".to_string(), yaml_title.clone());
     builtin_vars.insert("date".to_string(), today.clone());
     builtin_vars.insert("author".to_string(), author);
@@ -207,22 +255,7 @@ pub fn run(
     let rendered = tmpl.render(&context)?;
 
     fs::write(&file_path, rendered)?;
-
-    println!("{} {}", "Created".green().bold(), file_path.display());
-    if draft {
-        println!(
-            "Draft ID: {} (use 'dg finalize' before merging)",
-            new_id.yellow()
-        );
-    } else {
-        println!("ID: {}", new_id.cyan());
-    }
-
-    // Reload and update index
-    let graph = Graph::load(docs_path)?;
-    let _ = graph.save_index();
-
-    Ok(())
+    Ok(file_path)
 }
 
 fn default_template(rt: &RecordType) -> String {
@@ -232,7 +265,7 @@ fn default_template(rt: &RecordType) -> String {
 type: {}
 id: {}-{{{{NUMBER}}}}
 title: {{{{TITLE}}}}
-status: proposed
+status: {}
 created: {{{{DATE}}}}
 updated: {{{{DATE}}}}
 authors: []
@@ -256,8 +289,9 @@ links:
 ## Consequences
 
 "#,
-        rt.template_name(),
-        prefix
+        rt.name(),
+        prefix,
+        rt.default_status()
     )
 }
 
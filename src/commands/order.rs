@@ -0,0 +1,148 @@
+use crate::models::{Graph, LinkType};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::collections::HashSet;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, id: Option<&str>, link_types: &[String], format: &str) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+
+    let link_types: Vec<String> = if link_types.is_empty() {
+        LinkType::all()
+            .into_iter()
+            .filter(|t| t.acyclic)
+            .map(|t| t.name)
+            .collect()
+    } else {
+        link_types.to_vec()
+    };
+    let types: Vec<&str> = link_types.iter().map(|t| t.as_str()).collect();
+
+    // A record and everything it requires, or every record on such a link
+    let ids: HashSet<String> = match id {
+        Some(id) => {
+            let record = graph
+                .get(id)
+                .ok_or_else(|| anyhow!("Record not found: {}", id))?;
+            let mut ids = HashSet::from([record.id().to_string()]);
+            let mut stack = vec![record.id().to_string()];
+            while let Some(current) = stack.pop() {
+                for link_type in &types {
+                    for edge in graph.outgoing_edges_of_type(&current, link_type) {
+                        if graph.get(&edge.to).is_some() && ids.insert(edge.to.clone()) {
+                            stack.push(edge.to.clone());
+                        }
+                    }
+                }
+            }
+            ids
+        }
+        None => graph
+            .edges()
+            .iter()
+            .filter(|e| types.contains(&e.link_type.as_str()) && graph.get(&e.to).is_some())
+            .flat_map(|e| [e.from.clone(), e.to.clone()])
+            .collect(),
+    };
+
+    let layers = graph.topological_layers(&ids, &types).map_err(|cycle| {
+        anyhow!(
+            "cannot order records, they form a cycle: {}",
+            cycle.join(" → ")
+        )
+    })?;
+
+    // What each record waits for, within the ordered set
+    let after = |id: &str| -> Vec<String> {
+        let mut prereqs: Vec<String> = types
+            .iter()
+            .flat_map(|t| graph.outgoing_edges_of_type(id, t))
+            .filter(|e| e.to != id && ids.contains(&e.to))
+            .map(|e| e.to.clone())
+            .collect();
+        prereqs.sort();
+        prereqs.dedup();
+        prereqs
+    };
+
+    match format {
+        "json" => {
+            let output: Vec<_> = layers
+                .iter()
+                .map(|layer| {
+                    layer
+                        .iter()
+                        .filter_map(|id| graph.get(id))
+                        .map(|r| {
+                            serde_json::json!({
+                                "id": r.id(),
+                                "title": r.title(),
+                                "type": r.record_type().to_string(),
+                                "status": r.status().to_string(),
+                                "after": after(r.id()),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "link_types": link_types,
+                    "layers": output,
+                }))?
+            );
+        }
+        "ids" => {
+            for id in layers.iter().flatten() {
+                println!("{}", id);
+            }
+        }
+        _ => {
+            if layers.is_empty() {
+                println!(
+                    "{}",
+                    format!("No records are linked by {}.", link_types.join(", ")).yellow()
+                );
+                return Ok(());
+            }
+
+            println!(
+                "{} by {} (prerequisites first):\n",
+                "Order".green().bold(),
+                link_types.join(", ").cyan()
+            );
+            for (i, layer) in layers.iter().enumerate() {
+                let parallel = if layer.len() > 1 {
+                    format!(" ‖ {} in parallel", layer.len())
+                        .dimmed()
+                        .to_string()
+                } else {
+                    String::new()
+                };
+                println!("{}{}", format!("Step {}", i + 1).bold(), parallel);
+                for record in layer.iter().filter_map(|id| graph.get(id)) {
+                    let prereqs = after(record.id());
+                    let after_text = if prereqs.is_empty() {
+                        String::new()
+                    } else {
+                        format!("  after {}", prereqs.join(", "))
+                            .dimmed()
+                            .to_string()
+                    };
+                    println!(
+                        "  {} {} [{}]{}",
+                        record.id().cyan(),
+                        record.title(),
+                        record.status(),
+                        after_text
+                    );
+                }
+            }
+            println!("\n{} records in {} steps", ids.len(), layers.len());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::models::{Graph, RecordPath};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::Path;
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    docs_dir: &str,
+    from: &str,
+    to: &str,
+    link_types: &[String],
+    directed: bool,
+    all: bool,
+    max_len: usize,
+    format: &str,
+) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+
+    let from = graph
+        .get(from)
+        .ok_or_else(|| anyhow!("Record not found: {}", from))?
+        .id()
+        .to_string();
+    let to = graph
+        .get(to)
+        .ok_or_else(|| anyhow!("Record not found: {}", to))?
+        .id()
+        .to_string();
+    let types: Vec<&str> = link_types.iter().map(|t| t.as_str()).collect();
+
+    let paths = if all {
+        graph.simple_paths(&from, &to, &types, directed, max_len)
+    } else {
+        graph.shortest_paths(&from, &to, &types, directed)
+    };
+
+    match format {
+        "json" => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "from": from,
+                    "to": to,
+                    "paths": paths,
+                }))?
+            );
+        }
+        "dot" => print!("{}", graph.paths_to_dot(&paths)),
+        _ => {
+            if paths.is_empty() {
+                let within = if all {
+                    format!(" within {} links", max_len)
+                } else {
+                    String::new()
+                };
+                println!(
+                    "{}",
+                    format!("No path from {} to {}{}.", from, to, within).yellow()
+                );
+                return Ok(());
+            }
+
+            let kind = if all { "Paths" } else { "Shortest paths" };
+            println!(
+                "{} from {} to {}:\n",
+                kind.green().bold(),
+                from.cyan(),
+                to.cyan()
+            );
+            for path in &paths {
+                print_path(&graph, path);
+                println!();
+            }
+            println!(
+                "{} path{}",
+                paths.len(),
+                if paths.len() == 1 { "" } else { "s" }
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_path(graph: &Graph, path: &RecordPath) {
+    let title = |id: &str| {
+        graph
+            .get(id)
+            .map(|r| r.title().to_string())
+            .unwrap_or_default()
+    };
+
+    println!("  {} {}", path.nodes[0].cyan(), title(&path.nodes[0]));
+    for (link, id) in path.links.iter().zip(&path.nodes[1..]) {
+        // Links walked against their declared direction point back up
+        let arrow = if &link.to == id {
+            format!("─{}→", link.link_type)
+        } else {
+            format!("←{}─", link.link_type)
+        };
+        println!("    {}", arrow.dimmed());
+        println!("  {} {}", id.cyan(), title(id));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::models::pattern::{rows_to_json, PathQuery};
+use crate::models::Graph;
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, query: &str, format: &str) -> Result<()> {
+    let query = PathQuery::parse(query)?;
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+
+    let columns = query.columns();
+    let rows = query.run(&graph);
+
+    match format {
+        "json" => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&rows_to_json(&columns, &rows))?
+            );
+        }
+        "ids" => {
+            for row in &rows {
+                let ids: Vec<_> = row.iter().map(|r| r.id()).collect();
+                println!("{}", ids.join("\t"));
+            }
+        }
+        _ => {
+            if rows.is_empty() {
+                println!("{}", "No matches.".yellow());
+                return Ok(());
+            }
+
+            if let [column] = columns.as_slice() {
+                println!(
+                    "{:<12} {:<10} {:<12} {}",
+                    column.to_uppercase().bold(),
+                    "TYPE".bold(),
+                    "STATUS".bold(),
+                    "TITLE".bold()
+                );
+                println!("{}", "-".repeat(72));
+                for row in &rows {
+                    let record = row[0];
+                    println!(
+                        "{:<12} {:<10} {:<12} {}",
+                        record.id().cyan(),
+                        record.record_type().to_string(),
+                        record.status().to_string(),
+                        record.title()
+                    );
+                }
+            } else {
+                let header: Vec<_> = columns
+                    .iter()
+                    .map(|c| format!("{:<12}", c.to_uppercase()).bold().to_string())
+                    .collect();
+                println!("{}", header.join(" "));
+                println!("{}", "-".repeat(13 * columns.len()));
+                for row in &rows {
+                    let cells: Vec<_> = row
+                        .iter()
+                        .map(|r| format!("{:<12}", r.id()).cyan().to_string())
+                        .collect();
+                    println!("{}", cells.join(" "));
+                }
+            }
+
+            println!(
+                "\n{} row{}",
+                rows.len(),
+                if rows.len() == 1 { "" } else { "s" }
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::models::code_refs::CodeIndex;
+use crate::models::Graph;
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let graph = Graph::load(docs_path)?;
+    let index = CodeIndex::scan(docs_path)?;
+    let refs = index.for_record(id);
+
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "id": id,
+                "root": index.root,
+                "refs": refs,
+            }))?
+        );
+        return Ok(());
+    }
+
+    match graph.get(id) {
+        Some(record) => println!("{} {}", id.cyan().bold(), record.title().bold()),
+        None => println!("{} {}", id.cyan().bold(), "[not found]".yellow()),
+    }
+
+    if refs.is_empty() {
+        println!("\nNo code under {} mentions {}.", index.root.display(), id);
+        return Ok(());
+    }
+
+    let mut current_file = "";
+    for r in &refs {
+        if r.file != current_file {
+            println!("\n{}", r.file.yellow());
+            current_file = &r.file;
+        }
+        let content = if r.annotation.is_some() {
+            r.content.green()
+        } else {
+            r.content.normal()
+        };
+        println!("  {:>5}  {}", r.line.to_string().dimmed(), content);
+    }
+
+    let annotated = refs.iter().filter(|r| r.annotation.is_some()).count();
+    println!(
+        "\n{} reference(s) in {} file(s), {} annotated",
+        refs.len(),
+        index.files_for(id).len(),
+        annotated
+    );
+
+    Ok(())
+}
@@ -5,7 +5,7 @@ use std::path::Path;
 
 pub fn run(docs_dir: &str) -> Result<()> {
     let docs_path = Path::new(docs_dir);
-    let graph = Graph::load(docs_path)?;
+    let graph = Graph::rebuild(docs_path)?;
 
     graph.save_index()?;
 
@@ -15,7 +15,7 @@ pub fn run(docs_dir: &str) -> Result<()> {
         "Reindexed".green(),
         index_path.display(),
         graph.records.len(),
-        graph.edges.len()
+        graph.edges().len()
     );
 
     Ok(())
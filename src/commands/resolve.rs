@@ -1,5 +1,7 @@
+use crate::models::workflow::Actor;
 use crate::models::{Graph, RecordType, Status};
-use anyhow::{bail, Result};
+use crate::serve::config::DgConfig;
+use anyhow::{anyhow, bail, Result};
 use chrono::Local;
 use colored::Colorize;
 use std::path::Path;
@@ -7,8 +9,9 @@ use std::path::Path;
 pub fn run(docs_dir: &str, id: &str, note: Option<&str>) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let mut graph = Graph::load(docs_path)?;
+    let config = DgConfig::load(docs_path)?;
 
-    let record = match graph.get_mut(id) {
+    let mut record = match graph.get_mut(id) {
         Some(r) => r,
         None => bail!("Record not found: {}", id),
     };
@@ -29,6 +32,18 @@ pub fn run(docs_dir: &str, id: &str, note: Option<&str>) -> Result<()> {
         return Ok(());
     }
 
+    // Check the workflow allows resolving from the current status
+    if let Some(wf) = config.workflow(record.record_type()) {
+        let actor = Actor::current(&config.users_config());
+        wf.check_transition(
+            record.record_type().name(),
+            record.status(),
+            &Status::Resolved,
+            Some(&actor),
+        )
+        .map_err(|e| anyhow!("{}: {}", id, e))?;
+    }
+
     // Update status
     record.frontmatter.status = Status::Resolved;
 
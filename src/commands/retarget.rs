@@ -0,0 +1,149 @@
+use crate::commands::show::stale_successors;
+use crate::models::{Graph, LinkType, Links};
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use colored::Colorize;
+use std::path::Path;
+
+/// A link to move from a stale record to the one that replaced it
+struct Move {
+    from: String,
+    link_type: String,
+    old: String,
+    new: String,
+}
+
+pub fn run(docs_dir: &str, id: Option<&str>, link_types: &[String], dry_run: bool) -> Result<()> {
+    let docs_path = Path::new(docs_dir);
+    let mut graph = Graph::load(docs_path)?;
+
+    let link_types: Vec<String> = if link_types.is_empty() {
+        Links::BUILDS_ON_TYPES
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    } else {
+        link_types.to_vec()
+    };
+
+    let mut ids: Vec<String> = match id {
+        Some(id) => vec![graph
+            .get(id)
+            .ok_or_else(|| anyhow!("Record not found: {}", id))?
+            .id()
+            .to_string()],
+        None => graph
+            .all_records()
+            .filter(|r| !r.status().is_inactive())
+            .map(|r| r.id().to_string())
+            .collect(),
+    };
+    ids.sort();
+
+    let mut moves = Vec::new();
+    for id in &ids {
+        let record = graph.get(id).unwrap();
+        for (link_type, target) in record.frontmatter.links.all_links() {
+            if !link_types.iter().any(|t| t == link_type) || !graph.is_stale(target) {
+                continue;
+            }
+            match stale_successors(&graph, target).as_slice() {
+                [new] => moves.push(Move {
+                    from: id.clone(),
+                    link_type: link_type.to_string(),
+                    old: target.to_string(),
+                    new: new.clone(),
+                }),
+                [] => println!(
+                    "  {} {} {} {}: {}",
+                    "Skipped".yellow(),
+                    id.cyan(),
+                    link_type,
+                    target.cyan(),
+                    "nothing supersedes it".dimmed()
+                ),
+                several => println!(
+                    "  {} {} {} {}: {}",
+                    "Skipped".yellow(),
+                    id.cyan(),
+                    link_type,
+                    target.cyan(),
+                    format!(
+                        "superseded by {}, pick one with 'dg link'",
+                        several.join(", ")
+                    )
+                    .dimmed()
+                ),
+            }
+        }
+    }
+
+    if moves.is_empty() {
+        println!("{}", "No links to retarget.".green());
+        return Ok(());
+    }
+
+    let today = Local::now().date_naive();
+    for m in &moves {
+        let verb = if dry_run {
+            "Would retarget"
+        } else {
+            "Retargeted"
+        };
+        println!(
+            "  {} {} {} {} → {}",
+            verb.green(),
+            m.from.cyan(),
+            m.link_type,
+            m.old,
+            m.new.cyan()
+        );
+        if dry_run {
+            continue;
+        }
+
+        {
+            let mut record = graph
+                .get_mut(&m.from)
+                .ok_or_else(|| anyhow!("Record not found: {}", m.from))?;
+            let links = &mut record.frontmatter.links;
+            let meta = links
+                .all_targets()
+                .into_iter()
+                .find(|(t, target)| *t == m.link_type && target.id == m.old)
+                .map(|(_, target)| target.meta.clone())
+                .unwrap_or_default();
+            links.remove_link(&m.link_type, &m.old)?;
+            links.add_link_with_meta(&m.link_type, &m.new, meta)?;
+            record.frontmatter.updated = today;
+            record.save()?;
+        }
+
+        // Move the declared inverse along with the link
+        let inverse = LinkType::lookup(&m.link_type)
+            .and_then(|t| t.inverse_name().map(|name| name.to_string()));
+        if let Some(inverse) = inverse {
+            for (target, add) in [(&m.old, false), (&m.new, true)] {
+                let mut record = graph
+                    .get_mut(target)
+                    .ok_or_else(|| anyhow!("Record not found: {}", target))?;
+                if add {
+                    record.frontmatter.links.add_link(&inverse, &m.from)?;
+                } else {
+                    record.frontmatter.links.remove_link(&inverse, &m.from)?;
+                }
+                record.frontmatter.updated = today;
+                record.save()?;
+            }
+        }
+    }
+
+    if dry_run {
+        println!("\n{} links would move (dry run)", moves.len());
+    } else {
+        let _ = graph.save_index();
+        println!("\n{} links moved", moves.len());
+    }
+
+    Ok(())
+}
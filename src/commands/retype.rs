@@ -1,3 +1,4 @@
+use crate::models::code_refs::CodeIndex;
 use crate::models::{Graph, Record, RecordType};
 use anyhow::{anyhow, Result};
 use colored::Colorize;
@@ -6,7 +7,6 @@ use std::collections::HashMap;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 /// Change a record's type while preserving chronological order
 pub fn run(docs_dir: &str, record_id: &str, new_type: &str, force: bool) -> Result<()> {
@@ -260,101 +260,25 @@ fn find_code_mentions(
     docs_path: &Path,
     id_mapping: &HashMap<String, String>,
 ) -> Result<Vec<CodeMention>> {
-    let mut mentions = Vec::new();
-
-    // Find project root
-    let project_root = find_project_root(docs_path)
-        .unwrap_or_else(|| docs_path.parent().unwrap_or(docs_path).to_path_buf());
-
-    // Build regex pattern for all IDs we're renaming
-    let ids: Vec<&str> = id_mapping.keys().map(|s| s.as_str()).collect();
-    if ids.is_empty() {
-        return Ok(mentions);
+    if id_mapping.is_empty() {
+        return Ok(Vec::new());
     }
 
-    let pattern = format!(
-        r"\b({})\b",
-        ids.iter()
-            .map(|id| regex::escape(id))
-            .collect::<Vec<_>>()
-            .join("|")
-    );
-    let re = Regex::new(&pattern)?;
-
-    let code_extensions = [
-        "rs", "py", "js", "ts", "tsx", "jsx", "rb", "go", "java", "kt", "swift", "c", "cpp", "h",
-        "hpp", "cs", "php", "sh", "bash", "zsh", "yaml", "yml", "toml", "json", "sql", "ex", "exs",
-        "erl", "hs", "ml", "scala", "clj",
-    ];
-
-    for entry in WalkDir::new(&project_root)
+    let index = CodeIndex::scan_matching(docs_path, |id| id_mapping.contains_key(id))?;
+    Ok(index
+        .refs
         .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-
-        if !path.is_file() {
-            continue;
-        }
-
-        // Skip markdown files in decisions (handled separately)
-        if path.to_string_lossy().contains("decisions")
-            && path.extension().is_some_and(|e| e == "md")
-        {
-            continue;
-        }
-
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !code_extensions.contains(&ext) {
-            continue;
-        }
-
-        if let Ok(content) = fs::read_to_string(path) {
-            for (line_num, line) in content.lines().enumerate() {
-                for cap in re.captures_iter(line) {
-                    let matched_id = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-                    if let Some(new_id) = id_mapping.get(matched_id) {
-                        mentions.push(CodeMention {
-                            file: path.to_path_buf(),
-                            line: line_num + 1,
-                            content: line.to_string(),
-                            old_id: matched_id.to_string(),
-                            new_id: new_id.clone(),
-                        });
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(mentions)
-}
-
-fn find_project_root(start: &Path) -> Option<PathBuf> {
-    let mut current = start.to_path_buf();
-    loop {
-        if current.join(".git").exists()
-            || current.join("Cargo.toml").exists()
-            || current.join("package.json").exists()
-        {
-            return Some(current);
-        }
-        if !current.pop() {
-            return None;
-        }
-    }
-}
-
-fn is_ignored(path: &Path) -> bool {
-    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-    name.starts_with('.')
-        || name == "node_modules"
-        || name == "target"
-        || name == "dist"
-        || name == "build"
-        || name == "vendor"
-        || name == "__pycache__"
+        .filter_map(|r| {
+            let new_id = id_mapping.get(&r.id)?.clone();
+            Some(CodeMention {
+                file: r.path,
+                line: r.line,
+                content: r.content,
+                old_id: r.id,
+                new_id,
+            })
+        })
+        .collect())
 }
 
 fn display_plan(plan: &ChangePlan) {
@@ -542,9 +466,19 @@ fn execute_plan(_docs_path: &Path, plan: &ChangePlan) -> Result<()> {
 }
 
 /// Update only the ID in frontmatter (not links or other references)
+/// (`id: X` in YAML frontmatter, `id = "X"` in TOML)
 fn update_record_id_only(content: &str, old_id: &str, new_id: &str) -> Result<String> {
-    let re = Regex::new(&format!(r"(?m)^id:\s*{}\s*$", regex::escape(old_id)))?;
-    Ok(re.replace(content, format!("id: {}", new_id)).to_string())
+    let re = id_field_regex(old_id)?;
+    Ok(re
+        .replace(content, format!("${{1}}{}${{2}}", new_id))
+        .to_string())
+}
+
+fn id_field_regex(id: &str) -> Result<Regex> {
+    Ok(Regex::new(&format!(
+        r#"(?m)^(id\s*[:=]\s*"?){}("?\s*)$"#,
+        regex::escape(id)
+    ))?)
 }
 
 /// Update type and ID in frontmatter only (not links or other references)
@@ -555,14 +489,11 @@ fn update_record_type_and_id_only(
     new_id: &str,
 ) -> Result<String> {
     // Replace type in frontmatter
-    let type_re = Regex::new(r"(?m)^type:\s*\w+\s*$")?;
-    let result = type_re.replace(content, format!("type: {}", new_type.template_name()));
+    let type_re = Regex::new(r#"(?m)^(type\s*[:=]\s*"?)\w+("?\s*)$"#)?;
+    let result = type_re.replace(content, format!("${{1}}{}${{2}}", new_type.name()));
 
     // Replace id in frontmatter
-    let id_re = Regex::new(&format!(r"(?m)^id:\s*{}\s*$", regex::escape(old_id)))?;
-    Ok(id_re
-        .replace(&result, format!("id: {}", new_id))
-        .to_string())
+    update_record_id_only(&result, old_id, new_id)
 }
 
 /// Replace IDs only in the links section (not the id: field)
@@ -571,6 +502,24 @@ fn replace_ids_in_links(content: &str, id_mapping: &HashMap<String, String>) ->
         return content.to_string();
     }
 
+    // TOML frontmatter keeps links in a [links] table that runs until the
+    // next table header or the closing +++
+    if let Some(links_start) = content.find("\n[links]") {
+        let (before_links, links_section) = content.split_at(links_start + 1);
+        let links_end = [links_section.find("\n["), links_section.find("\n+++")]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(links_section.len());
+        let (links_content, after_links) = links_section.split_at(links_end);
+        return format!(
+            "{}{}{}",
+            before_links,
+            replace_ids_in_content(links_content, id_mapping),
+            after_links
+        );
+    }
+
     // Find the links: section and only replace within it
     // This avoids accidentally changing the record's own ID in frontmatter
     if let Some(links_start) = content.find("\nlinks:") {
@@ -1,17 +1,68 @@
-use crate::models::{Graph, Status};
+use crate::git::trailers::TrailerCommit;
+use crate::git::GitHistory;
+use crate::models::{Graph, Links, Status};
 use anyhow::{anyhow, Result};
 use colored::Colorize;
 use std::path::Path;
 
-pub fn run(docs_dir: &str, id: &str, show_links: bool, as_json: bool) -> Result<()> {
+pub fn run(
+    docs_dir: &str,
+    id: &str,
+    show_links: bool,
+    as_json: bool,
+    at: Option<&str>,
+) -> Result<()> {
     let docs_path = Path::new(docs_dir);
-    let graph = Graph::load(docs_path)?;
+    let graph = Graph::load_as_of(docs_path, at)?;
 
     let record = graph
         .get(id)
         .ok_or_else(|| anyhow!("Record not found: {}", id))?;
 
+    let effective = graph.effective_records(record.id());
+    let effective = if effective == [record.id()] {
+        Vec::new()
+    } else {
+        effective
+    };
+
+    // Trailers are read from HEAD, so a past view of the graph leaves them out
+    let commits: Vec<TrailerCommit> = match at {
+        None => GitHistory::new(docs_path)
+            .and_then(|history| history.commits_for(record.id()))
+            .unwrap_or_default(),
+        Some(_) => Vec::new(),
+    };
+
     if as_json {
+        let stale_links: Vec<_> = record
+            .frontmatter
+            .links
+            .all_links()
+            .into_iter()
+            .filter(|(_, target)| graph.is_stale(target))
+            .map(|(link_type, target)| {
+                serde_json::json!({
+                    "link_type": link_type,
+                    "id": target,
+                    "status": graph.get(target).map(|r| r.status().to_string()),
+                    "effective": stale_successors(&graph, target),
+                })
+            })
+            .collect();
+        // Every built-in type, even when empty, plus types declared in dg.toml
+        let mut links = serde_json::Map::new();
+        for link_type in Links::BUILTIN_TYPES {
+            links.insert(link_type.to_string(), serde_json::json!([]));
+        }
+        for (link_type, target) in record.frontmatter.links.all_targets() {
+            if let serde_json::Value::Array(targets) = links
+                .entry(link_type)
+                .or_insert_with(|| serde_json::json!([]))
+            {
+                targets.push(serde_json::to_value(target)?);
+            }
+        }
         let json = serde_json::json!({
             "id": record.id(),
             "title": record.title(),
@@ -21,16 +72,10 @@ pub fn run(docs_dir: &str, id: &str, show_links: bool, as_json: bool) -> Result<
             "updated": record.frontmatter.updated.to_string(),
             "authors": record.frontmatter.authors,
             "tags": record.frontmatter.tags,
-            "links": {
-                "supersedes": record.frontmatter.links.supersedes,
-                "superseded_by": record.frontmatter.links.superseded_by,
-                "depends_on": record.frontmatter.links.depends_on,
-                "enables": record.frontmatter.links.enables,
-                "relates_to": record.frontmatter.links.relates_to,
-                "conflicts_with": record.frontmatter.links.conflicts_with,
-                "refines": record.frontmatter.links.refines,
-                "implements": record.frontmatter.links.implements,
-            },
+            "links": links,
+            "effective": effective,
+            "stale_links": stale_links,
+            "commits": commits,
             "path": record.path.to_string_lossy(),
             "content": record.content.trim(),
         });
@@ -81,6 +126,14 @@ pub fn run(docs_dir: &str, id: &str, show_links: bool, as_json: bool) -> Result<
         );
     }
 
+    if !effective.is_empty() {
+        println!(
+            "{}: {}",
+            "Now in effect".dimmed(),
+            effective.join(", ").cyan()
+        );
+    }
+
     if !record.frontmatter.tags.is_empty() {
         println!(
             "{}: {}",
@@ -96,20 +149,26 @@ pub fn run(docs_dir: &str, id: &str, show_links: bool, as_json: bool) -> Result<
     }
 
     // Links
-    let links = record.frontmatter.links.all_links();
+    let links = record.frontmatter.links.all_targets();
     if !links.is_empty() {
         println!("\n{}", "Links:".yellow());
         for (link_type, target) in &links {
             let target_title = graph
-                .get(target)
+                .get(&target.id)
                 .map(|r| r.title())
                 .unwrap_or("[not found]");
             println!(
                 "  {} {} ({})",
                 link_type.cyan(),
-                target,
+                target.id,
                 target_title.dimmed()
             );
+            if let Some(summary) = target.meta.summary() {
+                println!("      {}", summary.dimmed());
+            }
+            if let Some(note) = stale_note(&graph, &target.id) {
+                println!("      {}", note.yellow());
+            }
         }
     }
 
@@ -129,10 +188,25 @@ pub fn run(docs_dir: &str, id: &str, show_links: bool, as_json: bool) -> Result<
                     edge.from,
                     from_title.dimmed()
                 );
+                if let Some(summary) = edge.meta.summary() {
+                    println!("      {}", summary.dimmed());
+                }
             }
         }
     }
 
+    if !commits.is_empty() {
+        println!("\n{}", "Implemented by commits:".yellow());
+        for commit in &commits {
+            println!(
+                "  {} {} {}",
+                commit.short().cyan(),
+                commit.message,
+                format!("({}, {})", commit.author, commit.date.format("%Y-%m-%d")).dimmed()
+            );
+        }
+    }
+
     // Content preview
     let content = record.content.trim();
     if !content.is_empty() {
@@ -153,3 +227,26 @@ pub fn run(docs_dir: &str, id: &str, show_links: bool, as_json: bool) -> Result<
 
     Ok(())
 }
+
+/// Records now standing in for a stale link target, other than itself
+pub(crate) fn stale_successors(graph: &Graph, id: &str) -> Vec<String> {
+    graph
+        .effective_records(id)
+        .into_iter()
+        .filter(|s| s != id)
+        .collect()
+}
+
+/// "⚠ superseded → DEC-004" for links to records that no longer stand
+pub(crate) fn stale_note(graph: &Graph, id: &str) -> Option<String> {
+    if !graph.is_stale(id) {
+        return None;
+    }
+    let successors = stale_successors(graph, id);
+    let status = graph.get(id)?.status().to_string();
+    Some(if successors.is_empty() {
+        format!("⚠ {}", status)
+    } else {
+        format!("⚠ {} → {}", status, successors.join(", "))
+    })
+}
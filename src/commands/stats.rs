@@ -1,14 +1,52 @@
 use crate::commands::list::warn_open_incidents;
+use crate::models::centrality::Centrality;
 use crate::models::Graph;
 use anyhow::Result;
 use colored::Colorize;
 use std::path::Path;
 
-pub fn run(docs_dir: &str) -> Result<()> {
+pub fn run(docs_dir: &str, format: &str) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let graph = Graph::load(docs_path)?;
 
     let stats = graph.stats();
+    let centrality = Centrality::compute(&graph);
+
+    // Action items stats
+    let mut total_actions = 0;
+    let mut completed_actions = 0;
+    for record in graph.all_records() {
+        for (_, completed, _) in record.extract_action_items() {
+            total_actions += 1;
+            if completed {
+                completed_actions += 1;
+            }
+        }
+    }
+
+    if format == "json" {
+        let suggestions: Vec<&str> = centrality
+            .core_suggestions(&graph)
+            .iter()
+            .map(|r| r.id.as_str())
+            .collect();
+        let output = serde_json::json!({
+            "total_records": stats.total_records,
+            "total_edges": stats.total_edges,
+            "by_type": stats.by_type,
+            "by_status": stats.by_status,
+            "action_items": {
+                "open": total_actions - completed_actions,
+                "completed": completed_actions,
+            },
+            "centrality": centrality.records,
+            "articulation_points": centrality.articulation_points,
+            "components": centrality.components,
+            "core_suggestions": suggestions,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
     println!("{}", "Decision Graph Statistics".bold());
     println!("{}", "=".repeat(40));
@@ -53,7 +91,7 @@ pub fn run(docs_dir: &str) -> Result<()> {
             (id, out + inc)
         })
         .collect();
-    connectivity.sort_by(|a, b| b.1.cmp(&a.1));
+    connectivity.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     if !connectivity.is_empty() {
         println!("\n{}", "Most connected:".yellow());
@@ -65,17 +103,7 @@ pub fn run(docs_dir: &str) -> Result<()> {
         }
     }
 
-    // Action items stats
-    let mut total_actions = 0;
-    let mut completed_actions = 0;
-    for record in graph.all_records() {
-        for (_, completed, _) in record.extract_action_items() {
-            total_actions += 1;
-            if completed {
-                completed_actions += 1;
-            }
-        }
-    }
+    print_centrality(&graph, &centrality);
 
     if total_actions > 0 {
         let open_actions = total_actions - completed_actions;
@@ -92,3 +120,72 @@ pub fn run(docs_dir: &str) -> Result<()> {
 
     Ok(())
 }
+
+fn print_centrality(graph: &Graph, centrality: &Centrality) {
+    let title = |id: &str| graph.get(id).map(|r| r.title()).unwrap_or("");
+
+    let central: Vec<_> = centrality
+        .records
+        .iter()
+        .filter(|r| r.in_degree + r.out_degree > 0)
+        .take(5)
+        .collect();
+    if !central.is_empty() {
+        println!("\n{}", "Most central (PageRank):".yellow());
+        for r in central {
+            println!(
+                "  {} {} {}",
+                r.id.cyan(),
+                title(&r.id),
+                format!(
+                    "in {} / out {}, betweenness {:.2}, rank {:.3}",
+                    r.in_degree, r.out_degree, r.betweenness, r.pagerank
+                )
+                .dimmed()
+            );
+        }
+    }
+
+    if !centrality.articulation_points.is_empty() {
+        println!("\n{}", "Keystones:".yellow());
+        for id in centrality.articulation_points.iter().take(5) {
+            let disconnects = centrality.get(id).map_or(0, |r| r.disconnects);
+            println!(
+                "  {} {} {}",
+                id.cyan(),
+                title(id),
+                format!(
+                    "(removing it disconnects {} record{})",
+                    disconnects,
+                    if disconnects == 1 { "" } else { "s" }
+                )
+                .dimmed()
+            );
+        }
+    }
+
+    let isolated: Vec<&str> = centrality
+        .components
+        .iter()
+        .filter(|c| c.len() == 1)
+        .map(|c| c[0].as_str())
+        .collect();
+    if let Some(largest) = centrality.components.first() {
+        println!(
+            "\n{}: {} (largest has {} records, {} unlinked)",
+            "Components".cyan(),
+            centrality.components.len(),
+            largest.len(),
+            isolated.len()
+        );
+    }
+
+    let suggestions = centrality.core_suggestions(graph);
+    if !suggestions.is_empty() {
+        println!("\n{}", "Consider marking core:".yellow());
+        for r in suggestions.iter().take(5) {
+            println!("  {} {}", r.id.cyan(), title(&r.id));
+        }
+        println!("  {}", "Set `core: true` in their frontmatter".dimmed());
+    }
+}
@@ -1,24 +1,64 @@
+use crate::models::workflow::Actor;
 use crate::models::{Graph, Status};
+use crate::serve::config::DgConfig;
 use anyhow::{anyhow, Result};
 use chrono::Local;
 use colored::Colorize;
 use std::path::Path;
 
-pub fn run(docs_dir: &str, id: &str, new_status: &str) -> Result<()> {
+pub fn run(docs_dir: &str, id: &str, new_status: Option<&str>) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let mut graph = Graph::load(docs_path)?;
+    let config = DgConfig::load(docs_path)?;
+
+    let mut record = graph
+        .get_mut(id)
+        .ok_or_else(|| anyhow!("Record not found: {}", id))?;
+    let workflow = config.workflow(record.record_type());
+
+    // Without a new status, show the current one and where it can go next
+    let Some(new_status) = new_status else {
+        println!(
+            "{} status: {}",
+            id.cyan(),
+            record.status().to_string().yellow()
+        );
+        match workflow {
+            Some(wf) => {
+                let next = wf.next_states(record.status());
+                if next.is_empty() {
+                    println!("No legal next states");
+                } else {
+                    println!("Legal next states: {}", next.join(", ").green());
+                }
+            }
+            None => println!(
+                "No workflow configured for {} records; any status is allowed",
+                record.record_type().name()
+            ),
+        }
+        return Ok(());
+    };
 
     let status =
         Status::from_str(new_status).ok_or_else(|| anyhow!("Unknown status: {}", new_status))?;
 
-    let record = graph
-        .get_mut(id)
-        .ok_or_else(|| anyhow!("Record not found: {}", id))?;
+    if let Some(wf) = workflow {
+        let actor = Actor::current(&config.users_config());
+        wf.check_transition(
+            record.record_type().name(),
+            record.status(),
+            &status,
+            Some(&actor),
+        )
+        .map_err(|e| anyhow!("{}: {}", id, e))?;
+    }
 
     let old_status = record.status().to_string();
     record.frontmatter.status = status;
     record.frontmatter.updated = Local::now().date_naive();
     record.save()?;
+    drop(record);
 
     println!(
         "{} {} status: {} -> {}",
@@ -1,7 +1,21 @@
-use crate::models::Graph;
+//! Suggest records that git history implies but the graph lacks
+//!
+//! Each commit is scored by signals in its subject, body and diffstat:
+//! decision or architecture language, significant fixes, dependency
+//! manifest changes, new top-level directories and config or infra files.
+//! Commits that name a record in a `Decision:` or `Implements:` trailer are
+//! already covered and skipped. Related commits are clustered into one
+//! suggestion.
+
+use crate::commands::new::{create_record, unique_draft_id};
+use crate::git::trailers::trailer_ids;
+use crate::git::GitHistory;
+use crate::models::{Graph, Record, RecordType};
 use anyhow::Result;
 use colored::Colorize;
 use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::process::Command;
 
@@ -67,23 +81,62 @@ const INCIDENT_KEYWORDS: &[&str] = &[
     "incident", "revert", "reverted", "rollback",
 ];
 
-pub fn run(docs_dir: &str, since: Option<&str>, format: &str) -> Result<()> {
+/// Dependency manifests; lockfiles are left out since they follow these
+const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+    "requirements.txt",
+    "Pipfile",
+    "Gemfile",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    "mix.exs",
+];
+
+/// Directories whose files configure builds, deployments or infrastructure
+const INFRA_DIRS: &[&str] = &[
+    ".github/workflows",
+    "infra",
+    "terraform",
+    "deploy",
+    "k8s",
+    "kubernetes",
+    "helm",
+    "ansible",
+    "config",
+];
+
+/// Frontmatter field listing the commits a drafted record came from
+const SUGGESTED_FROM: &str = "suggested_from";
+
+/// Commits scoring below this are not suggested
+const MIN_SCORE: u32 = 3;
+
+/// Changed files listed in a generated draft
+const DRAFT_FILE_LIMIT: usize = 20;
+
+pub fn run(docs_dir: &str, since: Option<&str>, format: &str, create_drafts: bool) -> Result<()> {
     let docs_path = Path::new(docs_dir);
     let graph = Graph::load(docs_path)?;
 
-    // Get git commits
     let since_arg = since.unwrap_or("1 week ago");
-    let output = Command::new("git")
-        .args(["log", "--oneline", "--since", since_arg])
-        .output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("git log failed: {}", stderr);
+    let mut commits = read_commits(docs_path, since_arg)?;
+    if let Some(prefix) = docs_prefix(docs_path) {
+        mark_docs_files(&mut commits, &prefix);
     }
+    mark_new_directories(docs_path, &mut commits);
+    let suggestions = cluster(&commits, &graph);
 
-    let commits = String::from_utf8(output.stdout)?;
-    let suggestions = analyze_commits(&commits, &graph);
+    if create_drafts && !suggestions.is_empty() {
+        create_draft_records(docs_path, &graph, &suggestions)?;
+        if format != "json" {
+            println!();
+        }
+    }
 
     if suggestions.is_empty() {
         if format != "json" {
@@ -99,12 +152,22 @@ pub fn run(docs_dir: &str, since: Option<&str>, format: &str) -> Result<()> {
             let output: Vec<_> = suggestions
                 .iter()
                 .map(|s| {
+                    let lead = s.lead();
                     serde_json::json!({
-                        "commit": s.commit,
-                        "message": s.message,
-                        "suggested_type": s.suggested_type,
-                        "reason": s.reason,
+                        "commit": lead.short,
+                        "message": lead.subject,
+                        "suggested_type": s.suggested_type.label(),
+                        "reason": s.reason(),
                         "related": s.related,
+                        "score": s.score,
+                        "signals": s.signals,
+                        "commits": s.commits.iter().map(|c| serde_json::json!({
+                            "commit": c.short,
+                            "message": c.subject,
+                            "author": c.author,
+                            "date": c.date,
+                        })).collect::<Vec<_>>(),
+                        "files": s.files(),
                     })
                 })
                 .collect();
@@ -117,13 +180,20 @@ pub fn run(docs_dir: &str, since: Option<&str>, format: &str) -> Result<()> {
                 suggestions.len()
             );
 
-            for suggestion in suggestions {
-                println!("{} {}", suggestion.commit.cyan(), suggestion.message);
+            for suggestion in &suggestions {
+                println!(
+                    "{} {}",
+                    format!("[score {}]", suggestion.score).bold(),
+                    suggestion.lead().subject
+                );
+                for commit in &suggestion.commits {
+                    println!("  {} {}", commit.short.cyan(), commit.subject);
+                }
                 println!(
                     "  {} Create a {} because: {}",
                     "→".yellow(),
-                    suggestion.suggested_type.bold(),
-                    suggestion.reason
+                    suggestion.suggested_type.label().bold(),
+                    suggestion.reason()
                 );
                 if !suggestion.related.is_empty() {
                     println!(
@@ -140,68 +210,521 @@ pub fn run(docs_dir: &str, since: Option<&str>, format: &str) -> Result<()> {
     Ok(())
 }
 
-struct Suggestion {
-    commit: String,
-    message: String,
-    suggested_type: String,
-    reason: String,
+/// A file changed by a commit, with its diffstat
+#[derive(Debug, Clone)]
+struct FileStat {
+    path: String,
+    added: u32,
+    deleted: u32,
+    /// Lives under the docs directory, so says nothing about the code
+    in_docs: bool,
+}
+
+#[derive(Debug, Clone)]
+struct CommitInfo {
+    hash: String,
+    short: String,
+    author: String,
+    date: String,
+    subject: String,
+    body: String,
+    files: Vec<FileStat>,
+    /// Top-level directories this commit created
+    new_dirs: Vec<String>,
+    /// Names the record it implements in a trailer
+    covered: bool,
+    /// Has no parent; an initial import is not a decision
+    root: bool,
+}
+
+/// Something about a commit that hints at an unrecorded decision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Signal {
+    kind: &'static str,
+    detail: String,
+    weight: u32,
+}
+
+impl Signal {
+    fn new(kind: &'static str, detail: impl Into<String>, weight: u32) -> Self {
+        Signal {
+            kind,
+            detail: detail.into(),
+            weight,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let what = match self.kind {
+            "decision_language" => "decision language",
+            "architecture_language" => "architectural change",
+            "incident" => "significant fix or incident response",
+            "dependency_manifest" => "dependencies changed",
+            "new_directory" => "new top-level directory",
+            "config_infra" => "config or infra changed",
+            _ => "large change",
+        };
+        format!("{} ({})", what, self.detail)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SuggestedType {
+    Adr,
+    Decision,
+    Incident,
+}
+
+impl SuggestedType {
+    fn label(&self) -> &'static str {
+        match self {
+            SuggestedType::Adr => "ADR",
+            SuggestedType::Decision => "Decision",
+            SuggestedType::Incident => "Incident",
+        }
+    }
+
+    fn record_type(&self) -> RecordType {
+        match self {
+            SuggestedType::Adr => RecordType::Adr,
+            SuggestedType::Decision => RecordType::Decision,
+            SuggestedType::Incident => RecordType::Incident,
+        }
+    }
+}
+
+/// One commit's score and the signals behind it
+struct Scored<'a> {
+    commit: &'a CommitInfo,
+    signals: Vec<Signal>,
+    score: u32,
+    suggested_type: SuggestedType,
+    arch_words: BTreeSet<&'static str>,
+}
+
+/// Related commits that together suggest one record
+struct Suggestion<'a> {
+    /// Highest scoring first
+    commits: Vec<&'a CommitInfo>,
+    suggested_type: SuggestedType,
+    signals: Vec<Signal>,
+    score: u32,
     related: Vec<String>,
 }
 
-fn analyze_commits(commits: &str, graph: &Graph) -> Vec<Suggestion> {
-    let mut suggestions = Vec::new();
+impl Suggestion<'_> {
+    fn lead(&self) -> &CommitInfo {
+        self.commits[0]
+    }
 
-    for line in commits.lines() {
-        if line.is_empty() {
-            continue;
+    fn reason(&self) -> String {
+        self.signals
+            .iter()
+            .map(Signal::describe)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Changed files across the commits with their summed diffstat
+    fn files(&self) -> Vec<serde_json::Value> {
+        let mut totals: Vec<(String, u32, u32)> = Vec::new();
+        for file in self.commits.iter().flat_map(|c| &c.files) {
+            match totals.iter_mut().find(|(p, _, _)| *p == file.path) {
+                Some(total) => {
+                    total.1 += file.added;
+                    total.2 += file.deleted;
+                }
+                None => totals.push((file.path.clone(), file.added, file.deleted)),
+            }
         }
+        totals.sort();
+        totals
+            .into_iter()
+            .map(|(path, added, deleted)| {
+                serde_json::json!({ "path": path, "added": added, "deleted": deleted })
+            })
+            .collect()
+    }
+}
+
+/// Commits since `since`, newest first, with bodies and diffstats
+fn read_commits(docs_path: &Path, since: &str) -> Result<Vec<CommitInfo>> {
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--no-merges",
+            "--since",
+            since,
+            "--numstat",
+            "--format=%x1e%H%x1f%h%x1f%P%x1f%an%x1f%as%x1f%s%x1f%b%x1f%(trailers:only,unfold)%x1f",
+        ])
+        .current_dir(docs_path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git log failed: {}", stderr);
+    }
 
-        let parts: Vec<&str> = line.splitn(2, ' ').collect();
-        if parts.len() < 2 {
+    let log = String::from_utf8(output.stdout)?;
+    Ok(parse_log(&log))
+}
+
+fn parse_log(log: &str) -> Vec<CommitInfo> {
+    let mut commits = Vec::new();
+    for entry in log.split('\x1e') {
+        let parts: Vec<&str> = entry.splitn(9, '\x1f').collect();
+        if parts.len() < 9 {
             continue;
         }
+        let files = parts[8]
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let added = fields.next()?.parse().unwrap_or(0);
+                let deleted = fields.next()?.parse().unwrap_or(0);
+                Some(FileStat {
+                    path: renamed_path(fields.next()?),
+                    added,
+                    deleted,
+                    in_docs: false,
+                })
+            })
+            .collect();
+        commits.push(CommitInfo {
+            hash: parts[0].trim().to_string(),
+            short: parts[1].to_string(),
+            author: parts[3].to_string(),
+            date: parts[4].to_string(),
+            subject: parts[5].to_string(),
+            body: parts[6].trim().to_string(),
+            files,
+            new_dirs: Vec::new(),
+            covered: !trailer_ids(parts[7]).is_empty(),
+            root: parts[2].trim().is_empty(),
+        });
+    }
+    commits
+}
+
+/// The destination of a numstat rename such as `src/{old => new}/lib.rs`
+fn renamed_path(path: &str) -> String {
+    if !path.contains(" => ") {
+        return path.to_string();
+    }
+    let braces = Regex::new(r"\{[^}]* => ([^}]*)\}").unwrap();
+    let path = braces.replace_all(path, "$1").replace("//", "/");
+    match path.split_once(" => ") {
+        Some((_, to)) => to.to_string(),
+        None => path,
+    }
+}
+
+/// The docs directory relative to the repo root, unless it is the root
+fn docs_prefix(docs_path: &Path) -> Option<String> {
+    let history = GitHistory::new(docs_path).ok()?;
+    let prefix = history.repo_relative(docs_path)?;
+    (!prefix.is_empty()).then(|| format!("{}/", prefix.trim_end_matches('/')))
+}
+
+fn mark_docs_files(commits: &mut [CommitInfo], prefix: &str) {
+    for file in commits.iter_mut().flat_map(|c| &mut c.files) {
+        file.in_docs = file.path.starts_with(prefix);
+    }
+}
+
+/// Record which commits create a top-level directory, walking from the
+/// oldest commit and starting from the tree just before it. The directories
+/// of a root commit, or of an oldest commit whose parent can't be read
+/// (e.g. in a shallow clone), are taken as already there.
+fn mark_new_directories(docs_path: &Path, commits: &mut [CommitInfo]) {
+    let Some(oldest) = commits.last() else {
+        return;
+    };
+    let output = Command::new("git")
+        .args([
+            "ls-tree",
+            "--full-tree",
+            "--name-only",
+            &format!("{}^", oldest.short),
+        ])
+        .current_dir(docs_path)
+        .output();
+    let (mut existing, baseline): (HashSet<String>, bool) = match output {
+        Ok(o) if !oldest.root && o.status.success() => (
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect(),
+            true,
+        ),
+        _ => (HashSet::new(), false),
+    };
+
+    for (index, commit) in commits.iter_mut().rev().enumerate() {
+        let seeds = commit.root || (!baseline && index == 0);
+        for file in commit.files.iter().filter(|f| !f.in_docs) {
+            let Some((top, _)) = file.path.split_once('/') else {
+                continue;
+            };
+            if existing.insert(top.to_string()) && !seeds {
+                commit.new_dirs.push(top.to_string());
+            }
+        }
+    }
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn keywords_in(words: &HashSet<String>, keywords: &[&'static str]) -> Vec<&'static str> {
+    keywords
+        .iter()
+        .copied()
+        .filter(|k| words.contains(*k))
+        .collect()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn is_manifest(path: &str) -> bool {
+    MANIFESTS.contains(&file_name(path))
+}
+
+fn is_config_or_infra(path: &str) -> bool {
+    let name = file_name(path);
+    name.starts_with("Dockerfile")
+        || name.starts_with("docker-compose")
+        || name == "Jenkinsfile"
+        || name == "Procfile"
+        || name == ".gitlab-ci.yml"
+        || name.ends_with(".tf")
+        || name.ends_with(".tfvars")
+        || name.ends_with(".hcl")
+        || INFRA_DIRS
+            .iter()
+            .any(|dir| path.starts_with(&format!("{}/", dir)))
+}
+
+fn score_commit(commit: &CommitInfo) -> Scored<'_> {
+    let mut signals = Vec::new();
+    let subject = words(&commit.subject);
+    let body = words(&commit.body);
+
+    // Keywords in the subject count double
+    let mut language = |keywords: &[&'static str], kind: &'static str| {
+        let in_subject = keywords_in(&subject, keywords);
+        let in_body: Vec<_> = keywords_in(&body, keywords)
+            .into_iter()
+            .filter(|k| !in_subject.contains(k))
+            .collect();
+        let found: Vec<_> = in_subject.iter().chain(&in_body).copied().collect();
+        if !found.is_empty() {
+            let weight = if in_subject.is_empty() { 1 } else { 2 };
+            signals.push(Signal::new(kind, found.join(", "), weight));
+        }
+        found
+    };
+    language(DECISION_KEYWORDS, "decision_language");
+    let arch_words: BTreeSet<&'static str> =
+        language(ARCHITECTURE_KEYWORDS, "architecture_language")
+            .into_iter()
+            .collect();
+
+    let message = format!("{}\n{}", commit.subject, commit.body).to_lowercase();
+    let incident =
+        !keywords_in(&subject, INCIDENT_KEYWORDS).is_empty() && is_significant_fix(&message);
+    if incident {
+        signals.push(Signal::new("incident", commit.subject.clone(), 3));
+    }
 
-        let commit = parts[0];
-        let message = parts[1].to_lowercase();
-
-        // Check for decision keywords
-        let has_decision = DECISION_KEYWORDS.iter().any(|k| message.contains(k));
-        let has_arch = ARCHITECTURE_KEYWORDS.iter().any(|k| message.contains(k));
-        let has_incident = INCIDENT_KEYWORDS.iter().any(|k| message.contains(k));
-
-        // Find related records by searching the message
-        let related = find_related_records(&message, graph);
-
-        if has_decision && has_arch {
-            // Likely an ADR
-            suggestions.push(Suggestion {
-                commit: commit.to_string(),
-                message: parts[1].to_string(),
-                suggested_type: "ADR".to_string(),
-                reason: "architectural decision detected".to_string(),
-                related,
-            });
-        } else if has_decision && !has_incident {
-            // General decision
-            suggestions.push(Suggestion {
-                commit: commit.to_string(),
-                message: parts[1].to_string(),
-                suggested_type: "Decision".to_string(),
-                reason: "decision language detected".to_string(),
-                related,
-            });
-        } else if has_incident && is_significant_fix(&message) {
-            // Potential incident
-            suggestions.push(Suggestion {
-                commit: commit.to_string(),
-                message: parts[1].to_string(),
-                suggested_type: "Incident".to_string(),
-                reason: "significant fix or incident response".to_string(),
-                related,
-            });
+    let manifests: Vec<&str> = commit
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .filter(|p| is_manifest(p))
+        .collect();
+    if !manifests.is_empty() {
+        signals.push(Signal::new("dependency_manifest", manifests.join(", "), 3));
+    }
+    if !commit.new_dirs.is_empty() {
+        let dirs: Vec<String> = commit.new_dirs.iter().map(|d| format!("{}/", d)).collect();
+        signals.push(Signal::new("new_directory", dirs.join(", "), 3));
+    }
+    let infra: Vec<&str> = commit
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .filter(|p| is_config_or_infra(p))
+        .collect();
+    if !infra.is_empty() {
+        signals.push(Signal::new("config_infra", infra.join(", "), 2));
+    }
+
+    // Record edits are what dg already tracks, so only code counts here
+    let code: Vec<&FileStat> = commit.files.iter().filter(|f| !f.in_docs).collect();
+    let churn: u32 = code.iter().map(|f| f.added + f.deleted).sum();
+    let mut weight = match churn {
+        500.. => 2,
+        150.. => 1,
+        _ => 0,
+    };
+    if code.len() >= 20 {
+        weight += 1;
+    }
+    if weight > 0 {
+        signals.push(Signal::new(
+            "diffstat",
+            format!("{} lines in {} files", churn, code.len()),
+            weight,
+        ));
+    }
+
+    let structural = !arch_words.is_empty()
+        || signals.iter().any(|s| {
+            matches!(
+                s.kind,
+                "dependency_manifest" | "new_directory" | "config_infra"
+            )
+        });
+    let suggested_type = if incident {
+        SuggestedType::Incident
+    } else if structural {
+        SuggestedType::Adr
+    } else {
+        SuggestedType::Decision
+    };
+
+    Scored {
+        commit,
+        score: signals.iter().map(|s| s.weight).sum(),
+        signals,
+        suggested_type,
+        arch_words,
+    }
+}
+
+/// Top-level directory most of a commit's files live in
+fn primary_area(commit: &CommitInfo) -> Option<&str> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for file in &commit.files {
+        if let Some((top, _)) = file.path.split_once('/') {
+            *counts.entry(top).or_default() += 1;
         }
     }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(area, _)| area)
+}
 
+/// Whether two candidate commits are part of the same piece of work: one
+/// builds on a directory the other created, they change mostly the same
+/// files, or they talk about the same architecture in the same area
+fn related(a: &Scored, b: &Scored) -> bool {
+    let touches = |commit: &CommitInfo, dir: &str| {
+        commit
+            .files
+            .iter()
+            .any(|f| f.path.starts_with(&format!("{}/", dir)))
+    };
+    if a.commit.new_dirs.iter().any(|d| touches(b.commit, d))
+        || b.commit.new_dirs.iter().any(|d| touches(a.commit, d))
+    {
+        return true;
+    }
+
+    let files_a: HashSet<&str> = a.commit.files.iter().map(|f| f.path.as_str()).collect();
+    let files_b: HashSet<&str> = b.commit.files.iter().map(|f| f.path.as_str()).collect();
+    let shared = files_a.intersection(&files_b).count();
+    let all = files_a.union(&files_b).count();
+    if all > 0 && shared * 2 >= all {
+        return true;
+    }
+
+    primary_area(a.commit).is_some()
+        && primary_area(a.commit) == primary_area(b.commit)
+        && !a.arch_words.is_disjoint(&b.arch_words)
+}
+
+/// Score every commit, group related ones, and suggest each group led by
+/// a commit that scores at least `MIN_SCORE`, highest score first. Quieter
+/// follow-up commits ride along with the group they belong to.
+fn cluster<'a>(commits: &'a [CommitInfo], graph: &Graph) -> Vec<Suggestion<'a>> {
+    let scored: Vec<Scored> = commits
+        .iter()
+        // Covered and root commits still count when spotting new directories
+        .filter(|c| !c.covered && !c.root)
+        .map(score_commit)
+        .collect();
+
+    // Union-find over the commits
+    let mut parent: Vec<usize> = (0..scored.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+    for i in 0..scored.len() {
+        for j in i + 1..scored.len() {
+            if related(&scored[i], &scored[j]) {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[rj] = ri;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<&Scored>> = Vec::new();
+    let mut group_of: HashMap<usize, usize> = HashMap::new();
+    for (i, commit) in scored.iter().enumerate() {
+        let root = find(&mut parent, i);
+        let index = *group_of.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(commit);
+    }
+
+    let mut suggestions: Vec<Suggestion> = groups
+        .into_iter()
+        .filter(|group| group.iter().any(|s| s.score >= MIN_SCORE))
+        .map(|mut group| {
+            // Stable sort keeps newer commits first among equal scores
+            group.sort_by_key(|s| std::cmp::Reverse(s.score));
+            let mut signals: Vec<Signal> = Vec::new();
+            for signal in group.iter().flat_map(|s| &s.signals) {
+                match signals
+                    .iter_mut()
+                    .find(|s| s.kind == signal.kind && s.detail == signal.detail)
+                {
+                    Some(existing) => existing.weight = existing.weight.max(signal.weight),
+                    None => signals.push(signal.clone()),
+                }
+            }
+            let text: String = group
+                .iter()
+                .map(|s| format!("{}\n{}\n", s.commit.subject, s.commit.body))
+                .collect();
+            Suggestion {
+                // Each further commit in the cluster adds weight
+                score: group[0].score + group.len() as u32 - 1,
+                suggested_type: group[0].suggested_type,
+                commits: group.iter().map(|s| s.commit).collect(),
+                signals,
+                related: find_related_records(&text, graph),
+            }
+        })
+        .collect();
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.score));
     suggestions
 }
 
@@ -212,14 +735,14 @@ fn find_related_records(message: &str, graph: &Graph) -> Vec<String> {
     let id_re = Regex::new(r"\b([A-Z]{3}-\d{3})\b").unwrap();
     for cap in id_re.captures_iter(message) {
         if let Some(id) = cap.get(1) {
-            if graph.get(id.as_str()).is_some() {
+            if graph.get(id.as_str()).is_some() && !related.contains(&id.as_str().to_string()) {
                 related.push(id.as_str().to_string());
             }
         }
     }
 
     // Search for matching records
-    let search_results = graph.search(message, false);
+    let search_results = graph.search(&message.to_lowercase(), false);
     for record in search_results.iter().take(3) {
         if !related.contains(&record.id().to_string()) {
             related.push(record.id().to_string());
@@ -243,3 +766,240 @@ fn is_significant_fix(message: &str) -> bool {
     ];
     significant_indicators.iter().any(|i| message.contains(i))
 }
+
+/// Write a draft record per suggestion, with the commits and changed files
+/// filled into its Setting section. The commits are listed under
+/// `suggested_from` in the frontmatter, and a suggestion whose commits a
+/// record already lists there is not drafted again.
+fn create_draft_records(docs_path: &Path, graph: &Graph, suggestions: &[Suggestion]) -> Result<()> {
+    let mut taken: HashSet<String> = graph.all_records().map(|r| r.id().to_string()).collect();
+    let mut drafted: HashMap<String, String> = HashMap::new();
+    for record in graph.all_records() {
+        for hash in suggested_from(record) {
+            drafted.insert(hash, record.id().to_string());
+        }
+    }
+
+    for suggestion in suggestions {
+        if let Some(id) = suggestion.commits.iter().find_map(|c| drafted.get(&c.hash)) {
+            eprintln!(
+                "{} {} (already drafted as {})",
+                "Skipped".yellow(),
+                suggestion.lead().subject,
+                id
+            );
+            continue;
+        }
+
+        let rt = suggestion.suggested_type.record_type();
+        let id = unique_draft_id(&rt, &taken);
+        taken.insert(id.clone());
+
+        let title = suggestion.lead().subject.trim_end_matches('.');
+        let path = create_record(docs_path, &rt, &id, title, &[])?;
+        let mut record = Record::parse(&path)?;
+        let hashes: Vec<serde_yaml::Value> = suggestion
+            .commits
+            .iter()
+            .map(|c| serde_yaml::Value::String(c.hash.clone()))
+            .collect();
+        record.frontmatter.extra.insert(
+            SUGGESTED_FROM.to_string(),
+            serde_yaml::Value::Sequence(hashes),
+        );
+        record.content = prefill_setting(&record.content, &setting_text(suggestion));
+        record.save()?;
+        for commit in &suggestion.commits {
+            drafted.insert(commit.hash.clone(), id.clone());
+        }
+        eprintln!("{} {} ({})", "Created".green().bold(), path.display(), id);
+    }
+
+    let graph = Graph::load(docs_path)?;
+    let _ = graph.save_index();
+    Ok(())
+}
+
+/// Commit hashes a record lists under `suggested_from`
+fn suggested_from(record: &Record) -> Vec<String> {
+    match record.frontmatter.extra.get(SUGGESTED_FROM) {
+        Some(serde_yaml::Value::Sequence(hashes)) => hashes
+            .iter()
+            .filter_map(|h| h.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn setting_text(suggestion: &Suggestion) -> String {
+    let mut text = format!(
+        "Suggested by `dg suggest` from {} commit{}:\n\n",
+        suggestion.commits.len(),
+        if suggestion.commits.len() == 1 {
+            ""
+        } else {
+            "s"
+        }
+    );
+    for commit in &suggestion.commits {
+        text.push_str(&format!(
+            "- {} {} ({}, {})\n",
+            commit.short, commit.subject, commit.author, commit.date
+        ));
+    }
+    text.push_str(&format!("\nSignals: {}\n", suggestion.reason()));
+
+    let files = suggestion.files();
+    if !files.is_empty() {
+        text.push_str("\nChanged files:\n\n");
+        for file in files.iter().take(DRAFT_FILE_LIMIT) {
+            text.push_str(&format!(
+                "- `{}` (+{} -{})\n",
+                file["path"].as_str().unwrap_or_default(),
+                file["added"],
+                file["deleted"]
+            ));
+        }
+        if files.len() > DRAFT_FILE_LIMIT {
+            text.push_str(&format!("- and {} more\n", files.len() - DRAFT_FILE_LIMIT));
+        }
+    }
+    text
+}
+
+/// Put `text` under the record's Setting section, falling back to the
+/// Context or Summary section that other templates open with, or a new
+/// Setting section after the title
+fn prefill_setting(content: &str, text: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let heading = ["## Setting", "## Context", "## Summary"]
+        .iter()
+        .find_map(|h| lines.iter().position(|l| l.trim() == *h));
+
+    let mut out: Vec<String> = Vec::new();
+    match heading {
+        Some(index) => {
+            // Replace the section's placeholder comment and blank lines
+            let end = lines[index + 1..]
+                .iter()
+                .position(|l| l.starts_with('#'))
+                .map(|p| index + 1 + p)
+                .unwrap_or(lines.len());
+            let keep = lines[index + 1..end]
+                .iter()
+                .filter(|l| !l.trim().is_empty() && !l.trim().starts_with("<!--"));
+            out.extend(lines[..=index].iter().map(|l| l.to_string()));
+            out.push(text.trim_end().to_string());
+            out.extend(keep.map(|l| l.to_string()));
+            out.push(String::new());
+            out.extend(lines[end..].iter().map(|l| l.to_string()));
+        }
+        None => {
+            let title = lines
+                .iter()
+                .position(|l| l.starts_with("# "))
+                .unwrap_or(lines.len().saturating_sub(1));
+            out.extend(lines[..=title].iter().map(|l| l.to_string()));
+            out.push(String::new());
+            out.push("## Setting".to_string());
+            out.push(text.trim_end().to_string());
+            out.extend(lines[title + 1..].iter().map(|l| l.to_string()));
+        }
+    }
+    let mut result = out.join("\n");
+    result.push('\n');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str, body: &str, files: &[&str]) -> CommitInfo {
+        CommitInfo {
+            hash: "abc1234def".to_string(),
+            short: "abc1234".to_string(),
+            author: "Ann".to_string(),
+            date: "2026-10-01".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+            files: files
+                .iter()
+                .map(|p| FileStat {
+                    path: p.to_string(),
+                    added: 10,
+                    deleted: 2,
+                    in_docs: p.starts_with("docs/"),
+                })
+                .collect(),
+            new_dirs: Vec::new(),
+            covered: false,
+            root: false,
+        }
+    }
+
+    #[test]
+    fn test_score_commit_signals() {
+        let plain = commit("Add login button", "", &["src/ui/login.rs"]);
+        let plain = score_commit(&plain);
+        assert!(plain.score < MIN_SCORE);
+
+        let deps = commit(
+            "Bump things",
+            "We decided to switch the queue to NATS.",
+            &["Cargo.toml", "Cargo.lock", "deploy/nats.yaml"],
+        );
+        let deps = score_commit(&deps);
+        let kinds: Vec<_> = deps.signals.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "decision_language",
+                "architecture_language",
+                "dependency_manifest",
+                "config_infra"
+            ]
+        );
+        assert_eq!(deps.signals[2].detail, "Cargo.toml");
+        assert_eq!(deps.score, 1 + 1 + 3 + 2);
+        assert_eq!(deps.suggested_type, SuggestedType::Adr);
+
+        let incident = commit(
+            "Hotfix crash in production checkout",
+            "",
+            &["src/checkout.rs"],
+        );
+        let incident = score_commit(&incident);
+        assert_eq!(incident.suggested_type, SuggestedType::Incident);
+    }
+
+    #[test]
+    fn test_parse_log_reads_numstat_and_trailers() {
+        let log = "\x1eabc1234ff\x1fabc1234\x1fdef5678ff\x1fAnn\x1f2026-10-01\x1fMove parser\
+                   \x1fBody\x1f\x1f\n\n3\t1\tsrc/{old => new}/parser.rs\n-\t-\tlogo.png\n\
+                   \x1edef5678ff\x1fdef5678\x1f\x1fBob\x1f2026-10-02\x1fAdopt Kafka\x1f\
+                   \x1fDecision: ADR-001\n\x1f\n";
+        let commits = parse_log(log);
+        assert_eq!(commits.len(), 2);
+        assert!(!commits[0].covered && commits[1].covered);
+        assert!(!commits[0].root && commits[1].root);
+        assert_eq!(commits[0].hash, "abc1234ff");
+        assert_eq!(commits[0].files[0].path, "src/new/parser.rs");
+        assert_eq!(commits[0].files[0].added, 3);
+        assert_eq!(commits[0].files[1].added, 0);
+    }
+
+    #[test]
+    fn test_prefill_setting() {
+        let decision = "---\nid: X\n---\n\n# T\n\n## Setting\n<!-- Why now? -->\n\n## People\n";
+        assert_eq!(
+            prefill_setting(decision, "From 1 commit\n"),
+            "---\nid: X\n---\n\n# T\n\n## Setting\nFrom 1 commit\n\n## People\n"
+        );
+        let other = "# T\n\n## Options\n";
+        assert_eq!(
+            prefill_setting(other, "From 1 commit"),
+            "# T\n\n## Setting\nFrom 1 commit\n\n## Options\n"
+        );
+    }
+}
@@ -11,8 +11,8 @@ pub fn run(docs_dir: &str, limit: usize, sort_by: &str, format: &str) -> Result<
 
     // Sort by date
     match sort_by {
-        "created" => records.sort_by(|a, b| b.frontmatter.created.cmp(&a.frontmatter.created)),
-        _ => records.sort_by(|a, b| b.frontmatter.updated.cmp(&a.frontmatter.updated)),
+        "created" => records.sort_by_key(|r| std::cmp::Reverse(r.frontmatter.created)),
+        _ => records.sort_by_key(|r| std::cmp::Reverse(r.frontmatter.updated)),
     }
 
     // Limit
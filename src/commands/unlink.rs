@@ -1,4 +1,4 @@
-use crate::models::Graph;
+use crate::models::{Graph, LinkType};
 use anyhow::Result;
 use chrono::Local;
 use colored::Colorize;
@@ -10,7 +10,7 @@ pub fn run(docs_dir: &str, from: &str, link_type: &str, to: &str) -> Result<()>
 
     // Remove the link
     {
-        let record = match graph.get_mut(from) {
+        let mut record = match graph.get_mut(from) {
             Some(r) => r,
             None => {
                 eprintln!(
@@ -36,15 +36,12 @@ pub fn run(docs_dir: &str, from: &str, link_type: &str, to: &str) -> Result<()>
         record.save()?;
     }
 
-    // Remove inverse link for certain types
-    let inverse_type = match link_type {
-        "supersedes" => Some("superseded_by"),
-        "superseded_by" => Some("supersedes"),
-        _ => None,
-    };
+    // Remove the declared inverse (or mirrored symmetric link) from the target
+    let link_def = LinkType::lookup(link_type);
+    let inverse_type = link_def.as_ref().and_then(|t| t.inverse_name());
 
     if let Some(inv) = inverse_type {
-        if let Some(record) = graph.get_mut(to) {
+        if let Some(mut record) = graph.get_mut(to) {
             record.frontmatter.links.remove_link(inv, from)?;
             record.frontmatter.updated = Local::now().date_naive();
             record.save()?;
@@ -1,11 +1,12 @@
+use crate::commands::show::{stale_note, stale_successors};
 use crate::models::Graph;
 use anyhow::{anyhow, Result};
 use colored::Colorize;
 use std::path::Path;
 
-pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
+pub fn run(docs_dir: &str, id: &str, format: &str, at: Option<&str>) -> Result<()> {
     let docs_path = Path::new(docs_dir);
-    let graph = Graph::load(docs_path)?;
+    let graph = Graph::load_as_of(docs_path, at)?;
 
     // Check record exists
     graph
@@ -19,9 +20,18 @@ pub fn run(docs_dir: &str, id: &str, format: &str) -> Result<()> {
             let output: Vec<_> = paths
                 .iter()
                 .map(|p| {
+                    // Superseded records on the path, with their successors
+                    let stale: serde_json::Map<_, _> = p
+                        .nodes
+                        .iter()
+                        .filter(|id| graph.is_stale(id))
+                        .map(|id| (id.clone(), serde_json::json!(stale_successors(&graph, id))))
+                        .collect();
                     serde_json::json!({
                         "path": p.nodes,
                         "link_types": p.link_types,
+                        "link_meta": p.link_meta,
+                        "stale": stale,
                     })
                 })
                 .collect();
@@ -55,7 +65,10 @@ fn print_path(graph: &Graph, path: &crate::models::DependencyPath) {
     let mut parts = Vec::new();
     for (i, node_id) in path.nodes.iter().enumerate() {
         if let Some(record) = graph.get(node_id) {
-            let node_str = format!("{} ({})", node_id.cyan(), record.title());
+            let mut node_str = format!("{} ({})", node_id.cyan(), record.title());
+            if let Some(note) = stale_note(graph, node_id) {
+                node_str.push_str(&format!(" {}", format!("[{}]", note).yellow()));
+            }
             parts.push(node_str);
 
             if i < path.link_types.len() {
@@ -68,5 +81,15 @@ fn print_path(graph: &Graph, path: &crate::models::DependencyPath) {
         }
     }
     println!("  {}", parts.join(""));
+    for (i, meta) in path.link_meta.iter().enumerate() {
+        if let Some(summary) = meta.summary() {
+            println!(
+                "    {} → {}: {}",
+                path.nodes[i],
+                path.nodes[i + 1],
+                summary.dimmed()
+            );
+        }
+    }
     println!();
 }
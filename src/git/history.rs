@@ -1,13 +1,15 @@
-//! Git history traversal for dg.toml configurations
+//! Git history traversal for dg.toml configurations and records
 //!
-//! Allows viewing team and user configurations at any point in git history.
+//! Allows viewing team and user configurations, and the records themselves,
+//! at any point in git history.
 
 use crate::serve::config::DgConfig;
 use anyhow::{Context, Result};
 use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::HashMap;
-use std::path::Path;
-use std::process::Command;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 /// A snapshot of configuration at a specific commit
 #[derive(Debug, Clone)]
@@ -22,12 +24,45 @@ pub struct ConfigSnapshot {
     pub config: DgConfig,
 }
 
+/// A commit that a point-in-time view is read from
+#[derive(Debug, Clone)]
+pub struct Revision {
+    /// Git commit hash (full)
+    pub commit: String,
+    /// Commit date
+    pub date: DateTime<Utc>,
+}
+
+impl Revision {
+    pub fn short(&self) -> &str {
+        &self.commit[..7.min(self.commit.len())]
+    }
+}
+
+/// A commit that touched one file
+#[derive(Debug, Clone)]
+pub struct FileCommit {
+    /// Git commit hash (full)
+    pub commit: String,
+    pub author: String,
+    /// Author date
+    pub date: DateTime<Utc>,
+    /// Commit message (first line)
+    pub message: String,
+    /// The file's path in this commit, relative to the repo root
+    pub path: String,
+    /// Unified diff hunks for the file in this commit
+    pub patch: String,
+}
+
 /// Git history traversal for configurations
 pub struct GitHistory {
     /// Path to the repository root
     repo_path: std::path::PathBuf,
     /// Relative path to dg.toml from repo root
     config_rel_path: String,
+    /// Relative path to the decisions directory from repo root
+    decisions_rel_path: String,
 }
 
 impl GitHistory {
@@ -55,12 +90,249 @@ impl GitHistory {
             .to_string_lossy()
             .to_string();
 
+        // Where the docs directory sits inside the repo, e.g. "docs/"
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-prefix"])
+            .current_dir(docs_dir)
+            .output()
+            .context("Failed to find docs directory in git repository")?;
+        let prefix = String::from_utf8(output.stdout)?.trim().to_string();
+        let decisions_rel_path = format!("{}decisions", prefix);
+
         Ok(Self {
             repo_path,
             config_rel_path,
+            decisions_rel_path,
         })
     }
 
+    /// Resolve a revision, or a YYYY-MM-DD date to the last commit made on
+    /// or before that day
+    pub fn resolve(&self, at: &str) -> Result<Revision> {
+        let commit = if let Ok(date) = NaiveDate::parse_from_str(at, "%Y-%m-%d") {
+            let output = self.git(&[
+                "rev-list",
+                "-1",
+                &format!("--before={}T23:59:59", date),
+                "HEAD",
+            ])?;
+            if output.is_empty() {
+                anyhow::bail!("No commits on or before {}", date);
+            }
+            output
+        } else {
+            self.git(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", at),
+            ])
+            .ok()
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Unknown revision: {}", at))?
+        };
+
+        let date = self.git(&["show", "-s", "--format=%cI", &commit])?;
+        let date = DateTime::parse_from_rfc3339(&date)
+            .map(|d| d.with_timezone(&Utc))
+            .context("Failed to read commit date")?;
+        Ok(Revision { commit, date })
+    }
+
+    /// Every record file as committed at `commit`, with its path and content
+    pub fn records_at(&self, commit: &str) -> Result<Vec<(PathBuf, String)>> {
+        let paths = self.record_paths_at(commit)?;
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let specs: Vec<String> = paths.iter().map(|p| format!("{}:{}", commit, p)).collect();
+        let blobs = self.read_blobs(&specs)?;
+        Ok(paths
+            .iter()
+            .zip(blobs)
+            .filter_map(|(path, content)| Some((self.repo_path.join(path), content?)))
+            .collect())
+    }
+
+    /// Record files at `rev`, relative to the repo root
+    pub fn record_paths_at(&self, rev: &str) -> Result<Vec<String>> {
+        let listing = self.git(&[
+            "ls-tree",
+            "-r",
+            "--name-only",
+            rev,
+            "--",
+            &self.decisions_rel_path,
+        ])?;
+        Ok(listing
+            .lines()
+            .filter(|p| p.ends_with(".md"))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// The best common ancestor of two revisions
+    pub fn merge_base(&self, a: &str, b: &str) -> Result<String> {
+        self.git(&["merge-base", a, b])
+    }
+
+    /// The repository root
+    pub fn root(&self) -> &Path {
+        &self.repo_path
+    }
+
+    /// Files changed between `base` and `head`, or between `base` and the
+    /// working tree (untracked files included) when there is no head.
+    /// Paths are relative to the repo root.
+    pub fn changed_paths(&self, base: &str, head: Option<&str>) -> Result<Vec<String>> {
+        let mut args = vec!["diff", "--name-only", base];
+        args.extend(head);
+        let mut paths: Vec<String> = self.git(&args)?.lines().map(str::to_string).collect();
+        if head.is_none() {
+            let untracked = self.git(&["ls-files", "--others", "--exclude-standard"])?;
+            paths.extend(untracked.lines().map(str::to_string));
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// Names of the local branches
+    pub fn local_branches(&self) -> Result<Vec<String>> {
+        let output = self.git(&["for-each-ref", "--format=%(refname:short)", "refs/heads"])?;
+        Ok(output.lines().map(str::to_string).collect())
+    }
+
+    /// A working tree path relative to the repo root, as git names it
+    pub fn repo_relative(&self, path: &Path) -> Option<String> {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        path.strip_prefix(&self.repo_path)
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())
+    }
+
+    /// Read `<commit>:<path>` objects through one cat-file process; missing
+    /// objects come back as None
+    pub fn read_blobs(&self, specs: &[String]) -> Result<Vec<Option<String>>> {
+        if specs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut child = Command::new("git")
+            .args(["cat-file", "--batch"])
+            .current_dir(&self.repo_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .context("Failed to run git cat-file")?;
+        let mut stdin = child.stdin.take().context("Failed to open git cat-file")?;
+        let request: String = specs.iter().map(|s| format!("{}\n", s)).collect();
+        let writer = std::thread::spawn(move || stdin.write_all(request.as_bytes()));
+        let output = child.wait_with_output()?;
+        writer
+            .join()
+            .map_err(|_| anyhow::anyhow!("Failed to write to git cat-file"))??;
+
+        // Each object is "<sha> blob <size>\n<content>\n", or "<spec> missing\n"
+        let mut blobs = Vec::new();
+        let mut rest = output.stdout.as_slice();
+        for _ in specs {
+            let header_end = rest
+                .iter()
+                .position(|&b| b == b'\n')
+                .context("Truncated git cat-file output")?;
+            let header = String::from_utf8_lossy(&rest[..header_end]).to_string();
+            rest = &rest[header_end + 1..];
+            let size: usize = match header.split(' ').nth(2).and_then(|s| s.parse().ok()) {
+                Some(size) => size,
+                None => {
+                    blobs.push(None);
+                    continue;
+                }
+            };
+            blobs.push(Some(String::from_utf8_lossy(&rest[..size]).to_string()));
+            rest = &rest[size + 1..];
+        }
+        Ok(blobs)
+    }
+
+    /// Commits that touched a file, newest first, following renames
+    pub fn file_log(&self, path: &Path) -> Result<Vec<FileCommit>> {
+        let mut current = self
+            .repo_relative(path)
+            .ok_or_else(|| anyhow::anyhow!("{} is outside the git repository", path.display()))?;
+        let output = self.git(&[
+            "log",
+            "--follow",
+            "-p",
+            "--format=%x1e%H%x1f%an%x1f%aI%x1f%s",
+            "--",
+            &current,
+        ])?;
+
+        let mut commits = Vec::new();
+        for entry in output.split('\x1e').filter(|e| !e.trim().is_empty()) {
+            let (header, body) = entry.split_once('\n').unwrap_or((entry, ""));
+            let parts: Vec<&str> = header.splitn(4, '\x1f').collect();
+            if parts.len() < 4 {
+                continue;
+            }
+            // The path this commit left the file at; renames show up in the patch header
+            for line in body.lines().take_while(|l| !l.starts_with("@@")) {
+                if let Some(to) = line
+                    .strip_prefix("rename to ")
+                    .or_else(|| line.strip_prefix("+++ b/"))
+                {
+                    current = to.to_string();
+                }
+            }
+            let patch = body
+                .find("\n@@")
+                .map(|start| body[start + 1..].trim_end().to_string())
+                .unwrap_or_default();
+            commits.push(FileCommit {
+                commit: parts[0].to_string(),
+                author: parts[1].to_string(),
+                date: DateTime::parse_from_rfc3339(parts[2])
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+                message: parts[3].to_string(),
+                path: current.clone(),
+                patch,
+            });
+        }
+        Ok(commits)
+    }
+
+    /// Days on which records changed, oldest first
+    pub fn record_change_dates(&self) -> Result<Vec<NaiveDate>> {
+        let output = self.git(&["log", "--format=%cs", "--", &self.decisions_rel_path])?;
+        let mut dates: Vec<NaiveDate> = output
+            .lines()
+            .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .collect();
+        dates.sort();
+        dates.dedup();
+        Ok(dates)
+    }
+
+    /// Run git in the repository root and return its trimmed output
+    pub(super) fn git(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.repo_path)
+            .output()
+            .with_context(|| format!("Failed to run git {}", args[0]))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+
     /// Get all commits that modified dg.toml
     pub fn config_commits(&self) -> Result<Vec<ConfigSnapshot>> {
         let output = Command::new("git")
@@ -1,5 +1,6 @@
-//! Git integration for historical config traversal
+//! Git integration for historical traversal and commit trailers
 
 pub mod history;
+pub mod trailers;
 
 pub use history::GitHistory;
@@ -0,0 +1,136 @@
+//! Commit trailers that tie code changes to the records they implement
+//!
+//! A commit names records in its trailer block, e.g.
+//!
+//! ```text
+//! Add read replicas to the order service
+//!
+//! Decision: ADR-003
+//! Implements: POL-001, POL-002
+//! ```
+
+use super::GitHistory;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// Trailer keys that name implemented records, matched case-insensitively
+pub const TRAILER_KEYS: &[&str] = &["Decision", "Implements"];
+
+/// A commit whose trailers name one or more records
+#[derive(Debug, Clone, Serialize)]
+pub struct TrailerCommit {
+    /// Git commit hash (full)
+    pub commit: String,
+    pub author: String,
+    /// Author date
+    pub date: DateTime<Utc>,
+    /// Commit message (first line)
+    pub message: String,
+    /// Record IDs named in the trailers
+    pub records: Vec<String>,
+    /// Files the commit touched, relative to the repo root
+    pub files: Vec<String>,
+}
+
+impl TrailerCommit {
+    pub fn short(&self) -> &str {
+        &self.commit[..7.min(self.commit.len())]
+    }
+}
+
+/// Record IDs named by `Decision:` and `Implements:` lines in a trailer block
+pub fn trailer_ids(trailers: &str) -> Vec<String> {
+    static ID: OnceLock<Regex> = OnceLock::new();
+    let id = ID.get_or_init(|| Regex::new(r"\b[A-Z][A-Z0-9]*-\d+\b").unwrap());
+
+    let mut ids = Vec::new();
+    for line in trailers.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !TRAILER_KEYS
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(key.trim()))
+        {
+            continue;
+        }
+        for m in id.find_iter(value) {
+            if !ids.iter().any(|i| i == m.as_str()) {
+                ids.push(m.as_str().to_string());
+            }
+        }
+    }
+    ids
+}
+
+impl GitHistory {
+    /// Commits reachable from HEAD whose trailers name a record, newest first
+    pub fn trailer_commits(&self) -> Result<Vec<TrailerCommit>> {
+        let grep = format!("--grep=^({}):", TRAILER_KEYS.join("|"));
+        let output = self.git(&[
+            "log",
+            "-i",
+            "-E",
+            &grep,
+            "--name-only",
+            "--format=%x1e%H%x1f%an%x1f%aI%x1f%s%x1f%(trailers:only,unfold)%x1f",
+        ])?;
+
+        let mut commits = Vec::new();
+        for entry in output.split('\x1e').filter(|e| !e.trim().is_empty()) {
+            let parts: Vec<&str> = entry.splitn(6, '\x1f').collect();
+            if parts.len() < 6 {
+                continue;
+            }
+            // --grep matches any line of the message, not just the trailer block
+            let records = trailer_ids(parts[4]);
+            if records.is_empty() {
+                continue;
+            }
+            commits.push(TrailerCommit {
+                commit: parts[0].to_string(),
+                author: parts[1].to_string(),
+                date: DateTime::parse_from_rfc3339(parts[2])
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+                message: parts[3].to_string(),
+                records,
+                files: parts[5]
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            });
+        }
+        Ok(commits)
+    }
+
+    /// Commits whose trailers name `id`, newest first
+    pub fn commits_for(&self, id: &str) -> Result<Vec<TrailerCommit>> {
+        Ok(self
+            .trailer_commits()?
+            .into_iter()
+            .filter(|c| c.records.iter().any(|r| r == id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailer_ids() {
+        let trailers = "Signed-off-by: Ann <ann@example.com>\n\
+                        Decision: ADR-003\n\
+                        implements: POL-001, POL-002\n\
+                        Refs: DEC-009\n\
+                        Decision: ADR-003";
+        assert_eq!(trailer_ids(trailers), vec!["ADR-003", "POL-001", "POL-002"]);
+        assert!(trailer_ids("Decision: none yet").is_empty());
+    }
+}
@@ -27,6 +27,10 @@ struct Cli {
     #[arg(long, global = true)]
     force: bool,
 
+    /// Read records as committed at a date (YYYY-MM-DD) or git revision; for list, show, why and graph
+    #[arg(long, global = true, value_name = "DATE|REV")]
+    at: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -34,11 +38,15 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize decision graph in current directory
-    Init,
+    Init {
+        /// Register the record merge driver without asking (run again in each clone)
+        #[arg(long)]
+        merge_driver: bool,
+    },
 
     /// Create a new record
     New {
-        /// Record type: decision, strategy, policy, client, opportunity, process, hiring
+        /// Record type: decision, strategy, policy, customer, adr, incident, ... or a custom type from dg.toml
         #[arg(value_name = "TYPE")]
         record_type: String,
 
@@ -60,11 +68,22 @@ enum Commands {
         /// Preview changes without applying
         #[arg(long)]
         dry_run: bool,
+
+        /// Git ref whose record IDs are already taken (default: [git] base in dg.toml, else origin/main)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Renumber the newer record of each duplicated permanent ID and rewrite its references
+        #[arg(long)]
+        rebase_ids: bool,
     },
 
     /// List all records
     #[command(alias = "ls")]
     List {
+        /// Query, e.g. 'tag:auth OR tag:security -status:deprecated' (see `dg search`)
+        query: Option<String>,
+
         /// Filter by type
         #[arg(short, long)]
         r#type: Option<String>,
@@ -107,11 +126,23 @@ enum Commands {
         /// Source record ID
         from: String,
 
-        /// Link type: supersedes, depends_on, enables, relates_to, conflicts_with, refines, implements
+        /// Link type: supersedes, depends_on, enables, relates_to, conflicts_with, refines, implements, or one declared under [links] in dg.toml
         link_type: String,
 
         /// Target record ID
         to: String,
+
+        /// Why the link exists (stored as link metadata)
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Date the relationship started (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+
+        /// How strongly the records are tied (e.g., strong, weak)
+        #[arg(long)]
+        strength: Option<String>,
     },
 
     /// Remove a link between records
@@ -126,9 +157,23 @@ enum Commands {
         to: String,
     },
 
+    /// Move links from superseded records to the records that replaced them
+    Retarget {
+        /// Only this record's links (default: every live record)
+        id: Option<String>,
+
+        /// Link type to move, repeatable (default: depends_on, implements, refines)
+        #[arg(short, long = "link-type")]
+        link_type: Vec<String>,
+
+        /// Preview changes without applying
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Search records
     Search {
-        /// Search query
+        /// Search query: words, "phrases" and filters such as type:adr, combined with OR, -term and ( )
         query: String,
 
         /// Search in content (not just titles)
@@ -172,7 +217,8 @@ enum Commands {
         id: String,
 
         /// New status: draft, proposed, accepted, deprecated, superseded
-        status: String,
+        /// (omit to show the legal next states)
+        status: Option<String>,
     },
 
     /// Resolve an incident
@@ -202,14 +248,92 @@ enum Commands {
     /// Validate all records
     Validate,
 
-    /// Show statistics
-    Stats,
+    /// Show statistics, including centrality and keystone records
+    Stats {
+        /// Output format: table, json
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
 
-    /// Show changes since last commit
+    /// Show record, link and section changes since a git ref
     Diff {
         /// Base reference to compare against (default: HEAD)
         #[arg(short, long)]
         base: Option<String>,
+
+        /// Reference to compare with the base (default: the working tree)
+        #[arg(long)]
+        head: Option<String>,
+
+        /// Output format: text, json, markdown
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Merge three versions of a record; used by git as a merge driver (%O %A %B)
+    MergeDriver {
+        /// Common ancestor version
+        base: std::path::PathBuf,
+
+        /// Our version, overwritten with the result
+        ours: std::path::PathBuf,
+
+        /// Their version
+        theirs: std::path::PathBuf,
+    },
+
+    /// Show how a record changed commit by commit, following renames
+    Log {
+        /// Record ID
+        id: String,
+
+        /// Show the file diff of each commit
+        #[arg(short, long)]
+        patch: bool,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Show the last commit to change each section of a record
+    Blame {
+        /// Record ID
+        id: String,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// List the records that govern a file, from [governance] in dg.toml or docs/GOVERNANCE
+    Governs {
+        /// File or directory path
+        path: String,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// List code that mentions a record, including `// @decision ID` annotations
+    Refs {
+        /// Record ID
+        id: String,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// List commits whose Decision: or Implements: trailers name a record
+    Commits {
+        /// Record ID
+        id: String,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
 
     /// Format markdown files
@@ -218,6 +342,10 @@ enum Commands {
         #[arg(short, long)]
         check: bool,
 
+        /// Rewrite frontmatter in another syntax: toml (+++) or yaml (---)
+        #[arg(long, value_name = "FORMAT")]
+        convert: Option<String>,
+
         /// Specific files to format (default: all files in decisions)
         #[arg(value_name = "FILES")]
         files: Option<Vec<String>>,
@@ -273,6 +401,59 @@ enum Commands {
         format: String,
     },
 
+    /// Order records so prerequisites come first, in layers that can proceed in parallel
+    Order {
+        /// Only this record and the records it requires
+        id: Option<String>,
+
+        /// Link type to order by, repeatable (default: depends_on, refines, supersedes and acyclic types from dg.toml)
+        #[arg(short, long = "link-type")]
+        link_type: Vec<String>,
+
+        /// Output format: table, json, ids
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Show how two records are connected, e.g. "dg path CUS-001 STR-002"
+    Path {
+        /// Record to start from
+        from: String,
+
+        /// Record to reach
+        to: String,
+
+        /// Link type to follow, repeatable (default: all)
+        #[arg(short, long = "link-type")]
+        link_type: Vec<String>,
+
+        /// Only follow links in their declared direction
+        #[arg(long)]
+        directed: bool,
+
+        /// List every path that visits no record twice, not just the shortest
+        #[arg(long)]
+        all: bool,
+
+        /// Longest path to list with --all, in links
+        #[arg(long, default_value = "6", requires = "all")]
+        max_len: usize,
+
+        /// Output format: text, json, dot
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Match paths in the graph, e.g. "match (s:strategy)-[:enables*1..3]->(d:decision) return d"
+    Query {
+        /// Path query: match PATTERN[, PATTERN] return VAR[, VAR] [limit N]
+        query: String,
+
+        /// Output format: table, json, ids
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
     /// Get context for a topic (LLM-friendly)
     Context {
         /// Topic to search for
@@ -341,6 +522,10 @@ enum Commands {
         /// Output format: table, json
         #[arg(short, long, default_value = "table")]
         format: String,
+
+        /// Write a draft record for each suggestion, prefilled from its commits
+        #[arg(long)]
+        create_drafts: bool,
     },
 
     /// List orphaned records (no links)
@@ -611,13 +796,21 @@ enum HistoryAction {
 fn needs_write_lock(cmd: &Commands) -> bool {
     matches!(
         cmd,
-        Commands::Init
+        Commands::Init { .. }
             | Commands::New { .. }
             | Commands::Finalize { .. }
+            | Commands::Suggest {
+                create_drafts: true,
+                ..
+            }
             | Commands::Edit { .. }
             | Commands::Link { .. }
             | Commands::Unlink { .. }
-            | Commands::Status { .. }
+            | Commands::Retarget { dry_run: false, .. }
+            | Commands::Status {
+                status: Some(_),
+                ..
+            }
             | Commands::Resolve { .. }
             | Commands::Reindex
             | Commands::Fmt { check: false, .. }
@@ -640,6 +833,39 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
     let docs_path = Path::new(&cli.docs_dir);
 
+    // Register custom record and link types and reference settings from dg.toml before any records are parsed
+    if let Ok(config) = serve::config::DgConfig::load(docs_path) {
+        config.register_types();
+        config.register_link_types();
+        config.register_references();
+    }
+
+    // Resolve --at once, so every command sees the same commit
+    let at = match &cli.at {
+        Some(at) => {
+            if !matches!(
+                cli.command,
+                Commands::List { .. }
+                    | Commands::Show { .. }
+                    | Commands::Why { .. }
+                    | Commands::Graph { .. }
+            ) {
+                anyhow::bail!("--at is only supported by list, show, why and graph");
+            }
+            let revision = git::GitHistory::new(docs_path)?.resolve(at)?;
+            if !cli.quiet {
+                eprintln!(
+                    "As of {} ({})",
+                    revision.short(),
+                    revision.date.format("%Y-%m-%d")
+                );
+            }
+            Some(revision.commit)
+        }
+        None => None,
+    };
+    let at = at.as_deref();
+
     // Acquire lock for write operations
     let _lock = if needs_write_lock(&cli.command) {
         lock::GraphLock::acquire(docs_path, cli.force)?
@@ -648,32 +874,65 @@ fn main() -> Result<()> {
     };
 
     match cli.command {
-        Commands::Init => commands::init::run(&cli.docs_dir),
+        Commands::Init { merge_driver } => commands::init::run(&cli.docs_dir, merge_driver),
         Commands::New {
             record_type,
             title,
             draft,
             vars,
         } => commands::new::run(&cli.docs_dir, &record_type, &title, draft, vars),
-        Commands::Finalize { dry_run } => commands::finalize::run(&cli.docs_dir, dry_run),
+        Commands::Finalize {
+            dry_run,
+            base,
+            rebase_ids,
+        } => commands::finalize::run(&cli.docs_dir, dry_run, base.as_deref(), rebase_ids),
         Commands::List {
+            query,
             r#type,
             status,
             tag,
             format,
-        } => commands::list::run(&cli.docs_dir, r#type, status, tag, &format),
-        Commands::Show { id, links, json } => commands::show::run(&cli.docs_dir, &id, links, json),
+        } => commands::list::run(
+            &cli.docs_dir,
+            query.as_deref(),
+            r#type,
+            status,
+            tag,
+            &format,
+            at,
+        ),
+        Commands::Show { id, links, json } => {
+            commands::show::run(&cli.docs_dir, &id, links, json, at)
+        }
         Commands::Edit { id } => commands::edit::run(&cli.docs_dir, &id),
         Commands::Link {
             from,
             link_type,
             to,
-        } => commands::link::run(&cli.docs_dir, &from, &link_type, &to),
+            note,
+            since,
+            strength,
+        } => commands::link::run(
+            &cli.docs_dir,
+            &from,
+            &link_type,
+            &to,
+            models::LinkMeta {
+                note,
+                since,
+                strength,
+            },
+        ),
         Commands::Unlink {
             from,
             link_type,
             to,
         } => commands::unlink::run(&cli.docs_dir, &from, &link_type, &to),
+        Commands::Retarget {
+            id,
+            link_type,
+            dry_run,
+        } => commands::retarget::run(&cli.docs_dir, id.as_deref(), &link_type, dry_run),
         Commands::Search {
             query,
             content,
@@ -693,8 +952,11 @@ fn main() -> Result<()> {
             &format,
             render.as_deref(),
             output.as_deref(),
+            at,
         ),
-        Commands::Status { id, status } => commands::status::run(&cli.docs_dir, &id, &status),
+        Commands::Status { id, status } => {
+            commands::status::run(&cli.docs_dir, &id, status.as_deref())
+        }
         Commands::Resolve { id, note } => {
             commands::resolve::run(&cli.docs_dir, &id, note.as_deref())
         }
@@ -703,11 +965,25 @@ fn main() -> Result<()> {
             commands::export::run(&cli.docs_dir, &format, output.as_deref())
         }
         Commands::Validate => commands::validate::run(&cli.docs_dir, cli.quiet),
-        Commands::Stats => commands::stats::run(&cli.docs_dir),
-        Commands::Diff { base } => commands::diff::run(&cli.docs_dir, base.as_deref()),
-        Commands::Fmt { check, files } => {
-            commands::fmt::run(&cli.docs_dir, check, files, cli.quiet)
+        Commands::Stats { format } => commands::stats::run(&cli.docs_dir, &format),
+        Commands::Diff { base, head, format } => {
+            commands::diff::run(&cli.docs_dir, base.as_deref(), head.as_deref(), &format)
         }
+        Commands::Log { id, patch, format } => {
+            commands::log::run(&cli.docs_dir, &id, patch, &format)
+        }
+        Commands::Blame { id, format } => commands::blame::run(&cli.docs_dir, &id, &format),
+        Commands::Commits { id, format } => commands::commits::run(&cli.docs_dir, &id, &format),
+        Commands::Refs { id, format } => commands::refs::run(&cli.docs_dir, &id, &format),
+        Commands::Governs { path, format } => commands::governs::run(&cli.docs_dir, &path, &format),
+        Commands::MergeDriver { base, ours, theirs } => {
+            commands::merge_driver::run(&base, &ours, &theirs)
+        }
+        Commands::Fmt {
+            check,
+            convert,
+            files,
+        } => commands::fmt::run(&cli.docs_dir, check, convert, files, cli.quiet),
         Commands::Lint {
             files,
             strict,
@@ -724,8 +1000,32 @@ fn main() -> Result<()> {
             cli.quiet,
         ),
         Commands::Principles { format } => commands::principles::run(&cli.docs_dir, &format),
-        Commands::Why { id, format } => commands::why::run(&cli.docs_dir, &id, &format),
+        Commands::Why { id, format } => commands::why::run(&cli.docs_dir, &id, &format, at),
         Commands::Impact { id, format } => commands::impact::run(&cli.docs_dir, &id, &format),
+        Commands::Order {
+            id,
+            link_type,
+            format,
+        } => commands::order::run(&cli.docs_dir, id.as_deref(), &link_type, &format),
+        Commands::Path {
+            from,
+            to,
+            link_type,
+            directed,
+            all,
+            max_len,
+            format,
+        } => commands::path::run(
+            &cli.docs_dir,
+            &from,
+            &to,
+            &link_type,
+            directed,
+            all,
+            max_len,
+            &format,
+        ),
+        Commands::Query { query, format } => commands::query::run(&cli.docs_dir, &query, &format),
         Commands::Context {
             topic,
             depth,
@@ -743,9 +1043,11 @@ fn main() -> Result<()> {
         Commands::Serve { port, open, watch } => {
             commands::serve::run(&cli.docs_dir, port, open, watch)
         }
-        Commands::Suggest { since, format } => {
-            commands::suggest::run(&cli.docs_dir, Some(&since), &format)
-        }
+        Commands::Suggest {
+            since,
+            format,
+            create_drafts,
+        } => commands::suggest::run(&cli.docs_dir, Some(&since), &format, create_drafts),
         Commands::Orphans { format } => commands::orphans::run(&cli.docs_dir, &format),
         Commands::Timeline {
             limit,
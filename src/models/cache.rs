@@ -0,0 +1,292 @@
+//! On-disk cache of parsed records, so `Graph::load` only re-parses files
+//! that changed since the last run.
+//!
+//! Entries are keyed on the path relative to the docs dir and trusted when
+//! mtime and size match. Files whose mtime changed (e.g. after a git checkout)
+//! or that were modified around the time the cache was written are checked
+//! against a content hash before the cached record is reused.
+
+use super::record::Frontmatter;
+use super::{FrontmatterFormat, Record};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bump when the cached representation of a record changes
+const CACHE_FORMAT: u32 = 1;
+const CACHE_DIR: &str = ".cache";
+const CACHE_FILE: &str = "records.json";
+/// Files modified this close to a cache write can't be trusted by mtime alone
+const RACY_WINDOW_NS: u64 = 2_000_000_000;
+
+fn cache_version() -> String {
+    format!("{}-{}", CACHE_FORMAT, env!("CARGO_PKG_VERSION"))
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    generated_ns: u64,
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    mtime_ns: u64,
+    size: u64,
+    hash: String,
+    format: FrontmatterFormat,
+    frontmatter: serde_json::Value,
+    content: String,
+}
+
+impl CacheEntry {
+    fn new(record: &Record, mtime_ns: u64, size: u64, hash: String) -> Option<CacheEntry> {
+        Some(CacheEntry {
+            mtime_ns,
+            size,
+            hash,
+            format: record.format,
+            frontmatter: serde_json::to_value(&record.frontmatter).ok()?,
+            content: record.content.clone(),
+        })
+    }
+
+    fn to_record(&self, path: &Path) -> Option<Record> {
+        Some(Record {
+            frontmatter: Frontmatter::deserialize(&self.frontmatter).ok()?,
+            content: self.content.clone(),
+            path: path.to_path_buf(),
+            format: self.format,
+        })
+    }
+}
+
+/// Parsed records from the previous load, plus the entries seen in this one
+pub struct RecordCache {
+    docs_dir: PathBuf,
+    generated_ns: u64,
+    previous: HashMap<String, CacheEntry>,
+    current: HashMap<String, CacheEntry>,
+    dirty: bool,
+}
+
+impl RecordCache {
+    /// Open the cache for a docs dir. A missing, unreadable or outdated cache
+    /// starts out empty.
+    pub fn open(docs_dir: &Path) -> RecordCache {
+        let file = read_cache_file(docs_dir, CACHE_FILE)
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == cache_version());
+
+        match file {
+            Some(file) => RecordCache {
+                docs_dir: docs_dir.to_path_buf(),
+                generated_ns: file.generated_ns,
+                previous: file.entries,
+                current: HashMap::new(),
+                dirty: false,
+            },
+            None => RecordCache::empty(docs_dir),
+        }
+    }
+
+    /// A cache that re-parses everything and replaces the file on save
+    pub fn empty(docs_dir: &Path) -> RecordCache {
+        RecordCache {
+            docs_dir: docs_dir.to_path_buf(),
+            generated_ns: 0,
+            previous: HashMap::new(),
+            current: HashMap::new(),
+            dirty: true,
+        }
+    }
+
+    /// Load a record, reusing the cached parse when the file is unchanged
+    pub fn parse(&mut self, path: &Path) -> Result<Record> {
+        let key = path
+            .strip_prefix(&self.docs_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        let metadata = fs::metadata(path)?;
+        let size = metadata.len();
+        let mtime_ns = metadata.modified().map(nanos_since_epoch).unwrap_or(0);
+
+        let mut bytes = None;
+        if let Some(mut entry) = self.previous.remove(&key) {
+            if entry.size == size {
+                let trusted = entry.mtime_ns == mtime_ns && !is_racy(mtime_ns, self.generated_ns);
+                if !trusted {
+                    let read = fs::read(path)?;
+                    if content_hash(&read) == entry.hash {
+                        // Rewrite once the new mtime can be trusted on its own
+                        if entry.mtime_ns != mtime_ns || !is_racy(mtime_ns, now_ns()) {
+                            self.dirty = true;
+                        }
+                        entry.mtime_ns = mtime_ns;
+                    } else {
+                        bytes = Some(read);
+                    }
+                }
+                if bytes.is_none() {
+                    if let Some(record) = entry.to_record(path) {
+                        self.current.insert(key, entry);
+                        return Ok(record);
+                    }
+                }
+            }
+        }
+
+        let bytes = match bytes {
+            Some(bytes) => bytes,
+            None => fs::read(path)?,
+        };
+        let text = String::from_utf8(bytes)?;
+        let record = Record::parse_content(&text, path.to_path_buf())?;
+        if let Some(entry) = CacheEntry::new(&record, mtime_ns, size, content_hash(text.as_bytes()))
+        {
+            self.current.insert(key, entry);
+        }
+        self.dirty = true;
+        Ok(record)
+    }
+
+    /// Write the cache if anything was added, changed or removed
+    pub fn save(self) -> Result<()> {
+        let unchanged = !self.dirty && self.previous.is_empty();
+        let nothing_cached = self.current.is_empty() && self.previous.is_empty();
+        if unchanged || nothing_cached {
+            return Ok(());
+        }
+
+        let file = CacheFile {
+            version: cache_version(),
+            generated_ns: now_ns(),
+            entries: self.current,
+        };
+        write_cache_file(&self.docs_dir, CACHE_FILE, &serde_json::to_vec(&file)?)
+    }
+}
+
+/// Read a file from the docs dir's cache directory
+pub fn read_cache_file(docs_dir: &Path, name: &str) -> Option<Vec<u8>> {
+    fs::read(docs_dir.join(CACHE_DIR).join(name)).ok()
+}
+
+/// Replace a file in the docs dir's cache directory, which git ignores
+pub fn write_cache_file(docs_dir: &Path, name: &str, bytes: &[u8]) -> Result<()> {
+    let dir = docs_dir.join(CACHE_DIR);
+    fs::create_dir_all(&dir)?;
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(&gitignore, "*\n")?;
+    }
+
+    // Write to a temporary file first so concurrent loads never see half a file
+    let tmp = dir.join(format!("{}.{}.tmp", name, std::process::id()));
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, dir.join(name))?;
+    Ok(())
+}
+
+fn nanos_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn now_ns() -> u64 {
+    nanos_since_epoch(SystemTime::now())
+}
+
+fn is_racy(mtime_ns: u64, written_ns: u64) -> bool {
+    mtime_ns.saturating_add(RACY_WINDOW_NS) >= written_ns
+}
+
+/// FNV-1a, stable across Rust versions unlike `DefaultHasher`
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const RECORD: &str = "---\ntype: decision\nid: DEC-001\ntitle: Use Postgres\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\ntags: [db]\nlinks:\n  depends_on:\n    - id: STR-001\n      note: needs the budget\n---\n\n# Use Postgres\n";
+
+    /// Write a record with an mtime old enough to be trusted without hashing
+    fn write_record(dir: &Path, content: &str) -> PathBuf {
+        let path = dir.join("DEC-001-use-postgres.md");
+        fs::write(&path, content).unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_unchanged_file_is_served_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_record(dir.path(), RECORD);
+
+        let mut cache = RecordCache::open(dir.path());
+        let parsed = cache.parse(&path).unwrap();
+        cache.save().unwrap();
+        assert!(dir.path().join(".cache/.gitignore").exists());
+
+        let mut cache = RecordCache::open(dir.path());
+        let cached = cache.parse(&path).unwrap();
+        assert!(!cache.dirty, "an unchanged file should not be re-parsed");
+        assert_eq!(cached.title(), parsed.title());
+        assert_eq!(cached.content, parsed.content);
+        assert_eq!(
+            cached.frontmatter.links.depends_on[0].meta.note.as_deref(),
+            Some("needs the budget")
+        );
+    }
+
+    #[test]
+    fn test_changed_file_is_reparsed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_record(dir.path(), RECORD);
+        let mut cache = RecordCache::open(dir.path());
+        cache.parse(&path).unwrap();
+        cache.save().unwrap();
+
+        // Same size, different content: caught by the hash even if the mtime matched
+        write_record(dir.path(), &RECORD.replace("Postgres", "Postgrez"));
+        let mut cache = RecordCache::open(dir.path());
+        let record = cache.parse(&path).unwrap();
+        assert_eq!(record.title(), "Use Postgrez");
+        assert!(cache.dirty);
+    }
+
+    #[test]
+    fn test_version_mismatch_discards_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_record(dir.path(), RECORD);
+        let mut cache = RecordCache::open(dir.path());
+        cache.parse(&path).unwrap();
+        cache.save().unwrap();
+
+        let cache_path = dir.path().join(CACHE_DIR).join(CACHE_FILE);
+        let stale = fs::read_to_string(&cache_path)
+            .unwrap()
+            .replace(&cache_version(), "0-old");
+        fs::write(&cache_path, stale).unwrap();
+
+        let cache = RecordCache::open(dir.path());
+        assert!(cache.previous.is_empty());
+        assert!(cache.dirty);
+    }
+}
@@ -0,0 +1,318 @@
+//! Structural metrics over the record graph: degree, betweenness, PageRank,
+//! articulation points and weakly connected components.
+//!
+//! Only frontmatter links count; body mentions are too incidental to say
+//! anything about how the graph hangs together.
+
+use super::references::MENTIONS;
+use super::Graph;
+use serde::Serialize;
+use std::collections::{BTreeSet, VecDeque};
+
+const DAMPING: f64 = 0.85;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordCentrality {
+    pub id: String,
+    pub in_degree: usize,
+    pub out_degree: usize,
+    /// Share of shortest paths between other records that pass through this one (0 to 1)
+    pub betweenness: f64,
+    /// PageRank following link direction, so records many others link to rank high.
+    /// Ranks sum to 1.
+    pub pagerank: f64,
+    /// Records cut off from the rest of their component if this one were removed
+    pub disconnects: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Centrality {
+    /// Highest PageRank first
+    pub records: Vec<RecordCentrality>,
+    /// Records whose removal splits their component, most disconnecting first
+    pub articulation_points: Vec<String>,
+    /// Weakly connected components, largest first
+    pub components: Vec<Vec<String>>,
+}
+
+impl Centrality {
+    pub fn compute(graph: &Graph) -> Self {
+        let mut ids: Vec<&str> = graph.records.keys().map(|id| id.as_str()).collect();
+        ids.sort();
+        let n = ids.len();
+        let index = |id: &str| ids.binary_search(&id).ok();
+
+        let mut in_degree = vec![0; n];
+        let mut out_degree = vec![0; n];
+        let mut targets: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+        let mut neighbors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+        for edge in graph.edges() {
+            if edge.link_type == MENTIONS {
+                continue;
+            }
+            let (Some(from), Some(to)) = (index(&edge.from), index(&edge.to)) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            out_degree[from] += 1;
+            in_degree[to] += 1;
+            targets[from].insert(to);
+            neighbors[from].insert(to);
+            neighbors[to].insert(from);
+        }
+
+        let betweenness = betweenness(&neighbors);
+        let pagerank = pagerank(&targets);
+        let disconnects: Vec<usize> = (0..n).map(|v| disconnected_by(&neighbors, v)).collect();
+
+        let mut records: Vec<RecordCentrality> = (0..n)
+            .map(|i| RecordCentrality {
+                id: ids[i].to_string(),
+                in_degree: in_degree[i],
+                out_degree: out_degree[i],
+                betweenness: betweenness[i],
+                pagerank: pagerank[i],
+                disconnects: disconnects[i],
+            })
+            .collect();
+        records.sort_by(|a, b| b.pagerank.total_cmp(&a.pagerank).then(a.id.cmp(&b.id)));
+
+        let mut articulation: Vec<&RecordCentrality> =
+            records.iter().filter(|r| r.disconnects > 0).collect();
+        articulation.sort_by(|a, b| b.disconnects.cmp(&a.disconnects).then(a.id.cmp(&b.id)));
+        let articulation_points = articulation.iter().map(|r| r.id.clone()).collect();
+
+        let mut components: Vec<Vec<String>> = components(&neighbors)
+            .into_iter()
+            .map(|c| {
+                let mut c: Vec<String> = c.into_iter().map(|i| ids[i].to_string()).collect();
+                c.sort();
+                c
+            })
+            .collect();
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+
+        Centrality {
+            records,
+            articulation_points,
+            components,
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&RecordCentrality> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    /// Live records that hold the graph together but aren't marked core:
+    /// articulation points, records on at least a tenth of all shortest
+    /// paths, or records with twice the average PageRank
+    pub fn core_suggestions(&self, graph: &Graph) -> Vec<&RecordCentrality> {
+        let average = 1.0 / self.records.len().max(1) as f64;
+        let mut suggestions: Vec<&RecordCentrality> = self
+            .records
+            .iter()
+            .filter(|r| {
+                graph.get(&r.id).is_some_and(|record| {
+                    !record.frontmatter.core && !record.status().is_inactive()
+                })
+            })
+            .filter(|r| r.disconnects > 0 || r.betweenness >= 0.1 || r.pagerank >= 2.0 * average)
+            .collect();
+        suggestions.sort_by(|a, b| {
+            b.betweenness
+                .total_cmp(&a.betweenness)
+                .then(b.pagerank.total_cmp(&a.pagerank))
+                .then(a.id.cmp(&b.id))
+        });
+        suggestions
+    }
+}
+
+/// Brandes' algorithm over undirected links, normalized to 0..1
+fn betweenness(neighbors: &[BTreeSet<usize>]) -> Vec<f64> {
+    let n = neighbors.len();
+    let mut scores = vec![0.0; n];
+    for source in 0..n {
+        let mut order = Vec::new();
+        let mut previous: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0; n];
+        let mut distance: Vec<Option<usize>> = vec![None; n];
+        paths[source] = 1.0;
+        distance[source] = Some(0);
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            let d = distance[v].unwrap();
+            for &w in &neighbors[v] {
+                if distance[w].is_none() {
+                    distance[w] = Some(d + 1);
+                    queue.push_back(w);
+                }
+                if distance[w] == Some(d + 1) {
+                    paths[w] += paths[v];
+                    previous[w].push(v);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0; n];
+        while let Some(w) = order.pop() {
+            for &v in &previous[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != source {
+                scores[w] += dependency[w];
+            }
+        }
+    }
+
+    // Each pair was counted from both ends
+    if n > 2 {
+        let pairs = ((n - 1) * (n - 2)) as f64;
+        for score in &mut scores {
+            *score /= pairs;
+        }
+    }
+    scores
+}
+
+/// PageRank by power iteration; records without outgoing links spread
+/// their rank evenly
+fn pagerank(targets: &[BTreeSet<usize>]) -> Vec<f64> {
+    let n = targets.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..100 {
+        let dangling: f64 = (0..n)
+            .filter(|&v| targets[v].is_empty())
+            .map(|v| rank[v])
+            .sum();
+        let mut next = vec![(1.0 - DAMPING + DAMPING * dangling) / n as f64; n];
+        for v in 0..n {
+            for &w in &targets[v] {
+                next[w] += DAMPING * rank[v] / targets[v].len() as f64;
+            }
+        }
+        let change: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if change < 1e-10 {
+            break;
+        }
+    }
+    rank
+}
+
+/// Connected components over undirected links
+fn components(neighbors: &[BTreeSet<usize>]) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; neighbors.len()];
+    let mut components = Vec::new();
+    for start in 0..neighbors.len() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut component = vec![start];
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            for &w in &neighbors[v] {
+                if !seen[w] {
+                    seen[w] = true;
+                    component.push(w);
+                    stack.push(w);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// How many records lose their way to the largest remaining part of
+/// `v`'s component once `v` is gone
+fn disconnected_by(neighbors: &[BTreeSet<usize>], v: usize) -> usize {
+    if neighbors[v].len() < 2 {
+        return 0;
+    }
+    // The pieces left behind all touch one of v's neighbors
+    let mut seen = vec![false; neighbors.len()];
+    seen[v] = true;
+    let mut pieces = Vec::new();
+    for &start in &neighbors[v] {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut size = 0;
+        let mut stack = vec![start];
+        while let Some(u) = stack.pop() {
+            size += 1;
+            for &w in &neighbors[u] {
+                if !seen[w] {
+                    seen[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        pieces.push(size);
+    }
+    let largest = pieces.iter().copied().max().unwrap_or(0);
+    pieces.iter().sum::<usize>() - largest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::{self, graph_of};
+    use crate::models::Record;
+
+    fn record(id: &str, links: &str, core: bool) -> Record {
+        let frontmatter = format!(
+            "type: decision\ntitle: \"{}\"\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\nauthors: []\ntags: []\ncore: {}\nlinks: {}",
+            id, core, links
+        );
+        test_support::record(id, &frontmatter, &format!("# {}", id))
+    }
+
+    #[test]
+    fn test_centrality_finds_the_hub() {
+        // A star around DEC-001, plus DEC-005 on its own
+        let graph = graph_of(&[
+            record("DEC-001", "{}", false),
+            record("DEC-002", "{depends_on: [DEC-001]}", false),
+            record("DEC-003", "{depends_on: [DEC-001]}", false),
+            record("DEC-004", "{depends_on: [DEC-001]}", false),
+            record("DEC-005", "{}", false),
+        ]);
+        let centrality = Centrality::compute(&graph);
+
+        let hub = centrality.get("DEC-001").unwrap();
+        assert_eq!(hub.in_degree, 3);
+        assert_eq!(centrality.records[0].id, "DEC-001");
+        // Every path between the three leaves runs through the hub
+        assert!((hub.betweenness - 0.5).abs() < 1e-9);
+        assert_eq!(hub.disconnects, 2);
+        assert_eq!(centrality.get("DEC-002").unwrap().betweenness, 0.0);
+        let total: f64 = centrality.records.iter().map(|r| r.pagerank).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        assert_eq!(centrality.articulation_points, vec!["DEC-001"]);
+        assert_eq!(
+            centrality.components,
+            vec![
+                vec!["DEC-001", "DEC-002", "DEC-003", "DEC-004"],
+                vec!["DEC-005"]
+            ]
+        );
+
+        let suggested: Vec<&str> = centrality
+            .core_suggestions(&graph)
+            .iter()
+            .map(|r| r.id.as_str())
+            .collect();
+        assert_eq!(suggested, vec!["DEC-001"]);
+    }
+}
@@ -0,0 +1,275 @@
+//! Record IDs in source files: plain mentions such as `// see ADR-003`, and
+//! annotations such as `// @decision ADR-003` or `# @implements POL-001`
+//! that mark code as the implementation of a record
+
+use crate::models::references::bare_id_regex;
+use crate::models::RecordType;
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+/// Pseudo-edge type from a record to the files that reference it
+pub const IMPLEMENTED_IN: &str = "implemented_in";
+
+/// Annotation keywords, written `@decision ID` or `@implements ID, ID`
+pub const ANNOTATION_KEYWORDS: &[&str] = &["decision", "implements"];
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "ts", "tsx", "jsx", "rb", "go", "java", "kt", "swift", "c", "cpp", "h",
+    "hpp", "cs", "php", "sh", "bash", "zsh", "yaml", "yml", "toml", "json", "sql", "ex", "exs",
+    "erl", "hs", "ml", "scala", "clj",
+];
+
+/// One record ID on one line of a source file
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeRef {
+    pub id: String,
+    /// Path relative to the project root
+    pub file: String,
+    /// Absolute path
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub line: usize,
+    /// The line, trimmed
+    pub content: String,
+    /// The keyword when written as an annotation, e.g. "decision"
+    pub annotation: Option<String>,
+}
+
+/// Every record reference found under the project root
+#[derive(Debug, Default)]
+pub struct CodeIndex {
+    pub root: PathBuf,
+    pub refs: Vec<CodeRef>,
+}
+
+impl CodeIndex {
+    /// Scan the project around `docs_path` for IDs with a known record type
+    /// prefix, and for any ID written as an annotation
+    pub fn scan(docs_path: &Path) -> Result<Self> {
+        Self::scan_matching(docs_path, has_record_prefix)
+    }
+
+    /// Scan for plain mentions of IDs accepted by `matches`; annotations are
+    /// always kept so that unknown IDs can be reported. The docs directory
+    /// is skipped: its dg.toml and GOVERNANCE name records too, but they are
+    /// configuration, not code. So is anything git ignores, such as build
+    /// output.
+    pub fn scan_matching(docs_path: &Path, matches: impl Fn(&str) -> bool) -> Result<Self> {
+        let root = project_root(docs_path);
+        let docs_path = fs::canonicalize(docs_path).unwrap_or_else(|_| docs_path.to_path_buf());
+        let git_ignored = git_ignored(&root);
+        let mut refs = Vec::new();
+
+        for entry in WalkDir::new(&root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0
+                    || !(is_ignored(e.path())
+                        || e.path() == docs_path
+                        || git_ignored.contains(e.path()))
+            })
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !path.is_file() || !CODE_EXTENSIONS.contains(&ext) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            let file = path
+                .strip_prefix(&root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string();
+
+            for (index, line) in content.lines().enumerate() {
+                let annotated = annotation_ids(line);
+                let mut push = |id: &str, annotation: Option<String>| {
+                    refs.push(CodeRef {
+                        id: id.to_string(),
+                        file: file.clone(),
+                        path: path.to_path_buf(),
+                        line: index + 1,
+                        content: line.trim().to_string(),
+                        annotation,
+                    });
+                };
+                for (keyword, id) in &annotated {
+                    push(id, Some(keyword.clone()));
+                }
+                for m in bare_id_regex().find_iter(line) {
+                    if matches(m.as_str()) && !annotated.iter().any(|(_, id)| id == m.as_str()) {
+                        push(m.as_str(), None);
+                    }
+                }
+            }
+        }
+
+        Ok(CodeIndex { root, refs })
+    }
+
+    /// References to one record, in file and line order
+    pub fn for_record(&self, id: &str) -> Vec<&CodeRef> {
+        self.refs.iter().filter(|r| r.id == id).collect()
+    }
+
+    /// Targets of the record's `implemented_in` pseudo-edges
+    pub fn files_for(&self, id: &str) -> Vec<&str> {
+        let mut files: Vec<&str> = self
+            .for_record(id)
+            .into_iter()
+            .map(|r| r.file.as_str())
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    pub fn annotations(&self) -> impl Iterator<Item = &CodeRef> {
+        self.refs.iter().filter(|r| r.annotation.is_some())
+    }
+}
+
+/// `(keyword, id)` for each ID named by an annotation on the line
+pub fn annotation_ids(line: &str) -> Vec<(String, String)> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"@(?i:({}))\b[:\s]\s*((?:[A-Z][A-Z0-9]*-\d+)(?:\s*,\s*[A-Z][A-Z0-9]*-\d+)*)",
+            ANNOTATION_KEYWORDS.join("|")
+        ))
+        .unwrap()
+    });
+
+    let mut ids = Vec::new();
+    for cap in re.captures_iter(line) {
+        let keyword = cap[1].to_lowercase();
+        for id in cap[2].split(',') {
+            ids.push((keyword.clone(), id.trim().to_string()));
+        }
+    }
+    ids
+}
+
+/// Whether the ID starts with the prefix of a built-in or configured type
+fn has_record_prefix(id: &str) -> bool {
+    id.split('-')
+        .next()
+        .is_some_and(|prefix| RecordType::from_prefix(prefix).is_some())
+}
+
+/// The nearest directory above the docs that looks like a project root
+pub fn project_root(docs_path: &Path) -> PathBuf {
+    let docs_path = fs::canonicalize(docs_path).unwrap_or_else(|_| docs_path.to_path_buf());
+    let mut current = docs_path.clone();
+    loop {
+        if current.join(".git").exists()
+            || current.join("Cargo.toml").exists()
+            || current.join("package.json").exists()
+        {
+            return current;
+        }
+        if !current.pop() {
+            return docs_path.parent().unwrap_or(&docs_path).to_path_buf();
+        }
+    }
+}
+
+/// Files and directories git ignores under the root; empty outside a
+/// repository
+fn git_ignored(root: &Path) -> HashSet<PathBuf> {
+    let output = Command::new("git")
+        .args([
+            "ls-files",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+        ])
+        .current_dir(root)
+        .output();
+    match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .map(|line| root.join(line.trim_end_matches('/')))
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
+fn is_ignored(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    name.starts_with('.')
+        || name == "node_modules"
+        || name == "target"
+        || name == "dist"
+        || name == "build"
+        || name == "vendor"
+        || name == "__pycache__"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_ids() {
+        assert_eq!(
+            annotation_ids("// @decision ADR-003"),
+            vec![("decision".to_string(), "ADR-003".to_string())]
+        );
+        assert_eq!(
+            annotation_ids("# @Implements: POL-001, POL-002 (retention)"),
+            vec![
+                ("implements".to_string(), "POL-001".to_string()),
+                ("implements".to_string(), "POL-002".to_string()),
+            ]
+        );
+        assert!(annotation_ids("// see ADR-003").is_empty());
+        assert!(annotation_ids("// @decisions ADR-003").is_empty());
+    }
+
+    #[test]
+    fn test_scan_keeps_annotations_and_known_prefixes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".git")).unwrap();
+        fs::create_dir_all(temp.path().join("docs/decisions")).unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(
+            temp.path().join("docs/dg.toml"),
+            "[governance]\n\"src/**\" = [\"ADR-003\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join("src/cache.rs"),
+            "// @decision ADR-003\nfn cache() {} // see DEC-001, not UTF-8\n// @decision XYZ-9\n",
+        )
+        .unwrap();
+
+        let index = CodeIndex::scan(&temp.path().join("docs")).unwrap();
+        let found: Vec<_> = index
+            .refs
+            .iter()
+            .map(|r| (r.id.as_str(), r.line, r.annotation.is_some()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("ADR-003", 1, true),
+                ("DEC-001", 2, false),
+                ("XYZ-9", 3, true)
+            ]
+        );
+        assert_eq!(index.files_for("ADR-003"), vec!["src/cache.rs"]);
+    }
+}
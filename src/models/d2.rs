@@ -1,4 +1,4 @@
-use crate::models::{Graph, Record};
+use crate::models::{Graph, LinkType, Record};
 use anyhow::{anyhow, Result};
 use std::collections::HashSet;
 use std::io::Write;
@@ -109,7 +109,7 @@ pub fn graph_to_d2(graph: &Graph, subset: Option<&HashSet<String>>) -> String {
     d2.push('\n');
 
     // Edges
-    for edge in &graph.edges {
+    for edge in graph.edges() {
         if let Some(subset) = subset {
             if !subset.contains(&edge.from) || !subset.contains(&edge.to) {
                 continue;
@@ -124,6 +124,7 @@ pub fn graph_to_d2(graph: &Graph, subset: Option<&HashSet<String>>) -> String {
             "conflicts_with" => " { style: { stroke: \"orange\"; stroke-width: 2 } }",
             "refines" => " { style: { stroke: \"purple\" } }",
             "implements" => " { style: { stroke: \"teal\" } }",
+            "mentions" => " { style: { stroke: \"gray\"; stroke-dash: 2; opacity: 0.6 } }",
             _ => "",
         };
 
@@ -131,7 +132,7 @@ pub fn graph_to_d2(graph: &Graph, subset: Option<&HashSet<String>>) -> String {
             "{} -> {}: {}{}\n",
             escape_d2_id(&edge.from),
             escape_d2_id(&edge.to),
-            edge.link_type,
+            LinkType::label_for(&edge.link_type),
             style
         ));
     }
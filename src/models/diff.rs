@@ -0,0 +1,433 @@
+//! Semantic comparison of two versions of the graph: which records were
+//! added or removed, what changed in each record's frontmatter and sections,
+//! and how the links between records moved.
+
+use super::{Graph, Record};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordSummary {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub status: String,
+}
+
+impl RecordSummary {
+    fn of(record: &Record) -> Self {
+        RecordSummary {
+            id: record.id().to_string(),
+            title: record.title().to_string(),
+            record_type: record.record_type().to_string(),
+            status: record.status().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct LinkChange {
+    pub from: String,
+    pub link_type: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionStatus {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionChange {
+    /// Heading text, or empty for the text before the first heading
+    pub heading: String,
+    pub change: SectionStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordDiff {
+    pub id: String,
+    pub title: String,
+    pub fields: Vec<FieldChange>,
+    pub tags_added: Vec<String>,
+    pub tags_removed: Vec<String>,
+    pub links_added: Vec<LinkChange>,
+    pub links_removed: Vec<LinkChange>,
+    pub sections: Vec<SectionChange>,
+}
+
+impl RecordDiff {
+    /// What changed from one version of a record to the next
+    pub fn compare(old: &Record, new: &Record) -> Self {
+        compare_records(old, new)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.tags_added.is_empty()
+            && self.tags_removed.is_empty()
+            && self.links_added.is_empty()
+            && self.links_removed.is_empty()
+            && self.sections.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GraphDiff {
+    pub added: Vec<RecordSummary>,
+    pub removed: Vec<RecordSummary>,
+    pub changed: Vec<RecordDiff>,
+    /// Edges between existing records, including body mentions
+    pub edges_added: Vec<LinkChange>,
+    pub edges_removed: Vec<LinkChange>,
+    /// Frontmatter links whose target no longer exists
+    pub broken_links: Vec<LinkChange>,
+    /// Records that had links before and have none now
+    pub newly_orphaned: Vec<String>,
+}
+
+impl GraphDiff {
+    pub fn compare(before: &Graph, after: &Graph) -> Self {
+        let mut diff = GraphDiff::default();
+
+        let mut ids: BTreeSet<&String> = before.records.keys().collect();
+        ids.extend(after.records.keys());
+        for id in ids {
+            match (before.get(id), after.get(id)) {
+                (None, Some(record)) => diff.added.push(RecordSummary::of(record)),
+                (Some(record), None) => diff.removed.push(RecordSummary::of(record)),
+                (Some(old), Some(new)) => {
+                    let record_diff = compare_records(old, new);
+                    if !record_diff.is_empty() {
+                        diff.changed.push(record_diff);
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+
+        let old_edges = edge_set(before);
+        let new_edges = edge_set(after);
+        diff.edges_added = new_edges.difference(&old_edges).cloned().collect();
+        diff.edges_removed = old_edges.difference(&new_edges).cloned().collect();
+        diff.edges_added.sort();
+        diff.edges_removed.sort();
+
+        let old_broken = broken_links(before);
+        diff.broken_links = broken_links(after)
+            .into_iter()
+            .filter(|l| !old_broken.contains(l))
+            .collect();
+        diff.broken_links.sort();
+
+        let linked = |edges: &HashSet<LinkChange>, id: &str| {
+            edges.iter().any(|e| e.from == id || e.to == id)
+        };
+        diff.newly_orphaned = after
+            .records
+            .keys()
+            .filter(|id| linked(&old_edges, id) && !linked(&new_edges, id))
+            .cloned()
+            .collect();
+        diff.newly_orphaned.sort();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.edges_added.is_empty()
+            && self.edges_removed.is_empty()
+    }
+}
+
+fn compare_records(old: &Record, new: &Record) -> RecordDiff {
+    let mut fields = Vec::new();
+    let mut field = |name: &str, before: String, after: String| {
+        if before != after {
+            fields.push(FieldChange {
+                field: name.to_string(),
+                before: Some(before),
+                after: Some(after),
+            });
+        }
+    };
+    field("id", old.id().to_string(), new.id().to_string());
+    field("title", old.title().to_string(), new.title().to_string());
+    field(
+        "type",
+        old.record_type().to_string(),
+        new.record_type().to_string(),
+    );
+    field("status", old.status().to_string(), new.status().to_string());
+    field(
+        "core",
+        old.frontmatter.core.to_string(),
+        new.frontmatter.core.to_string(),
+    );
+    field(
+        "authors",
+        old.frontmatter.authors.join(", "),
+        new.frontmatter.authors.join(", "),
+    );
+    field(
+        "created",
+        old.frontmatter.created.to_string(),
+        new.frontmatter.created.to_string(),
+    );
+
+    // Custom fields, which may come and go
+    let mut extra: BTreeSet<&String> = old.frontmatter.extra.keys().collect();
+    extra.extend(new.frontmatter.extra.keys());
+    for name in extra {
+        // Mappings compare regardless of key order
+        let before = old.frontmatter.extra.get(name);
+        let after = new.frontmatter.extra.get(name);
+        if before != after {
+            fields.push(FieldChange {
+                field: name.clone(),
+                before: before.map(yaml_text),
+                after: after.map(yaml_text),
+            });
+        }
+    }
+
+    let (tags_added, tags_removed) = added_removed(&old.frontmatter.tags, &new.frontmatter.tags);
+
+    let links = |record: &Record| -> Vec<LinkChange> {
+        record
+            .frontmatter
+            .links
+            .all_links()
+            .into_iter()
+            .map(|(link_type, to)| LinkChange {
+                from: record.id().to_string(),
+                link_type: link_type.to_string(),
+                to: to.to_string(),
+            })
+            .collect()
+    };
+    let (links_added, links_removed) = added_removed(&links(old), &links(new));
+
+    RecordDiff {
+        id: new.id().to_string(),
+        title: new.title().to_string(),
+        fields,
+        tags_added,
+        tags_removed,
+        links_added,
+        links_removed,
+        sections: compare_sections(&old.content, &new.content),
+    }
+}
+
+/// Items only in `new`, and items only in `old`, each sorted
+fn added_removed<T: Clone + Ord>(old: &[T], new: &[T]) -> (Vec<T>, Vec<T>) {
+    let old: BTreeSet<&T> = old.iter().collect();
+    let new: BTreeSet<&T> = new.iter().collect();
+    (
+        new.difference(&old).map(|t| (*t).clone()).collect(),
+        old.difference(&new).map(|t| (*t).clone()).collect(),
+    )
+}
+
+fn yaml_text(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
+
+/// Section bodies in document order, keyed by heading; a repeated heading
+/// gets " (2)", " (3)"...
+pub(crate) fn sections(content: &str) -> Vec<(String, String)> {
+    let mut sections = Vec::new();
+    let mut heading = String::new();
+    let mut body = String::new();
+    let mut in_code = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+        }
+        if !in_code && trimmed.starts_with('#') {
+            push_section(&mut sections, &heading, &body);
+            heading = trimmed.trim_start_matches('#').trim().to_string();
+            body.clear();
+        } else {
+            body.push_str(line.trim_end());
+            body.push('\n');
+        }
+    }
+    push_section(&mut sections, &heading, &body);
+    sections
+}
+
+fn push_section(sections: &mut Vec<(String, String)>, heading: &str, body: &str) {
+    let body = body.trim();
+    if heading.is_empty() && body.is_empty() {
+        return;
+    }
+    let mut key = heading.to_string();
+    let mut n = 1;
+    while sections.iter().any(|(k, _)| *k == key) {
+        n += 1;
+        key = format!("{} ({})", heading, n);
+    }
+    sections.push((key, body.to_string()));
+}
+
+/// Changes in the order the new version reads, then what was dropped
+fn compare_sections(old: &str, new: &str) -> Vec<SectionChange> {
+    let old = sections(old);
+    let new = sections(new);
+    let find = |sections: &[(String, String)], heading: &str| {
+        sections
+            .iter()
+            .find(|(h, _)| h == heading)
+            .map(|(_, body)| body.clone())
+    };
+
+    let mut changes = Vec::new();
+    for (heading, body) in &new {
+        let change = match find(&old, heading) {
+            None => SectionStatus::Added,
+            Some(before) if before != *body => SectionStatus::Modified,
+            Some(_) => continue,
+        };
+        changes.push(SectionChange {
+            heading: heading.clone(),
+            change,
+        });
+    }
+    for (heading, _) in &old {
+        if find(&new, heading).is_none() {
+            changes.push(SectionChange {
+                heading: heading.clone(),
+                change: SectionStatus::Removed,
+            });
+        }
+    }
+    changes
+}
+
+fn edge_set(graph: &Graph) -> HashSet<LinkChange> {
+    graph
+        .edges()
+        .iter()
+        .filter(|e| graph.get(&e.to).is_some())
+        .map(|e| LinkChange {
+            from: e.from.clone(),
+            link_type: e.link_type.clone(),
+            to: e.to.clone(),
+        })
+        .collect()
+}
+
+fn broken_links(graph: &Graph) -> HashSet<LinkChange> {
+    graph
+        .all_records()
+        .flat_map(|record| {
+            record
+                .frontmatter
+                .links
+                .all_links()
+                .into_iter()
+                .filter(|(_, to)| graph.get(to).is_none())
+                .map(|(link_type, to)| LinkChange {
+                    from: record.id().to_string(),
+                    link_type: link_type.to_string(),
+                    to: to.to_string(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn graph(records: &[(&str, &str, &str, &str)]) -> Graph {
+        let records: HashMap<String, Record> = records
+            .iter()
+            .map(|(id, status, links, body)| {
+                let content = format!(
+                    "---\ntype: decision\nid: {}\ntitle: \"{}\"\nstatus: {}\ncreated: 2024-01-01\nupdated: 2024-01-01\nauthors: []\ntags: []\nlinks: {}\n---\n\n{}",
+                    id, id, status, links, body
+                );
+                let record =
+                    Record::parse_content(&content, format!("{}.md", id).into()).unwrap();
+                (id.to_string(), record)
+            })
+            .collect();
+        Graph::from_records(records)
+    }
+
+    #[test]
+    fn test_compare_records_and_links() {
+        let before = graph(&[
+            (
+                "DEC-001",
+                "proposed",
+                "{}",
+                "## Context\n\nOld\n\n## Gone\n\nx\n",
+            ),
+            ("DEC-002", "accepted", "{depends_on: [DEC-001]}", ""),
+            ("DEC-003", "accepted", "{}", ""),
+        ]);
+        let after = graph(&[
+            (
+                "DEC-001",
+                "accepted",
+                "{}",
+                "## Context\n\nNew\n\n```sh\n# not a heading\n```\n\n## Consequences\n\ny\n",
+            ),
+            ("DEC-002", "accepted", "{depends_on: [DEC-009]}", ""),
+            ("DEC-004", "proposed", "{relates_to: [DEC-001]}", ""),
+        ]);
+        let diff = GraphDiff::compare(&before, &after);
+
+        assert_eq!(diff.added[0].id, "DEC-004");
+        assert_eq!(diff.removed[0].id, "DEC-003");
+
+        let dec1 = diff.changed.iter().find(|c| c.id == "DEC-001").unwrap();
+        assert_eq!(dec1.fields[0].field, "status");
+        assert_eq!(dec1.fields[0].after.as_deref(), Some("accepted"));
+        let sections: Vec<(&str, SectionStatus)> = dec1
+            .sections
+            .iter()
+            .map(|s| (s.heading.as_str(), s.change))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("Context", SectionStatus::Modified),
+                ("Consequences", SectionStatus::Added),
+                ("Gone", SectionStatus::Removed),
+            ]
+        );
+
+        let dec2 = diff.changed.iter().find(|c| c.id == "DEC-002").unwrap();
+        assert_eq!(dec2.links_added[0].to, "DEC-009");
+        assert_eq!(dec2.links_removed[0].to, "DEC-001");
+        assert_eq!(diff.broken_links[0].to, "DEC-009");
+        assert_eq!(diff.edges_added[0].from, "DEC-004");
+        // DEC-002 lost its only working link
+        assert_eq!(diff.newly_orphaned, vec!["DEC-002"]);
+    }
+}
@@ -0,0 +1,293 @@
+//! Frontmatter syntaxes: `---` delimited YAML and `+++` delimited TOML
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Key order used when writing frontmatter; other keys follow in map order
+pub const KEY_ORDER: [&str; 10] = [
+    "type", "id", "title", "status", "created", "updated", "authors", "tags", "core", "links",
+];
+
+/// Syntax used for a record's frontmatter block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontmatterFormat {
+    #[default]
+    Yaml,
+    Toml,
+}
+
+impl FrontmatterFormat {
+    pub fn from_str(s: &str) -> Option<FrontmatterFormat> {
+        match s.to_lowercase().as_str() {
+            "yaml" | "yml" => Some(FrontmatterFormat::Yaml),
+            "toml" => Some(FrontmatterFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Line that opens and closes the frontmatter block
+    pub fn delimiter(&self) -> &'static str {
+        match self {
+            FrontmatterFormat::Yaml => "---",
+            FrontmatterFormat::Toml => "+++",
+        }
+    }
+
+    /// Detect the format from the opening delimiter of a file
+    pub fn detect(content: &str) -> Option<FrontmatterFormat> {
+        let content = content.trim_start();
+        if content.starts_with("---") {
+            Some(FrontmatterFormat::Yaml)
+        } else if content.starts_with("+++") {
+            Some(FrontmatterFormat::Toml)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for FrontmatterFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrontmatterFormat::Yaml => write!(f, "yaml"),
+            FrontmatterFormat::Toml => write!(f, "toml"),
+        }
+    }
+}
+
+/// Split a file into its frontmatter format, raw frontmatter text and body.
+/// Line endings must already be normalized to `\n`.
+pub fn split(content: &str) -> Result<(FrontmatterFormat, &str, &str)> {
+    let content = content.trim_start(); // Allow leading whitespace
+    let format = FrontmatterFormat::detect(content).ok_or_else(|| {
+        anyhow!("Missing frontmatter: file must start with '---' (YAML) or '+++' (TOML)")
+    })?;
+    let delimiter = format.delimiter();
+
+    // Skip the opening delimiter and find the next one on its own line
+    let after_opening = &content[3..];
+    let after_opening = after_opening.strip_prefix('\n').unwrap_or(after_opening);
+
+    let closing = format!("\n{}", delimiter);
+    let closing_pos = after_opening
+        .find(&closing)
+        .ok_or_else(|| anyhow!("Unterminated frontmatter: missing closing '{}'", delimiter))?;
+
+    let raw = &after_opening[..closing_pos];
+    let after_closing = &after_opening[closing_pos + closing.len()..];
+
+    // Body is everything after the closing delimiter and its newline
+    let body = after_closing.strip_prefix('\n').unwrap_or(after_closing);
+
+    Ok((format, raw, body))
+}
+
+/// Parse raw frontmatter text into a YAML value tree, whichever syntax it uses.
+/// TOML dates and datetimes become strings so both syntaxes deserialize alike.
+pub fn parse_value(format: FrontmatterFormat, raw: &str) -> Result<serde_yaml::Value> {
+    match format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str(raw).map_err(|e| {
+            anyhow!(
+                "Invalid YAML in frontmatter: {}",
+                e.to_string().lines().next().unwrap_or("unknown error")
+            )
+        }),
+        FrontmatterFormat::Toml => {
+            let table: toml::Table = toml::from_str(raw).map_err(|e| {
+                anyhow!(
+                    "Invalid TOML in frontmatter: {}",
+                    e.message().lines().next().unwrap_or("unknown error")
+                )
+            })?;
+            Ok(toml_to_yaml(toml::Value::Table(table)))
+        }
+    }
+}
+
+fn toml_to_yaml(value: toml::Value) -> serde_yaml::Value {
+    match value {
+        toml::Value::String(s) => serde_yaml::Value::String(s),
+        toml::Value::Integer(i) => serde_yaml::Value::Number(i.into()),
+        toml::Value::Float(f) => serde_yaml::Value::Number(f.into()),
+        toml::Value::Boolean(b) => serde_yaml::Value::Bool(b),
+        toml::Value::Datetime(dt) => serde_yaml::Value::String(dt.to_string()),
+        toml::Value::Array(items) => {
+            serde_yaml::Value::Sequence(items.into_iter().map(toml_to_yaml).collect())
+        }
+        toml::Value::Table(table) => serde_yaml::Value::Mapping(
+            table
+                .into_iter()
+                .map(|(k, v)| (serde_yaml::Value::String(k), toml_to_yaml(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Convert a YAML value to TOML. Nulls have no TOML equivalent and are
+/// dropped; `YYYY-MM-DD` strings become native TOML dates.
+fn yaml_to_toml(value: &serde_yaml::Value) -> Option<toml::Value> {
+    match value {
+        serde_yaml::Value::Null => None,
+        serde_yaml::Value::Bool(b) => Some(toml::Value::Boolean(*b)),
+        serde_yaml::Value::Number(n) => Some(match n.as_i64() {
+            Some(i) => toml::Value::Integer(i),
+            None => toml::Value::Float(n.as_f64().unwrap_or_default()),
+        }),
+        serde_yaml::Value::String(s) => {
+            let is_date = s.len() == 10 && NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok();
+            Some(match s.parse::<toml::value::Datetime>() {
+                Ok(date) if is_date => toml::Value::Datetime(date),
+                _ => toml::Value::String(s.clone()),
+            })
+        }
+        serde_yaml::Value::Sequence(items) => Some(toml::Value::Array(
+            items.iter().filter_map(yaml_to_toml).collect(),
+        )),
+        serde_yaml::Value::Mapping(map) => Some(toml::Value::Table(
+            map.iter()
+                .filter_map(|(k, v)| Some((yaml_key(k)?, yaml_to_toml(v)?)))
+                .collect(),
+        )),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_toml(&tagged.value),
+    }
+}
+
+fn yaml_key(key: &serde_yaml::Value) -> Option<String> {
+    match key {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Render a frontmatter value tree as TOML (without delimiters).
+/// Scalars and arrays come first in `KEY_ORDER`, followed by tables such as
+/// `[links]`, which TOML requires to come last.
+pub fn to_toml(value: &serde_yaml::Value) -> Result<String> {
+    let map = match value {
+        serde_yaml::Value::Mapping(map) => map,
+        _ => return Err(anyhow!("Frontmatter must be a mapping")),
+    };
+
+    let mut keys: Vec<String> = KEY_ORDER
+        .iter()
+        .filter(|k| map.contains_key(**k))
+        .map(|k| k.to_string())
+        .collect();
+    keys.extend(
+        map.keys()
+            .filter_map(yaml_key)
+            .filter(|k| !KEY_ORDER.contains(&k.as_str())),
+    );
+
+    let mut lines = Vec::new();
+    let mut tables = Vec::new();
+    for key in keys {
+        match map.get(key.as_str()).and_then(yaml_to_toml) {
+            Some(toml::Value::Table(table)) => tables.push((key, table)),
+            Some(value) => lines.push(format!("{} = {}", toml_key(&key), inline(&value))),
+            None => {}
+        }
+    }
+
+    for (key, table) in tables {
+        lines.push(String::new());
+        lines.push(format!("[{}]", toml_key(&key)));
+        for (k, v) in table {
+            lines.push(format!("{} = {}", toml_key(&k), inline(&v)));
+        }
+    }
+
+    let mut toml = lines.join("\n");
+    toml.push('\n');
+    Ok(toml)
+}
+
+/// Render a value inline (`Value`'s own Display spells dates as private tables)
+fn inline(value: &toml::Value) -> String {
+    match value {
+        toml::Value::Datetime(dt) => dt.to_string(),
+        toml::Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(inline).collect::<Vec<_>>().join(", ")
+        ),
+        toml::Value::Table(table) if table.is_empty() => "{}".to_string(),
+        toml::Value::Table(table) => format!(
+            "{{ {} }}",
+            table
+                .iter()
+                .map(|(k, v)| format!("{} = {}", toml_key(k), inline(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => other.to_string(),
+    }
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_yaml_and_toml() {
+        let (format, raw, body) = split("---\nid: DEC-001\n---\n\n# Body\n").unwrap();
+        assert_eq!(format, FrontmatterFormat::Yaml);
+        assert_eq!(raw, "id: DEC-001");
+        assert_eq!(body, "\n# Body\n");
+
+        let (format, raw, body) = split("+++\nid = \"DEC-001\"\n+++\n# Body\n").unwrap();
+        assert_eq!(format, FrontmatterFormat::Toml);
+        assert_eq!(raw, "id = \"DEC-001\"");
+        assert_eq!(body, "# Body\n");
+    }
+
+    #[test]
+    fn test_split_errors() {
+        assert!(split("# No frontmatter").is_err());
+        let err = split("+++\nid = \"DEC-001\"\n").unwrap_err();
+        assert!(err.to_string().contains("'+++'"));
+    }
+
+    #[test]
+    fn test_toml_dates_become_strings() {
+        let value = parse_value(
+            FrontmatterFormat::Toml,
+            "created = 2024-01-15\ntags = [\"a\"]\n[links]\ndepends_on = [\"DEC-002\"]",
+        )
+        .unwrap();
+        assert_eq!(value["created"].as_str(), Some("2024-01-15"));
+        assert_eq!(value["links"]["depends_on"][0].as_str(), Some("DEC-002"));
+    }
+
+    #[test]
+    fn test_to_toml_orders_keys_and_tables_last() {
+        let value: serde_yaml::Value = serde_yaml::from_str(
+            "links:\n  depends_on: [DEC-002]\nseverity: high\ntitle: \"Auth: tokens\"\nid: DEC-001\ncreated: 2024-01-15\nowner: null\n",
+        )
+        .unwrap();
+        let toml = to_toml(&value).unwrap();
+        assert_eq!(
+            toml,
+            "id = \"DEC-001\"\ntitle = \"Auth: tokens\"\ncreated = 2024-01-15\nseverity = \"high\"\n\n[links]\ndepends_on = [\"DEC-002\"]\n"
+        );
+
+        let back = parse_value(FrontmatterFormat::Toml, &toml).unwrap();
+        assert_eq!(back["title"].as_str(), Some("Auth: tokens"));
+        assert_eq!(back["created"].as_str(), Some("2024-01-15"));
+    }
+}
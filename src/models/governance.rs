@@ -0,0 +1,196 @@
+//! Code paths governed by records, declared CODEOWNERS-style either under
+//! `[governance]` in dg.toml or in a `GOVERNANCE` file next to it:
+//!
+//! ```text
+//! # pattern            records
+//! src/payments/**      ADR-005 POL-001
+//! *.sql                ADR-002
+//! /infra/              ADR-007
+//! ```
+//!
+//! Patterns are relative to the project root. As in CODEOWNERS, a pattern
+//! without a slash matches at any depth, a leading `/` anchors it to the
+//! root, and a trailing `/` covers everything below a directory. Every
+//! matching rule applies, not just the last one.
+
+use crate::serve::config::DgConfig;
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// One pattern and the records it names
+#[derive(Debug, Clone)]
+pub struct GovernanceRule {
+    pub pattern: String,
+    pub records: Vec<String>,
+    /// Where the rule was declared, e.g. "GOVERNANCE:3" or "dg.toml"
+    pub source: String,
+    regex: Regex,
+}
+
+impl GovernanceRule {
+    pub fn new(pattern: &str, records: Vec<String>, source: String) -> Result<Self> {
+        Ok(GovernanceRule {
+            pattern: pattern.to_string(),
+            records,
+            source,
+            regex: glob_regex(pattern)?,
+        })
+    }
+
+    /// Whether the rule covers a path relative to the project root
+    pub fn matches(&self, path: &str) -> bool {
+        self.regex.is_match(path.trim_start_matches("./"))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Governance {
+    pub rules: Vec<GovernanceRule>,
+}
+
+impl Governance {
+    /// Rules from dg.toml followed by those in docs/GOVERNANCE
+    pub fn load(docs_path: &Path) -> Result<Self> {
+        let config = DgConfig::load(docs_path).unwrap_or_default();
+        let mut rules = Vec::new();
+        for (pattern, records) in &config.governance {
+            rules.push(GovernanceRule::new(
+                pattern,
+                records.clone(),
+                "dg.toml".to_string(),
+            )?);
+        }
+
+        let file = docs_path.join("GOVERNANCE");
+        if file.exists() {
+            rules.extend(parse_governance_file(&fs::read_to_string(&file)?)?);
+        }
+        Ok(Governance { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rules that cover the path
+    pub fn rules_for(&self, path: &str) -> Vec<&GovernanceRule> {
+        self.rules.iter().filter(|r| r.matches(path)).collect()
+    }
+
+    /// Records governing the path, in rule order without repeats
+    pub fn records_for(&self, path: &str) -> Vec<&str> {
+        let mut records: Vec<&str> = Vec::new();
+        for rule in self.rules_for(path) {
+            for id in &rule.records {
+                if !records.contains(&id.as_str()) {
+                    records.push(id);
+                }
+            }
+        }
+        records
+    }
+}
+
+/// Parse `pattern ID ID...` lines; `#` starts a comment
+pub fn parse_governance_file(content: &str) -> Result<Vec<GovernanceRule>> {
+    let mut rules = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let pattern = parts.next().unwrap_or_default();
+        let records: Vec<String> = parts
+            .map(|id| id.trim_matches(',').to_string())
+            .filter(|id| !id.is_empty())
+            .collect();
+        if records.is_empty() {
+            return Err(anyhow!(
+                "GOVERNANCE:{}: '{}' names no records",
+                index + 1,
+                pattern
+            ));
+        }
+        rules.push(GovernanceRule::new(
+            pattern,
+            records,
+            format!("GOVERNANCE:{}", index + 1),
+        )?);
+    }
+    Ok(rules)
+}
+
+/// Translate a CODEOWNERS-style glob into an anchored regex
+fn glob_regex(pattern: &str) -> Result<Regex> {
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let directory = pattern.ends_with('/');
+    let glob = pattern.trim_start_matches('/').trim_end_matches('/');
+
+    let mut re = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    // "**/" is zero or more directories
+                    re.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    re.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    // A pattern names a file, or a directory and everything below it
+    re.push_str(if directory { "/.*$" } else { "(?:/.*)?$" });
+    Regex::new(&re).map_err(|e| anyhow!("Invalid governance pattern '{}': {}", pattern, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str) -> GovernanceRule {
+        GovernanceRule::new(pattern, vec!["ADR-001".to_string()], String::new()).unwrap()
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        assert!(rule("src/payments/**").matches("src/payments/stripe/charge.rs"));
+        assert!(!rule("src/payments/**").matches("src/payments.rs"));
+        assert!(rule("src/payments").matches("src/payments/charge.rs"));
+        assert!(rule("*.sql").matches("db/migrations/001_init.sql"));
+        assert!(!rule("*.sql").matches("db/schema.sqlite"));
+        assert!(rule("/infra/").matches("infra/main.tf"));
+        assert!(!rule("/infra/").matches("services/infra/main.tf"));
+        assert!(rule("infra/").matches("infra/main.tf"));
+        assert!(rule("src/**/handler.rs").matches("src/handler.rs"));
+        assert!(rule("src/**/handler.rs").matches("src/api/v1/handler.rs"));
+        assert!(rule("Dockerfile").matches("services/api/Dockerfile"));
+    }
+
+    #[test]
+    fn test_parse_governance_file() {
+        let rules = parse_governance_file(
+            "# payments\nsrc/payments/** ADR-005, POL-001\n\n*.sql ADR-002 # schema\n",
+        )
+        .unwrap();
+        let governance = Governance { rules };
+        assert_eq!(
+            governance.records_for("src/payments/db/schema.sql"),
+            vec!["ADR-005", "POL-001", "ADR-002"]
+        );
+        assert_eq!(governance.rules[1].source, "GOVERNANCE:4");
+        assert!(parse_governance_file("src/**\n").is_err());
+    }
+}
@@ -1,9 +1,14 @@
-use crate::models::{Record, RecordType};
+use crate::git::GitHistory;
+use crate::models::cache::RecordCache;
+use crate::models::references::{self, MENTIONS};
+use crate::models::search::{SearchHit, SearchIndex, Snippet};
+use crate::models::{LinkMeta, LinkType, Record, RecordType, Status};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::Path;
+use std::sync::OnceLock;
 use walkdir::WalkDir;
 
 // Re-export validation from shared module
@@ -14,6 +19,8 @@ pub use super::validation::{validate_graph, ValidationError, ValidationOptions};
 pub struct DependencyPath {
     pub nodes: Vec<String>,
     pub link_types: Vec<String>,
+    /// Metadata of each link along the path, parallel to `link_types`
+    pub link_meta: Vec<LinkMeta>,
 }
 
 impl DependencyPath {
@@ -21,18 +28,40 @@ impl DependencyPath {
         Self {
             nodes: vec![start],
             link_types: Vec::new(),
+            link_meta: Vec::new(),
         }
     }
 
-    pub fn extend(&self, next_id: String, link_type: String) -> Self {
+    pub fn extend(&self, next_id: String, edge: &GraphEdge) -> Self {
         let mut nodes = self.nodes.clone();
         let mut link_types = self.link_types.clone();
+        let mut link_meta = self.link_meta.clone();
         nodes.push(next_id);
-        link_types.push(link_type);
-        Self { nodes, link_types }
+        link_types.push(edge.link_type.clone());
+        link_meta.push(edge.meta.clone());
+        Self {
+            nodes,
+            link_types,
+            link_meta,
+        }
     }
 }
 
+/// A route between two records, following links in either direction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordPath {
+    pub nodes: Vec<String>,
+    /// The link joining each pair of consecutive nodes, as declared
+    pub links: Vec<PathLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct PathLink {
+    pub from: String,
+    pub to: String,
+    pub link_type: String,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct GraphNode {
@@ -46,15 +75,34 @@ pub struct GraphEdge {
     pub from: String,
     pub to: String,
     pub link_type: String,
+    /// Note, date and strength from the extended link form
+    pub meta: LinkMeta,
 }
 
+/// Edge indices grouped by record ID, then link type
+type Adjacency = HashMap<String, HashMap<String, Vec<usize>>>;
+
 #[derive(Debug)]
 pub struct Graph {
     pub records: HashMap<String, Record>,
-    pub edges: Vec<GraphEdge>,
+    edges: Vec<GraphEdge>,
+    /// Edges leaving each record, as indices into `edges`
+    outgoing: Adjacency,
+    /// Edges arriving at each record, as indices into `edges`
+    incoming: Adjacency,
+    /// Full-text index, built on first search
+    search: OnceLock<SearchIndex>,
     docs_dir: Option<std::path::PathBuf>,
 }
 
+/// A record matching a full-text search
+pub struct SearchResult<'a> {
+    pub record: &'a Record,
+    pub score: f64,
+    /// Body text around the matches, when the body matched
+    pub snippet: Option<Snippet>,
+}
+
 /// Index file structure for fast metadata access
 #[derive(Debug, Serialize, Deserialize)]
 struct IndexFile {
@@ -88,54 +136,281 @@ struct IndexStats {
     edges: usize,
 }
 
+/// Edges for a record's frontmatter links, plus `mentions` edges for `[[ID]]`
+/// references (and bare IDs, if enabled) in its body. Only references to
+/// existing records become edges.
+fn record_edges(record: &Record, records: &HashMap<String, Record>) -> Vec<GraphEdge> {
+    let id = record.id();
+    let mut edges: Vec<GraphEdge> = record
+        .frontmatter
+        .links
+        .all_targets()
+        .into_iter()
+        .map(|(link_type, target)| GraphEdge {
+            from: id.to_string(),
+            to: target.id.clone(),
+            link_type: link_type.to_string(),
+            meta: target.meta.clone(),
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    for reference in references::find_references(&record.content, references::bare_ids_enabled()) {
+        if reference.id != id
+            && records.contains_key(&reference.id)
+            && seen.insert(reference.id.clone())
+        {
+            edges.push(GraphEdge {
+                from: id.to_string(),
+                to: reference.id,
+                link_type: MENTIONS.to_string(),
+                meta: LinkMeta::default(),
+            });
+        }
+    }
+    edges
+}
+
+/// Drop edge `slot` from a record's adjacency entry, and the entry if empty
+fn unindex(adjacency: &mut Adjacency, id: &str, link_type: &str, slot: usize) {
+    let Some(by_type) = adjacency.get_mut(id) else {
+        return;
+    };
+    if let Some(slots) = by_type.get_mut(link_type) {
+        slots.retain(|&s| s != slot);
+        if slots.is_empty() {
+            by_type.remove(link_type);
+        }
+    }
+    if by_type.is_empty() {
+        adjacency.remove(id);
+    }
+}
+
+/// Add edge `slot` to a record's adjacency entry, keeping it in edge order
+/// as `index_edges` builds it
+fn index(adjacency: &mut Adjacency, id: &str, link_type: &str, slot: usize) {
+    let slots = adjacency
+        .entry(id.to_string())
+        .or_default()
+        .entry(link_type.to_string())
+        .or_default();
+    if let Err(at) = slots.binary_search(&slot) {
+        slots.insert(at, slot);
+    }
+}
+
+/// Move an edge in a record's adjacency entry to its new slot
+fn reindex(adjacency: &mut Adjacency, id: &str, link_type: &str, from: usize, to: usize) {
+    unindex(adjacency, id, link_type, from);
+    index(adjacency, id, link_type, to);
+}
+
+/// Mutable access to a record; the graph's edges are re-derived from the
+/// record's links and body when this is dropped
+pub struct RecordMut<'a> {
+    graph: &'a mut Graph,
+    id: String,
+}
+
+impl std::ops::Deref for RecordMut<'_> {
+    type Target = Record;
+
+    fn deref(&self) -> &Record {
+        &self.graph.records[&self.id]
+    }
+}
+
+impl std::ops::DerefMut for RecordMut<'_> {
+    fn deref_mut(&mut self) -> &mut Record {
+        self.graph.records.get_mut(&self.id).unwrap()
+    }
+}
+
+impl Drop for RecordMut<'_> {
+    fn drop(&mut self) {
+        self.graph.record_changed(&self.id);
+    }
+}
+
 impl Graph {
+    /// Load all records, re-parsing only files that changed since the last load
     pub fn load(docs_dir: &Path) -> Result<Graph> {
-        let decisions_dir = docs_dir.join("decisions");
+        let mut cache = RecordCache::open(docs_dir);
+        let graph = Self::load_records(docs_dir, &mut cache)?;
+        // The cache is only an optimization; a read-only docs dir still loads
+        let _ = cache.save();
+        Ok(graph)
+    }
+
+    /// The graph as committed at `at`: a revision, or a YYYY-MM-DD date for
+    /// the last commit on or before that day. Records are read from git
+    /// objects, so the working tree and the cache are left alone.
+    pub fn load_at(docs_dir: &Path, at: &str) -> Result<Graph> {
+        let history = GitHistory::new(docs_dir)?;
+        let revision = history.resolve(at)?;
         let mut records = HashMap::new();
-        let mut edges = Vec::new();
+        for (path, content) in history.records_at(&revision.commit)? {
+            match Record::parse_content(&content, path.clone()) {
+                Ok(record) => {
+                    records.insert(record.id().to_string(), record);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Failed to parse {} at {}: {}",
+                        path.display(),
+                        revision.short(),
+                        e
+                    );
+                }
+            }
+        }
+        Ok(Graph::from_records(records))
+    }
 
-        if !decisions_dir.exists() {
-            return Ok(Graph {
-                records,
-                edges,
-                docs_dir: Some(docs_dir.to_path_buf()),
-            });
+    /// The graph as of `at` when given, otherwise as it is on disk
+    pub fn load_as_of(docs_dir: &Path, at: Option<&str>) -> Result<Graph> {
+        match at {
+            Some(at) => Self::load_at(docs_dir, at),
+            None => Self::load(docs_dir),
         }
+    }
 
-        for entry in WalkDir::new(&decisions_dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.extension().is_some_and(|ext| ext == "md") {
-                match Record::parse(path) {
-                    Ok(record) => {
-                        let id = record.id().to_string();
-
-                        // Collect edges
-                        for (link_type, target) in record.frontmatter.links.all_links() {
-                            edges.push(GraphEdge {
-                                from: id.clone(),
-                                to: target.to_string(),
-                                link_type: link_type.to_string(),
-                            });
-                        }
+    /// Load all records without consulting the cache, then rewrite it
+    pub fn rebuild(docs_dir: &Path) -> Result<Graph> {
+        let mut cache = RecordCache::empty(docs_dir);
+        let graph = Self::load_records(docs_dir, &mut cache)?;
+        cache.save()?;
+        let index = SearchIndex::load_or_build(Some(docs_dir), &graph.records, true);
+        let _ = graph.search.set(index);
+        Ok(graph)
+    }
 
-                        records.insert(id, record);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+    fn load_records(docs_dir: &Path, cache: &mut RecordCache) -> Result<Graph> {
+        let decisions_dir = docs_dir.join("decisions");
+        let mut records = HashMap::new();
+
+        if decisions_dir.exists() {
+            for entry in WalkDir::new(&decisions_dir)
+                .follow_links(true)
+                .into_iter()
+                .filter_map(|e| e.ok())
+            {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "md") {
+                    match cache.parse(path) {
+                        Ok(record) => {
+                            records.insert(record.id().to_string(), record);
+                        }
+                        Err(e) => {
+                            eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                        }
                     }
                 }
             }
         }
 
-        Ok(Graph {
+        let mut graph = Graph::from_records(records);
+        graph.docs_dir = Some(docs_dir.to_path_buf());
+        Ok(graph)
+    }
+
+    /// Build a graph from parsed records, deriving edges from their links and bodies
+    pub fn from_records(records: HashMap<String, Record>) -> Graph {
+        let mut ids: Vec<&String> = records.keys().collect();
+        ids.sort();
+        let edges = ids
+            .into_iter()
+            .flat_map(|id| record_edges(&records[id], &records))
+            .collect();
+
+        let mut graph = Graph {
             records,
             edges,
-            docs_dir: Some(docs_dir.to_path_buf()),
-        })
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+            search: OnceLock::new(),
+            docs_dir: None,
+        };
+        graph.index_edges();
+        graph
+    }
+
+    /// Rebuild the adjacency maps from `edges`
+    fn index_edges(&mut self) {
+        self.outgoing.clear();
+        self.incoming.clear();
+        for (i, edge) in self.edges.iter().enumerate() {
+            self.outgoing
+                .entry(edge.from.clone())
+                .or_default()
+                .entry(edge.link_type.clone())
+                .or_default()
+                .push(i);
+            self.incoming
+                .entry(edge.to.clone())
+                .or_default()
+                .entry(edge.link_type.clone())
+                .or_default()
+                .push(i);
+        }
+    }
+
+    /// Re-derive the edges leaving a record after it changed, and drop the
+    /// search index so the next search sees the change. Only that record's
+    /// edges and adjacency entries are touched: new edges reuse the old
+    /// ones' slots, and slots left over are filled from the end of `edges`.
+    fn record_changed(&mut self, id: &str) {
+        let mut slots: Vec<usize> = self
+            .outgoing
+            .remove(id)
+            .map(|by_type| by_type.into_values().flatten().collect())
+            .unwrap_or_default();
+        slots.sort_unstable();
+        for &slot in &slots {
+            let edge = &self.edges[slot];
+            unindex(&mut self.incoming, &edge.to, &edge.link_type, slot);
+        }
+
+        let mut new_edges = match self.records.get(id) {
+            Some(record) => record_edges(record, &self.records),
+            None => Vec::new(),
+        };
+        let kept = new_edges.len().min(slots.len());
+        let extra = new_edges.split_off(kept);
+        let mut placed = slots[..kept].to_vec();
+        for (edge, &slot) in new_edges.into_iter().zip(&slots) {
+            self.edges[slot] = edge;
+        }
+        // Any further new edges go on the end...
+        for edge in extra {
+            placed.push(self.edges.len());
+            self.edges.push(edge);
+        }
+        // ...or unused slots are filled by moving the last edge down. Going
+        // from the highest slot, the moved edge is never one of this record's.
+        for &slot in slots[kept..].iter().rev() {
+            let last = self.edges.len() - 1;
+            self.edges.swap_remove(slot);
+            if slot != last {
+                let moved = &self.edges[slot];
+                reindex(
+                    &mut self.outgoing,
+                    &moved.from,
+                    &moved.link_type,
+                    last,
+                    slot,
+                );
+                reindex(&mut self.incoming, &moved.to, &moved.link_type, last, slot);
+            }
+        }
+
+        for slot in placed {
+            let edge = &self.edges[slot];
+            index(&mut self.outgoing, &edge.from, &edge.link_type, slot);
+            index(&mut self.incoming, &edge.to, &edge.link_type, slot);
+        }
+        self.search = OnceLock::new();
     }
 
     /// Save the index file for fast metadata access
@@ -194,8 +469,19 @@ impl Graph {
         self.records.get(id)
     }
 
-    pub fn get_mut(&mut self, id: &str) -> Option<&mut Record> {
-        self.records.get_mut(id)
+    pub fn get_mut(&mut self, id: &str) -> Option<RecordMut<'_>> {
+        if !self.records.contains_key(id) {
+            return None;
+        }
+        Some(RecordMut {
+            graph: self,
+            id: id.to_string(),
+        })
+    }
+
+    /// All edges, in the order they were derived from records
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
     }
 
     pub fn all_records(&self) -> impl Iterator<Item = &Record> {
@@ -211,11 +497,39 @@ impl Graph {
     }
 
     pub fn outgoing_edges(&self, id: &str) -> Vec<&GraphEdge> {
-        self.edges.iter().filter(|e| e.from == id).collect()
+        self.adjacent(&self.outgoing, id, None)
     }
 
     pub fn incoming_edges(&self, id: &str) -> Vec<&GraphEdge> {
-        self.edges.iter().filter(|e| e.to == id).collect()
+        self.adjacent(&self.incoming, id, None)
+    }
+
+    /// Edges of one link type leaving a record
+    pub fn outgoing_edges_of_type(&self, id: &str, link_type: &str) -> Vec<&GraphEdge> {
+        self.adjacent(&self.outgoing, id, Some(link_type))
+    }
+
+    /// Edges of one link type arriving at a record
+    pub fn incoming_edges_of_type(&self, id: &str, link_type: &str) -> Vec<&GraphEdge> {
+        self.adjacent(&self.incoming, id, Some(link_type))
+    }
+
+    fn adjacent(
+        &self,
+        adjacency: &Adjacency,
+        id: &str,
+        link_type: Option<&str>,
+    ) -> Vec<&GraphEdge> {
+        let Some(by_type) = adjacency.get(id) else {
+            return Vec::new();
+        };
+        let mut indices: Vec<usize> = match link_type {
+            Some(link_type) => by_type.get(link_type).cloned().unwrap_or_default(),
+            None => by_type.values().flatten().copied().collect(),
+        };
+        // Keep the order edges were derived in, regardless of map iteration
+        indices.sort_unstable();
+        indices.into_iter().map(|i| &self.edges[i]).collect()
     }
 
     pub fn neighbors(&self, id: &str, depth: usize) -> HashSet<String> {
@@ -257,19 +571,21 @@ impl Graph {
     /// Get next available ID, excluding already-reserved IDs
     pub fn next_id_excluding(&self, record_type: &RecordType, exclude: &[String]) -> String {
         let prefix = record_type.prefix();
+        // Match on "PREFIX-" so custom prefixes like "RF" don't pick up "RFC-001"
+        let id_prefix = format!("{}-", prefix);
 
         // Get all existing numeric IDs
         let existing: std::collections::HashSet<u32> = self
             .records
             .keys()
-            .filter(|id| id.starts_with(prefix))
+            .filter(|id| id.starts_with(&id_prefix))
             .filter_map(|id| id.split('-').nth(1).and_then(|s| s.parse::<u32>().ok()))
             .collect();
 
         // Also consider excluded IDs
         let excluded: std::collections::HashSet<u32> = exclude
             .iter()
-            .filter(|id| id.starts_with(prefix))
+            .filter(|id| id.starts_with(&id_prefix))
             .filter_map(|id| id.split('-').nth(1).and_then(|s| s.parse::<u32>().ok()))
             .collect();
 
@@ -282,22 +598,42 @@ impl Graph {
         format!("{}-{:03}", prefix, next_num)
     }
 
+    /// Records matching a full-text query, best match first
     pub fn search(&self, query: &str, include_content: bool) -> Vec<&Record> {
-        let query_lower = query.to_lowercase();
-        self.records
-            .values()
-            .filter(|r| {
-                r.title().to_lowercase().contains(&query_lower)
-                    || r.id().to_lowercase().contains(&query_lower)
-                    || r.frontmatter
-                        .tags
-                        .iter()
-                        .any(|t| t.to_lowercase().contains(&query_lower))
-                    || (include_content && r.content.to_lowercase().contains(&query_lower))
+        self.search_ranked(query, include_content)
+            .into_iter()
+            .map(|r| r.record)
+            .collect()
+    }
+
+    /// Full-text search with scores and highlighted body snippets
+    pub fn search_ranked(&self, query: &str, include_content: bool) -> Vec<SearchResult<'_>> {
+        self.search_hits(query, include_content)
+            .into_iter()
+            .filter_map(|hit| {
+                let record = self.records.get(&hit.id)?;
+                let snippet = if include_content {
+                    Snippet::build(&record.content, &hit.terms, 120)
+                } else {
+                    None
+                };
+                Some(SearchResult {
+                    record,
+                    score: hit.score,
+                    snippet,
+                })
             })
             .collect()
     }
 
+    /// Raw index hits for a full-text query, with the terms each record matched
+    pub fn search_hits(&self, query: &str, include_content: bool) -> Vec<SearchHit> {
+        let index = self.search.get_or_init(|| {
+            SearchIndex::load_or_build(self.docs_dir.as_deref(), &self.records, false)
+        });
+        index.search(query, include_content)
+    }
+
     pub fn validate(&self) -> Vec<ValidationError> {
         validate_graph(self, &ValidationOptions::basic())
     }
@@ -320,6 +656,16 @@ impl Graph {
     }
 
     pub fn to_dot(&self, subset: Option<&HashSet<String>>) -> String {
+        let in_subset = |id: &str| subset.is_none_or(|subset| subset.contains(id));
+        let edges: Vec<&GraphEdge> = self
+            .edges
+            .iter()
+            .filter(|e| in_subset(&e.from) && in_subset(&e.to))
+            .collect();
+        self.render_dot(in_subset, &edges)
+    }
+
+    fn render_dot(&self, include: impl Fn(&str) -> bool, edges: &[&GraphEdge]) -> String {
         let mut dot = String::from("digraph DecisionGraph {\n");
         dot.push_str("  rankdir=LR;\n");
         dot.push_str("  node [shape=box, style=rounded];\n\n");
@@ -342,10 +688,8 @@ impl Graph {
         ];
 
         for record in self.records.values() {
-            if let Some(subset) = subset {
-                if !subset.contains(record.id()) {
-                    continue;
-                }
+            if !include(record.id()) {
+                continue;
             }
 
             let color = type_colors
@@ -374,15 +718,10 @@ impl Graph {
             ("conflicts_with", "color=orange, style=bold"),
             ("refines", "color=purple"),
             ("implements", "color=teal"),
+            (MENTIONS, "color=gray60, style=dotted, arrowhead=open"),
         ];
 
-        for edge in &self.edges {
-            if let Some(subset) = subset {
-                if !subset.contains(&edge.from) || !subset.contains(&edge.to) {
-                    continue;
-                }
-            }
-
+        for edge in edges {
             let style = edge_styles
                 .iter()
                 .find(|(t, _)| edge.link_type == *t)
@@ -391,7 +730,10 @@ impl Graph {
 
             dot.push_str(&format!(
                 "  \"{}\" -> \"{}\" [label=\"{}\", {}];\n",
-                edge.from, edge.to, edge.link_type, style
+                edge.from,
+                edge.to,
+                LinkType::label_for(&edge.link_type),
+                style
             ));
         }
 
@@ -421,11 +763,7 @@ impl Graph {
             let current_id = current_path.nodes.last().unwrap();
 
             // Find depends_on edges from current node
-            let deps: Vec<_> = self
-                .edges
-                .iter()
-                .filter(|e| &e.from == current_id && e.link_type == "depends_on")
-                .collect();
+            let deps = self.outgoing_edges_of_type(current_id, "depends_on");
 
             let has_deps = !deps.is_empty();
             if !has_deps && current_path.nodes.len() > 1 {
@@ -435,7 +773,7 @@ impl Graph {
                 for edge in &deps {
                     if !visited.contains(&edge.to) {
                         visited.insert(edge.to.clone());
-                        let new_path = current_path.extend(edge.to.clone(), edge.link_type.clone());
+                        let new_path = current_path.extend(edge.to.clone(), edge);
                         queue.push_back(new_path);
                     }
                 }
@@ -463,11 +801,7 @@ impl Graph {
             let current_id = current_path.nodes.last().unwrap();
 
             // Find records that have depends_on pointing TO current node
-            let dependents: Vec<_> = self
-                .edges
-                .iter()
-                .filter(|e| &e.to == current_id && e.link_type == "depends_on")
-                .collect();
+            let dependents = self.incoming_edges_of_type(current_id, "depends_on");
 
             let has_dependents = !dependents.is_empty();
             if !has_dependents && current_path.nodes.len() > 1 {
@@ -476,8 +810,7 @@ impl Graph {
                 for edge in &dependents {
                     if !visited.contains(&edge.from) {
                         visited.insert(edge.from.clone());
-                        let new_path =
-                            current_path.extend(edge.from.clone(), edge.link_type.clone());
+                        let new_path = current_path.extend(edge.from.clone(), edge);
                         queue.push_back(new_path);
                     }
                 }
@@ -490,6 +823,295 @@ impl Graph {
         paths
     }
 
+    /// Records one link away from `id`, with the link used. `link_types`
+    /// restricts the links followed (empty follows all); `directed` only
+    /// follows links in their declared direction.
+    fn path_steps(&self, id: &str, link_types: &[&str], directed: bool) -> Vec<(String, PathLink)> {
+        let allowed =
+            |e: &&GraphEdge| link_types.is_empty() || link_types.contains(&e.link_type.as_str());
+        let link = |e: &GraphEdge| PathLink {
+            from: e.from.clone(),
+            to: e.to.clone(),
+            link_type: e.link_type.clone(),
+        };
+        let mut steps: Vec<(String, PathLink)> = self
+            .outgoing_edges(id)
+            .into_iter()
+            .filter(allowed)
+            .map(|e| (e.to.clone(), link(e)))
+            .collect();
+        if !directed {
+            steps.extend(
+                self.incoming_edges(id)
+                    .into_iter()
+                    .filter(allowed)
+                    .map(|e| (e.from.clone(), link(e))),
+            );
+        }
+        steps.retain(|(next, _)| next != id && self.records.contains_key(next));
+        steps.sort_by(|a, b| (&a.0, &a.1.link_type).cmp(&(&b.0, &b.1.link_type)));
+        steps
+    }
+
+    /// Every shortest path from `from` to `to`
+    pub fn shortest_paths(
+        &self,
+        from: &str,
+        to: &str,
+        link_types: &[&str],
+        directed: bool,
+    ) -> Vec<RecordPath> {
+        let mut distance: HashMap<String, usize> = HashMap::from([(from.to_string(), 0)]);
+        let mut previous: HashMap<String, Vec<(String, PathLink)>> = HashMap::new();
+        let mut queue = VecDeque::from([from.to_string()]);
+
+        while let Some(current) = queue.pop_front() {
+            let d = distance[&current];
+            if distance.get(to).is_some_and(|&found| d >= found) {
+                break;
+            }
+            for (next, link) in self.path_steps(&current, link_types, directed) {
+                match distance.get(&next) {
+                    None => {
+                        distance.insert(next.clone(), d + 1);
+                        previous
+                            .entry(next.clone())
+                            .or_default()
+                            .push((current.clone(), link));
+                        queue.push_back(next);
+                    }
+                    Some(&dn) if dn == d + 1 => {
+                        previous
+                            .entry(next)
+                            .or_default()
+                            .push((current.clone(), link));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        if !distance.contains_key(to) {
+            return Vec::new();
+        }
+        // Walk the predecessors back from the target
+        let mut paths = Vec::new();
+        let mut stack = vec![RecordPath {
+            nodes: vec![to.to_string()],
+            links: Vec::new(),
+        }];
+        while let Some(partial) = stack.pop() {
+            let head = &partial.nodes[0];
+            if head == from {
+                paths.push(partial);
+                continue;
+            }
+            for (prev, link) in previous.get(head).into_iter().flatten() {
+                let mut path = partial.clone();
+                path.nodes.insert(0, prev.clone());
+                path.links.insert(0, link.clone());
+                stack.push(path);
+            }
+        }
+        sort_paths(&mut paths);
+        paths
+    }
+
+    /// Every path from `from` to `to` of at most `max_len` links that visits
+    /// no record twice, shortest first
+    pub fn simple_paths(
+        &self,
+        from: &str,
+        to: &str,
+        link_types: &[&str],
+        directed: bool,
+        max_len: usize,
+    ) -> Vec<RecordPath> {
+        let mut paths = Vec::new();
+        let mut path = RecordPath {
+            nodes: vec![from.to_string()],
+            links: Vec::new(),
+        };
+        self.extend_simple_paths(to, link_types, directed, max_len, &mut path, &mut paths);
+        sort_paths(&mut paths);
+        paths
+    }
+
+    fn extend_simple_paths(
+        &self,
+        to: &str,
+        link_types: &[&str],
+        directed: bool,
+        max_len: usize,
+        path: &mut RecordPath,
+        paths: &mut Vec<RecordPath>,
+    ) {
+        let current = path.nodes.last().unwrap().clone();
+        if current == to {
+            paths.push(path.clone());
+            return;
+        }
+        if path.links.len() >= max_len {
+            return;
+        }
+        for (next, link) in self.path_steps(&current, link_types, directed) {
+            if path.nodes.contains(&next) {
+                continue;
+            }
+            path.nodes.push(next);
+            path.links.push(link);
+            self.extend_simple_paths(to, link_types, directed, max_len, path, paths);
+            path.nodes.pop();
+            path.links.pop();
+        }
+    }
+
+    /// Graphviz rendering of the records and links along `paths`
+    pub fn paths_to_dot(&self, paths: &[RecordPath]) -> String {
+        let nodes: HashSet<String> = paths.iter().flat_map(|p| p.nodes.clone()).collect();
+        let links: HashSet<&PathLink> = paths.iter().flat_map(|p| &p.links).collect();
+        let edges: Vec<&GraphEdge> = self
+            .edges
+            .iter()
+            .filter(|e| {
+                links
+                    .iter()
+                    .any(|l| l.from == e.from && l.to == e.to && l.link_type == e.link_type)
+            })
+            .collect();
+        self.render_dot(|id| nodes.contains(id), &edges)
+    }
+
+    /// Records that directly replace `id`, from its superseded_by links and
+    /// from supersedes links declared on the newer record
+    pub fn successors(&self, id: &str) -> Vec<String> {
+        let mut successors: Vec<String> = self
+            .outgoing_edges_of_type(id, "superseded_by")
+            .into_iter()
+            .map(|e| e.to.clone())
+            .chain(
+                self.incoming_edges_of_type(id, "supersedes")
+                    .into_iter()
+                    .map(|e| e.from.clone()),
+            )
+            .filter(|s| s != id && self.records.contains_key(s))
+            .collect();
+        successors.sort();
+        successors.dedup();
+        successors
+    }
+
+    /// The records currently standing in for `id`, found by following
+    /// supersession until reaching records nothing replaces. A record that
+    /// hasn't been superseded stands for itself.
+    pub fn effective_records(&self, id: &str) -> Vec<String> {
+        let mut seen = HashSet::from([id.to_string()]);
+        let mut stack = vec![id.to_string()];
+        let mut effective = Vec::new();
+        while let Some(current) = stack.pop() {
+            let successors = self.successors(&current);
+            if successors.is_empty() {
+                effective.push(current);
+            }
+            stack.extend(successors.into_iter().filter(|s| seen.insert(s.clone())));
+        }
+        // A supersession loop has no way out; the record stands for itself
+        if effective.is_empty() {
+            effective.push(id.to_string());
+        }
+        effective.sort();
+        effective
+    }
+
+    /// Whether links to `id` point at a record that no longer stands:
+    /// superseded or deprecated, or replaced by a newer record
+    pub fn is_stale(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|r| {
+            matches!(r.status(), Status::Superseded | Status::Deprecated)
+                || !self.successors(id).is_empty()
+        })
+    }
+
+    /// Shortest cycle leading from `id` back to itself along `link_types`,
+    /// as the IDs visited, starting and ending with `id`
+    pub fn find_cycle(&self, id: &str, link_types: &[&str]) -> Option<Vec<String>> {
+        let mut parents: HashMap<String, String> = HashMap::new();
+        let mut queue = VecDeque::from([id.to_string()]);
+
+        while let Some(current) = queue.pop_front() {
+            for link_type in link_types {
+                for edge in self.outgoing_edges_of_type(&current, link_type) {
+                    if edge.to == id {
+                        let mut path = vec![id.to_string()];
+                        let mut node = current.clone();
+                        while node != id {
+                            path.push(node.clone());
+                            node = parents[&node].clone();
+                        }
+                        path.push(id.to_string());
+                        path.reverse();
+                        return Some(path);
+                    }
+                    if self.records.contains_key(&edge.to) && !parents.contains_key(&edge.to) {
+                        parents.insert(edge.to.clone(), current.clone());
+                        queue.push_back(edge.to.clone());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Order `ids` so every record comes after the records it links to with
+    /// `link_types`, grouped into layers whose records don't link to each
+    /// other. Links leaving `ids` are ignored. Returns a cycle when there is no
+    /// such order.
+    pub fn topological_layers(
+        &self,
+        ids: &HashSet<String>,
+        link_types: &[&str],
+    ) -> Result<Vec<Vec<String>>, Vec<String>> {
+        let prerequisites = |id: &str| -> HashSet<String> {
+            link_types
+                .iter()
+                .flat_map(|t| self.outgoing_edges_of_type(id, t))
+                .filter(|e| e.to != id && ids.contains(&e.to))
+                .map(|e| e.to.clone())
+                .collect()
+        };
+        let mut waiting: HashMap<&String, HashSet<String>> =
+            ids.iter().map(|id| (id, prerequisites(id))).collect();
+
+        let mut layers = Vec::new();
+        while !waiting.is_empty() {
+            let mut layer: Vec<String> = waiting
+                .iter()
+                .filter(|(_, prereqs)| prereqs.is_empty())
+                .map(|(id, _)| id.to_string())
+                .collect();
+            if layer.is_empty() {
+                let mut stuck: Vec<&&String> = waiting.keys().collect();
+                stuck.sort();
+                let cycle = stuck
+                    .iter()
+                    .find_map(|id| self.find_cycle(id, link_types))
+                    .unwrap_or_else(|| stuck.iter().map(|id| id.to_string()).collect());
+                return Err(cycle);
+            }
+            layer.sort();
+            for id in &layer {
+                waiting.remove(id);
+            }
+            for prereqs in waiting.values_mut() {
+                for id in &layer {
+                    prereqs.remove(id);
+                }
+            }
+            layers.push(layer);
+        }
+        Ok(layers)
+    }
+
     /// Get related records for context (all neighbors + their links)
     pub fn context(&self, query: &str, depth: usize) -> ContextResult<'_> {
         let matching = self.search(query, true);
@@ -503,11 +1125,12 @@ impl Graph {
 
         let records: Vec<&Record> = all_ids.iter().filter_map(|id| self.get(id)).collect();
 
-        let edges: Vec<&GraphEdge> = self
-            .edges
+        let mut edges: Vec<&GraphEdge> = all_ids
             .iter()
-            .filter(|e| all_ids.contains(&e.from) && all_ids.contains(&e.to))
+            .flat_map(|id| self.outgoing_edges(id))
+            .filter(|e| all_ids.contains(&e.to))
             .collect();
+        edges.sort_by(|a, b| (&a.from, &a.to, &a.link_type).cmp(&(&b.from, &b.to, &b.link_type)));
 
         ContextResult { records, edges }
     }
@@ -535,6 +1158,16 @@ pub struct GraphStats {
     pub by_status: HashMap<String, usize>,
 }
 
+/// Shortest first, then by the records and link types visited
+fn sort_paths(paths: &mut [RecordPath]) {
+    paths.sort_by(|a, b| {
+        let types = |p: &RecordPath| -> Vec<String> {
+            p.links.iter().map(|l| l.link_type.clone()).collect()
+        };
+        (a.nodes.len(), &a.nodes, types(a)).cmp(&(b.nodes.len(), &b.nodes, types(b)))
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -545,51 +1178,40 @@ mod tests {
         // DEC-002 -> ADR-001 (depends_on)
         // DEC-001 -> ADR-001 (enables)
         let mut records = HashMap::new();
-        let mut edges = Vec::new();
 
         // Helper to create minimal records
-        let create_record = |id: &str, title: &str, rtype: RecordType| {
+        let create_record = |id: &str, title: &str, rtype: RecordType, links: &str| {
             let content = format!(
-                "---\ntype: {}\nid: {}\ntitle: \"{}\"\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\nauthors: []\ntags: []\nlinks: {{}}\n---\n\n# {}\n",
-                rtype.template_name(), id, title, title
+                "---\ntype: {}\nid: {}\ntitle: \"{}\"\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\nauthors: []\ntags: []\nlinks: {}\n---\n\n# {}\n",
+                rtype.template_name(), id, title, links, title
             );
             Record::parse_content(&content, std::path::PathBuf::from(format!("{}.md", id))).unwrap()
         };
 
         records.insert(
             "DEC-001".to_string(),
-            create_record("DEC-001", "First Decision", RecordType::Decision),
+            create_record(
+                "DEC-001",
+                "First Decision",
+                RecordType::Decision,
+                "{depends_on: [DEC-002], enables: [ADR-001]}",
+            ),
         );
         records.insert(
             "DEC-002".to_string(),
-            create_record("DEC-002", "Second Decision", RecordType::Decision),
+            create_record(
+                "DEC-002",
+                "Second Decision",
+                RecordType::Decision,
+                "{depends_on: [ADR-001]}",
+            ),
         );
         records.insert(
             "ADR-001".to_string(),
-            create_record("ADR-001", "Architecture Decision", RecordType::Adr),
+            create_record("ADR-001", "Architecture Decision", RecordType::Adr, "{}"),
         );
 
-        edges.push(GraphEdge {
-            from: "DEC-001".to_string(),
-            to: "DEC-002".to_string(),
-            link_type: "depends_on".to_string(),
-        });
-        edges.push(GraphEdge {
-            from: "DEC-002".to_string(),
-            to: "ADR-001".to_string(),
-            link_type: "depends_on".to_string(),
-        });
-        edges.push(GraphEdge {
-            from: "DEC-001".to_string(),
-            to: "ADR-001".to_string(),
-            link_type: "enables".to_string(),
-        });
-
-        Graph {
-            records,
-            edges,
-            docs_dir: None,
-        }
+        Graph::from_records(records)
     }
 
     #[test]
@@ -729,9 +1351,22 @@ mod tests {
         assert_eq!(path.nodes.len(), 1);
         assert!(path.link_types.is_empty());
 
-        let extended = path.extend("DEC-002".to_string(), "depends_on".to_string());
+        let edge = GraphEdge {
+            from: "DEC-001".to_string(),
+            to: "DEC-002".to_string(),
+            link_type: "depends_on".to_string(),
+            meta: LinkMeta {
+                note: Some("Needs the schema".to_string()),
+                ..Default::default()
+            },
+        };
+        let extended = path.extend("DEC-002".to_string(), &edge);
         assert_eq!(extended.nodes.len(), 2);
         assert_eq!(extended.link_types.len(), 1);
+        assert_eq!(
+            extended.link_meta[0].note.as_deref(),
+            Some("Needs the schema")
+        );
     }
 
     #[test]
@@ -765,7 +1400,7 @@ mod tests {
     fn test_core_records_some() {
         let mut graph = make_test_graph();
         // Mark DEC-001 as core
-        if let Some(record) = graph.get_mut("DEC-001") {
+        if let Some(mut record) = graph.get_mut("DEC-001") {
             record.frontmatter.core = true;
         }
 
@@ -892,15 +1527,7 @@ mod tests {
         let record = Record::parse_content(content, std::path::PathBuf::from("test.md")).unwrap();
         records.insert("DEC-001".to_string(), record);
 
-        let graph = Graph {
-            records,
-            edges: vec![GraphEdge {
-                from: "DEC-001".to_string(),
-                to: "NONEXISTENT".to_string(),
-                link_type: "depends_on".to_string(),
-            }],
-            docs_dir: None,
-        };
+        let graph = Graph::from_records(records);
 
         let errors = graph.validate();
         // Should have at least one broken link error
@@ -912,7 +1539,7 @@ mod tests {
         let mut graph = make_test_graph();
 
         // Modify a record
-        if let Some(record) = graph.get_mut("DEC-001") {
+        if let Some(mut record) = graph.get_mut("DEC-001") {
             record.frontmatter.title = "Modified Title".to_string();
         }
 
@@ -921,12 +1548,179 @@ mod tests {
         assert_eq!(record.title(), "Modified Title");
     }
 
+    #[test]
+    fn test_edges_by_type() {
+        let graph = make_test_graph();
+        let deps: Vec<_> = graph
+            .outgoing_edges_of_type("DEC-001", "depends_on")
+            .iter()
+            .map(|e| e.to.as_str())
+            .collect();
+        assert_eq!(deps, vec!["DEC-002"]);
+
+        let into_adr: Vec<_> = graph
+            .incoming_edges_of_type("ADR-001", "depends_on")
+            .iter()
+            .map(|e| e.from.as_str())
+            .collect();
+        assert_eq!(into_adr, vec!["DEC-002"]);
+        assert_eq!(graph.incoming_edges("ADR-001").len(), 2);
+        assert!(graph
+            .outgoing_edges_of_type("ADR-001", "depends_on")
+            .is_empty());
+        assert!(graph.outgoing_edges("NONEXISTENT").is_empty());
+    }
+
+    #[test]
+    fn test_get_mut_keeps_edges_in_sync() {
+        let mut graph = make_test_graph();
+
+        if let Some(mut record) = graph.get_mut("ADR-001") {
+            record
+                .frontmatter
+                .links
+                .add_link("enables", "DEC-002")
+                .unwrap();
+        }
+        assert_eq!(graph.outgoing_edges("ADR-001").len(), 1);
+        assert_eq!(graph.incoming_edges_of_type("DEC-002", "enables").len(), 1);
+
+        if let Some(mut record) = graph.get_mut("DEC-001") {
+            record
+                .frontmatter
+                .links
+                .remove_link("depends_on", "DEC-002")
+                .unwrap();
+        }
+        assert!(graph
+            .outgoing_edges_of_type("DEC-001", "depends_on")
+            .is_empty());
+        assert_eq!(graph.incoming_edges("DEC-002").len(), 1);
+        assert_eq!(graph.edges().len(), 3);
+
+        // Updating in place leaves the same adjacency as a full rebuild
+        let rebuilt = Graph::from_records(graph.records.clone());
+        let links = |edges: Vec<&GraphEdge>| -> Vec<(String, String, String)> {
+            let mut links: Vec<_> = edges
+                .into_iter()
+                .map(|e| (e.from.clone(), e.to.clone(), e.link_type.clone()))
+                .collect();
+            links.sort();
+            links
+        };
+        for id in graph.records.keys() {
+            assert_eq!(
+                links(graph.outgoing_edges(id)),
+                links(rebuilt.outgoing_edges(id))
+            );
+            assert_eq!(
+                links(graph.incoming_edges(id)),
+                links(rebuilt.incoming_edges(id))
+            );
+        }
+    }
+
+    #[test]
+    fn test_topological_layers_and_cycles() {
+        let mut graph = make_test_graph();
+        let all: HashSet<String> = graph.records.keys().cloned().collect();
+
+        let layers = graph.topological_layers(&all, &["depends_on"]).unwrap();
+        assert_eq!(
+            layers,
+            vec![vec!["ADR-001"], vec!["DEC-002"], vec!["DEC-001"]]
+        );
+        // Only the chosen link types count: by enables, DEC-002 is unconstrained
+        let layers = graph.topological_layers(&all, &["enables"]).unwrap();
+        assert_eq!(layers, vec![vec!["ADR-001", "DEC-002"], vec!["DEC-001"]]);
+        assert_eq!(graph.find_cycle("DEC-001", &["depends_on"]), None);
+
+        if let Some(mut record) = graph.get_mut("ADR-001") {
+            record
+                .frontmatter
+                .links
+                .add_link("depends_on", "DEC-001")
+                .unwrap();
+        }
+        assert_eq!(
+            graph.find_cycle("DEC-002", &["depends_on"]).unwrap(),
+            vec!["DEC-002", "ADR-001", "DEC-001", "DEC-002"]
+        );
+        let cycle = graph.topological_layers(&all, &["depends_on"]).unwrap_err();
+        assert_eq!(cycle, vec!["ADR-001", "DEC-001", "DEC-002", "ADR-001"]);
+    }
+
+    #[test]
+    fn test_shortest_and_simple_paths() {
+        let graph = make_test_graph();
+        let nodes = |paths: &[RecordPath]| -> Vec<Vec<String>> {
+            paths.iter().map(|p| p.nodes.clone()).collect()
+        };
+
+        let paths = graph.shortest_paths("ADR-001", "DEC-001", &[], false);
+        assert_eq!(nodes(&paths), vec![vec!["ADR-001", "DEC-001"]]);
+        // Walked backwards, the link keeps its declared direction
+        assert_eq!(paths[0].links[0].from, "DEC-001");
+        assert_eq!(paths[0].links[0].link_type, "enables");
+        assert!(graph
+            .shortest_paths("ADR-001", "DEC-001", &[], true)
+            .is_empty());
+
+        let paths = graph.shortest_paths("DEC-001", "ADR-001", &["depends_on"], true);
+        assert_eq!(nodes(&paths), vec![vec!["DEC-001", "DEC-002", "ADR-001"]]);
+
+        let paths = graph.simple_paths("DEC-001", "ADR-001", &[], false, 6);
+        assert_eq!(
+            nodes(&paths),
+            vec![
+                vec!["DEC-001", "ADR-001"],
+                vec!["DEC-001", "DEC-002", "ADR-001"]
+            ]
+        );
+        assert_eq!(
+            graph
+                .simple_paths("DEC-001", "ADR-001", &[], false, 1)
+                .len(),
+            1
+        );
+
+        let dot = graph.paths_to_dot(&paths);
+        assert!(dot.contains("\"DEC-001\" -> \"ADR-001\""));
+    }
+
+    #[test]
+    fn test_effective_records_follow_supersession() {
+        let mut graph = make_test_graph();
+        assert_eq!(graph.effective_records("DEC-002"), vec!["DEC-002"]);
+        assert!(!graph.is_stale("DEC-002"));
+
+        // ADR-001 replaces DEC-002 from one side, DEC-002 names DEC-001 from the other
+        if let Some(mut record) = graph.get_mut("ADR-001") {
+            record
+                .frontmatter
+                .links
+                .add_link("supersedes", "DEC-002")
+                .unwrap();
+        }
+        assert_eq!(graph.successors("DEC-002"), vec!["ADR-001"]);
+        assert!(graph.is_stale("DEC-002"));
+        if let Some(mut record) = graph.get_mut("ADR-001") {
+            record
+                .frontmatter
+                .links
+                .add_link("superseded_by", "DEC-001")
+                .unwrap();
+        }
+        assert_eq!(graph.effective_records("DEC-002"), vec!["DEC-001"]);
+    }
+
     #[test]
     fn test_graph_edge_fields() {
         let edge = GraphEdge {
             from: "DEC-001".to_string(),
             to: "DEC-002".to_string(),
             link_type: "depends_on".to_string(),
+            meta: LinkMeta::default(),
         };
 
         assert_eq!(edge.from, "DEC-001");
@@ -956,4 +1750,78 @@ mod tests {
         assert!(stats.by_status.contains_key("accepted"));
         assert_eq!(*stats.by_status.get("accepted").unwrap(), 3);
     }
+
+    /// Loads a few thousand synthetic records cold and from the cache.
+    /// Run with `--nocapture` to see the timings.
+    #[test]
+    fn test_cached_load_benchmark() {
+        use crate::models::cache::read_cache_file;
+        use std::time::{Duration, Instant, SystemTime};
+
+        const COUNT: usize = 3000;
+        let dir = tempfile::tempdir().unwrap();
+        let decisions = dir.path().join("decisions");
+        fs::create_dir_all(&decisions).unwrap();
+        let an_hour_ago = SystemTime::now() - Duration::from_secs(3600);
+
+        for i in 1..=COUNT {
+            let links = if i > 1 {
+                format!("links:\n  depends_on:\n    - DEC-{:04}\n", i - 1)
+            } else {
+                String::new()
+            };
+            let path = decisions.join(format!("DEC-{:04}-synthetic.md", i));
+            fs::write(
+                &path,
+                format!(
+                    "---\ntype: decision\nid: DEC-{i:04}\ntitle: Synthetic decision {i}\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\ntags: [bench, t{}]\n{links}---\n\n# Synthetic decision {i}\n\n## Context\n\nBuilds on [[DEC-0001]] with some prose to parse.\n",
+                    i % 10
+                ),
+            )
+            .unwrap();
+            // Old enough that the cache can trust mtime and size alone
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(an_hour_ago)
+                .unwrap();
+        }
+
+        let start = Instant::now();
+        let cold = Graph::rebuild(dir.path()).unwrap();
+        let cold_time = start.elapsed();
+        let written = read_cache_file(dir.path(), "records.json").unwrap();
+
+        let start = Instant::now();
+        let warm = Graph::load(dir.path()).unwrap();
+        let warm_time = start.elapsed();
+
+        eprintln!(
+            "{} records: cold {:?}, cached {:?}",
+            COUNT, cold_time, warm_time
+        );
+        assert_eq!(warm.records.len(), COUNT);
+        assert_eq!(warm.edges.len(), cold.edges.len());
+        assert_eq!(
+            warm.get("DEC-2000").unwrap().title(),
+            cold.get("DEC-2000").unwrap().title()
+        );
+        // Nothing was re-parsed, so the cache was not rewritten
+        assert_eq!(
+            read_cache_file(dir.path(), "records.json").unwrap(),
+            written
+        );
+
+        // Editing a file invalidates just that record
+        let edited = decisions.join("DEC-0042-synthetic.md");
+        let content = fs::read_to_string(&edited).unwrap();
+        fs::write(
+            &edited,
+            content.replace("Synthetic decision 42", "Edited 42"),
+        )
+        .unwrap();
+        let reloaded = Graph::load(dir.path()).unwrap();
+        assert_eq!(reloaded.get("DEC-0042").unwrap().title(), "Edited 42");
+    }
 }
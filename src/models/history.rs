@@ -0,0 +1,143 @@
+//! How a single record evolved: each committed version, what changed in
+//! its frontmatter and sections, and which commit last touched each section.
+
+use super::diff::{sections, RecordDiff};
+use super::Record;
+use crate::git::GitHistory;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordVersion {
+    pub commit: String,
+    pub author: String,
+    pub date: DateTime<Utc>,
+    pub message: String,
+    /// Where the file lived in this commit, relative to the repo root
+    pub path: String,
+    /// The record's ID and status in this version, if it parsed
+    pub id: Option<String>,
+    pub status: Option<String>,
+    /// Changes from the previous version; None for the first one
+    pub changes: Option<RecordDiff>,
+    /// Unified diff hunks of the file in this commit
+    pub patch: String,
+    #[serde(skip)]
+    body: String,
+}
+
+impl RecordVersion {
+    pub fn short(&self) -> &str {
+        &self.commit[..7.min(self.commit.len())]
+    }
+
+    /// Whether the file moved in this commit, e.g. through retype or finalize
+    pub fn renamed_from<'a>(&self, previous: Option<&'a RecordVersion>) -> Option<&'a str> {
+        previous
+            .filter(|p| p.path != self.path)
+            .map(|p| p.path.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionBlame {
+    /// Heading text, or empty for the text before the first heading
+    pub heading: String,
+    /// None when the section has uncommitted changes
+    pub commit: Option<String>,
+    pub author: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordHistory {
+    /// Newest first
+    pub versions: Vec<RecordVersion>,
+}
+
+impl RecordHistory {
+    /// Every committed version of the record's file, following renames
+    pub fn load(docs_dir: &Path, record: &Record) -> Result<Self> {
+        let history = GitHistory::new(docs_dir)?;
+        let commits = history.file_log(&record.path)?;
+        let specs: Vec<String> = commits
+            .iter()
+            .map(|c| format!("{}:{}", c.commit, c.path))
+            .collect();
+        let contents = history.read_blobs(&specs)?;
+
+        // Walk oldest first so each version can be compared with the one before
+        let mut versions: Vec<RecordVersion> = Vec::new();
+        let mut previous: Option<Record> = None;
+        for (commit, content) in commits.into_iter().zip(contents).rev() {
+            let content = content.unwrap_or_default();
+            let parsed = Record::parse_content(&content, commit.path.clone().into()).ok();
+            let changes = match (&previous, &parsed) {
+                (Some(old), Some(new)) => Some(RecordDiff::compare(old, new)),
+                _ => None,
+            };
+            versions.push(RecordVersion {
+                commit: commit.commit,
+                author: commit.author,
+                date: commit.date,
+                message: commit.message,
+                path: commit.path,
+                id: parsed.as_ref().map(|r| r.id().to_string()),
+                status: parsed.as_ref().map(|r| r.status().to_string()),
+                changes,
+                patch: commit.patch,
+                body: parsed
+                    .as_ref()
+                    .map(|r| r.content.clone())
+                    .unwrap_or(content),
+            });
+            if parsed.is_some() {
+                previous = parsed;
+            }
+        }
+        versions.reverse();
+        Ok(RecordHistory { versions })
+    }
+
+    /// The commit that last changed each section of `current`, the record's
+    /// body as it is now, in document order
+    pub fn blame(&self, current: &str) -> Vec<SectionBlame> {
+        // heading -> (body, index of the version that last changed it)
+        let mut last: Vec<(String, String, usize)> = Vec::new();
+        for (index, version) in self.versions.iter().enumerate().rev() {
+            let present = sections(&version.body);
+            // A section that was dropped and later restored counts as new
+            last.retain(|(h, _, _)| present.iter().any(|(p, _)| p == h));
+            for (heading, body) in present {
+                match last.iter_mut().find(|(h, _, _)| *h == heading) {
+                    Some(entry) if entry.1 == body => {}
+                    Some(entry) => {
+                        entry.1 = body;
+                        entry.2 = index;
+                    }
+                    None => last.push((heading, body, index)),
+                }
+            }
+        }
+
+        sections(current)
+            .into_iter()
+            .map(|(heading, body)| {
+                let version = last
+                    .iter()
+                    .find(|(h, b, _)| *h == heading && *b == body)
+                    .map(|(_, _, index)| &self.versions[*index]);
+                SectionBlame {
+                    heading,
+                    commit: version.map(|v| v.commit.clone()),
+                    author: version.map(|v| v.author.clone()),
+                    date: version.map(|v| v.date),
+                    message: version.map(|v| v.message.clone()),
+                }
+            })
+            .collect()
+    }
+}
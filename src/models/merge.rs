@@ -0,0 +1,557 @@
+//! Three-way merge of record files for `dg merge-driver`.
+//!
+//! Frontmatter is merged field by field: link, tag and author lists merge
+//! as sets, `updated` takes the newest date, and any other field changed
+//! differently on both sides is a conflict. Fields keep the text of the
+//! side whose value wins, so only changed fields are rewritten. The body
+//! goes through `git merge-file`, which leaves standard conflict markers.
+
+use super::frontmatter::{self, FrontmatterFormat};
+use super::record::Frontmatter;
+use super::Record;
+use anyhow::{Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const OURS: &str = "ours";
+const BASE: &str = "base";
+const THEIRS: &str = "theirs";
+
+/// Fields holding lists that merge as sets
+const SET_FIELDS: [&str; 2] = ["tags", "authors"];
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub content: String,
+    /// Frontmatter fields changed differently on both sides, plus "body"
+    /// when the text merge left conflict markers
+    pub conflicts: Vec<String>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merge two versions of a record file that share `base`, which is empty
+/// when both sides added the file
+pub fn merge_records(base: &str, ours: &str, theirs: &str) -> Result<MergeResult> {
+    let (Some(our_side), Some(their_side)) = (Side::parse(ours), Side::parse(theirs)) else {
+        return merge_whole_file(base, ours, theirs);
+    };
+    let base_side = Side::parse(base);
+    let our_fields = &our_side.fields;
+    let their_fields = &their_side.fields;
+    let base_fields = base_side
+        .as_ref()
+        .map(|side| side.fields.clone())
+        .unwrap_or_default();
+
+    let mut merged = Mapping::new();
+    let mut conflicts: Vec<(String, Option<Value>)> = Vec::new();
+    let mut keys: Vec<&Value> = our_fields.keys().collect();
+    keys.extend(their_fields.keys().filter(|k| !our_fields.contains_key(*k)));
+    for key in keys {
+        let name = key.as_str().unwrap_or_default();
+        let base_value = base_fields.get(key);
+        let our_value = our_fields.get(key);
+        let their_value = their_fields.get(key);
+
+        let value = match name {
+            "updated" => our_value
+                .into_iter()
+                .chain(their_value)
+                .max_by_key(|v| v.as_str())
+                .cloned(),
+            "links" => Some(merge_links(base_value, our_value, their_value)),
+            _ if SET_FIELDS.contains(&name) => {
+                let items = merge_sets(
+                    sequence(base_value),
+                    sequence(our_value),
+                    sequence(their_value),
+                    |v| v.as_str().map(str::to_string).unwrap_or_default(),
+                );
+                // A list one side removed stays removed once it's empty
+                let removed = our_value.is_none() || their_value.is_none();
+                (!items.is_empty() || !removed).then_some(Value::Sequence(items))
+            }
+            _ => match pick(base_value, our_value, their_value) {
+                Some(value) => value.cloned(),
+                None => {
+                    conflicts.push((name.to_string(), their_value.cloned()));
+                    our_value.cloned()
+                }
+            },
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    serde_yaml::from_value::<Frontmatter>(Value::Mapping(merged.clone()))
+        .context("Merged frontmatter is not a valid record")?;
+
+    // Conflict markers only make sense in YAML, where each field is a block of lines
+    if !conflicts.is_empty() && our_side.format != FrontmatterFormat::Yaml {
+        return merge_whole_file(base, ours, theirs);
+    }
+
+    let (body, body_conflicts) = merge_text(
+        base_side
+            .as_ref()
+            .map(|side| side.body.as_str())
+            .unwrap_or(""),
+        &our_side.body,
+        &their_side.body,
+    )?;
+
+    let delimiter = our_side.format.delimiter();
+    let mut content = format!(
+        "{}\n{}{}\n{}",
+        delimiter,
+        write_frontmatter(&merged, &our_side, &their_side)?,
+        delimiter,
+        body
+    );
+    for (field, theirs) in &conflicts {
+        content = mark_conflict(&content, field, theirs.as_ref())?;
+    }
+
+    let mut conflicts: Vec<String> = conflicts.into_iter().map(|(field, _)| field).collect();
+    if body_conflicts {
+        conflicts.push("body".to_string());
+    }
+    Ok(MergeResult { content, conflicts })
+}
+
+/// One version of a record file, with its frontmatter kept as written
+struct Side {
+    format: FrontmatterFormat,
+    /// Field values as parsed, without the defaults `Frontmatter` fills in
+    fields: Mapping,
+    /// The frontmatter text of each top-level field, keyed by field name
+    blocks: Vec<(Option<String>, String)>,
+    body: String,
+}
+
+impl Side {
+    /// None when the content isn't a valid record
+    fn parse(content: &str) -> Option<Side> {
+        Record::parse_content(content, PathBuf::new()).ok()?;
+        let content = content.replace("\r\n", "\n");
+        let (format, raw, body) = frontmatter::split(&content).ok()?;
+        let fields = match frontmatter::parse_value(format, raw).ok()? {
+            Value::Mapping(mapping) => mapping,
+            _ => return None,
+        };
+        Some(Side {
+            format,
+            fields,
+            blocks: field_blocks(format, raw),
+            body: body.to_string(),
+        })
+    }
+
+    fn block(&self, key: &str) -> Option<&str> {
+        self.blocks
+            .iter()
+            .find(|(name, _)| name.as_deref() == Some(key))
+            .map(|(_, text)| text.as_str())
+    }
+}
+
+/// Split raw frontmatter into the lines belonging to each top-level field.
+/// Comments stay with the field above them. Lines before the first field,
+/// and blank lines after a field, get no name.
+fn field_blocks(format: FrontmatterFormat, raw: &str) -> Vec<(Option<String>, String)> {
+    let mut blocks: Vec<(Option<String>, String)> = Vec::new();
+    let mut in_table = false;
+    for line in raw.lines() {
+        let key = match format {
+            FrontmatterFormat::Yaml => {
+                let starts_field = !line.starts_with([' ', '\t', '-', '#']) && line.contains(':');
+                starts_field.then(|| line.split(':').next().unwrap_or_default())
+            }
+            FrontmatterFormat::Toml => {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with('[') {
+                    in_table = true;
+                    // A table belongs to the field named by its first segment
+                    trimmed.trim_start_matches('[').split(['.', ']']).next()
+                } else if !in_table && !trimmed.starts_with('#') && trimmed.contains('=') {
+                    trimmed.split('=').next()
+                } else {
+                    None
+                }
+            }
+        };
+        let key = key.map(|k| k.trim().trim_matches(['"', '\'']).to_string());
+        match blocks.last_mut() {
+            Some((name, text)) if key.is_none() || *name == key => {
+                text.push_str(line);
+                text.push('\n');
+            }
+            _ => blocks.push((key, format!("{}\n", line))),
+        }
+    }
+
+    // Blank lines separate fields rather than belong to the one above
+    let mut split = Vec::new();
+    for (name, text) in blocks {
+        let kept = text.trim_end_matches('\n').len() + 1;
+        if name.is_some() && kept < text.len() {
+            split.push((name, text[..kept].to_string()));
+            split.push((None, text[kept..].to_string()));
+        } else {
+            split.push((name, text));
+        }
+    }
+    split
+}
+
+/// Write the merged fields in our field order, copying the text of any field
+/// either side already has with the merged value, so unchanged fields keep
+/// their formatting
+fn write_frontmatter(merged: &Mapping, ours: &Side, theirs: &Side) -> Result<String> {
+    let field_text = |key: &str, value: &Value| -> Result<String> {
+        if ours.fields.get(key) == Some(value) {
+            if let Some(text) = ours.block(key) {
+                return Ok(text.to_string());
+            }
+        }
+        if theirs.format == ours.format && theirs.fields.get(key) == Some(value) {
+            if let Some(text) = theirs.block(key) {
+                return Ok(text.to_string());
+            }
+        }
+        let mut mapping = Mapping::new();
+        mapping.insert(Value::String(key.to_string()), value.clone());
+        Ok(match ours.format {
+            FrontmatterFormat::Yaml => serde_yaml::to_string(&mapping)?,
+            FrontmatterFormat::Toml => {
+                format!(
+                    "{}\n",
+                    frontmatter::to_toml(&Value::Mapping(mapping))?.trim()
+                )
+            }
+        })
+    };
+    let is_table = |text: &str| text.trim_start().starts_with('[');
+
+    let mut out: Vec<String> = Vec::new();
+    for (name, text) in &ours.blocks {
+        match name {
+            None => out.push(text.clone()),
+            Some(key) => {
+                if let Some(value) = merged.get(key.as_str()) {
+                    out.push(field_text(key, value)?);
+                }
+            }
+        }
+    }
+    for (key, value) in merged {
+        let key = key.as_str().unwrap_or_default();
+        if ours.fields.contains_key(key) {
+            continue;
+        }
+        let text = field_text(key, value)?;
+        // TOML keys after a table header would land inside that table
+        let position = match ours.format {
+            FrontmatterFormat::Toml if !is_table(&text) => {
+                let mut position = out.iter().position(|t| is_table(t)).unwrap_or(out.len());
+                while position > 0 && out[position - 1].trim().is_empty() {
+                    position -= 1;
+                }
+                position
+            }
+            _ => out.len(),
+        };
+        out.insert(position, text);
+    }
+    Ok(out.concat())
+}
+
+/// A plain text merge of the whole file, for versions that don't parse
+fn merge_whole_file(base: &str, ours: &str, theirs: &str) -> Result<MergeResult> {
+    let (content, conflicted) = merge_text(base, ours, theirs)?;
+    Ok(MergeResult {
+        content,
+        conflicts: if conflicted {
+            vec!["file".to_string()]
+        } else {
+            Vec::new()
+        },
+    })
+}
+
+/// The value a field ends up with when at most one side changed it; None
+/// when both changed it differently
+fn pick<'a>(
+    base: Option<&'a Value>,
+    ours: Option<&'a Value>,
+    theirs: Option<&'a Value>,
+) -> Option<Option<&'a Value>> {
+    if ours == theirs || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+fn sequence(value: Option<&Value>) -> &[Value] {
+    match value {
+        Some(Value::Sequence(items)) => items,
+        _ => &[],
+    }
+}
+
+/// Keep what both sides kept, drop what either side removed, and add what
+/// either side added. An item changed on one side takes that side's value.
+fn merge_sets(
+    base: &[Value],
+    ours: &[Value],
+    theirs: &[Value],
+    key: impl Fn(&Value) -> String,
+) -> Vec<Value> {
+    let find = |items: &'_ [Value], k: &str| -> Option<Value> {
+        items.iter().find(|item| key(item) == k).cloned()
+    };
+    let mut merged = Vec::new();
+    for item in ours {
+        let k = key(item);
+        let in_base = find(base, &k);
+        match (find(theirs, &k), &in_base) {
+            // Removed on their side
+            (None, Some(_)) => {}
+            (Some(their_item), Some(base_item)) if *item == *base_item => merged.push(their_item),
+            _ => merged.push(item.clone()),
+        }
+    }
+    for item in theirs {
+        let k = key(item);
+        if find(ours, &k).is_none() && find(base, &k).is_none() {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+/// Merge each link type's targets as a set keyed by record ID
+fn merge_links(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Value {
+    let mapping = |value: Option<&Value>| match value {
+        Some(Value::Mapping(m)) => m.clone(),
+        _ => Mapping::new(),
+    };
+    let (base, ours, theirs) = (mapping(base), mapping(ours), mapping(theirs));
+    let target_id = |target: &Value| match target {
+        Value::Mapping(m) => m
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        other => other.as_str().unwrap_or_default().to_string(),
+    };
+
+    let mut merged = Mapping::new();
+    let mut types: Vec<&Value> = ours.keys().collect();
+    types.extend(theirs.keys().filter(|k| !ours.contains_key(*k)));
+    for link_type in types {
+        let targets = merge_sets(
+            sequence(base.get(link_type)),
+            sequence(ours.get(link_type)),
+            sequence(theirs.get(link_type)),
+            target_id,
+        );
+        // An empty list we wrote ourselves stays, so the field is unchanged
+        if !targets.is_empty() || ours.get(link_type) == Some(&Value::Sequence(Vec::new())) {
+            merged.insert(link_type.clone(), Value::Sequence(targets));
+        }
+    }
+    Value::Mapping(merged)
+}
+
+/// Wrap a top-level YAML field in conflict markers, with our value above
+/// and theirs below
+fn mark_conflict(content: &str, field: &str, theirs: Option<&Value>) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    // The frontmatter ends at the second delimiter
+    let end = lines
+        .iter()
+        .skip(1)
+        .position(|l| *l == "---")
+        .map(|i| i + 1)
+        .unwrap_or(lines.len());
+
+    let prefix = format!("{}:", field);
+    let start = lines[..end].iter().position(|l| l.starts_with(&prefix));
+    let (start, stop) = match start {
+        Some(start) => {
+            let length = lines[start + 1..end]
+                .iter()
+                .take_while(|l| l.starts_with(' ') || l.starts_with("- "))
+                .count();
+            (start, start + 1 + length)
+        }
+        None => (end, end),
+    };
+
+    let their_block = match theirs {
+        Some(value) => {
+            let mut mapping = Mapping::new();
+            mapping.insert(Value::String(field.to_string()), value.clone());
+            serde_yaml::to_string(&mapping)?
+        }
+        None => String::new(),
+    };
+
+    let mut out: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+    out.push(format!("<<<<<<< {}", OURS));
+    out.extend(lines[start..stop].iter().map(|l| l.to_string()));
+    out.push("=======".to_string());
+    out.extend(their_block.lines().map(str::to_string));
+    out.push(format!(">>>>>>> {}", THEIRS));
+    out.extend(lines[stop..].iter().map(|l| l.to_string()));
+
+    let mut merged = out.join("\n");
+    if content.ends_with('\n') {
+        merged.push('\n');
+    }
+    Ok(merged)
+}
+
+/// Three-way text merge through `git merge-file`; returns the merged text
+/// and whether it has conflict markers
+fn merge_text(base: &str, ours: &str, theirs: &str) -> Result<(String, bool)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "dg-merge-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir)?;
+    let write = |name: &str, content: &str| -> Result<PathBuf> {
+        let path = dir.join(name);
+        std::fs::write(&path, content)?;
+        Ok(path)
+    };
+    let paths = [
+        write(OURS, ours)?,
+        write(BASE, base)?,
+        write(THEIRS, theirs)?,
+    ];
+
+    let output = Command::new("git")
+        .args(["merge-file", "-p"])
+        .args(["-L", OURS, "-L", BASE, "-L", THEIRS])
+        .args(&paths)
+        .output()
+        .context("Failed to run git merge-file");
+    let _ = std::fs::remove_dir_all(&dir);
+    let output = output?;
+
+    // The exit code is the number of conflicts, or negative on error
+    match output.status.code() {
+        Some(code) if (0..128).contains(&code) => Ok((String::from_utf8(output.stdout)?, code > 0)),
+        _ => anyhow::bail!(
+            "git merge-file failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(status: &str, updated: &str, tags: &str, links: &str, body: &str) -> String {
+        format!(
+            "---\ntype: decision\nid: DEC-001\ntitle: Use Postgres\nstatus: {}\ncreated: 2024-01-01\nupdated: {}\nauthors: []\ntags: {}\nlinks: {}\n---\n\n# Use Postgres\n\n{}\n",
+            status, updated, tags, links, body
+        )
+    }
+
+    #[test]
+    fn test_merge_unions_links_and_tags() {
+        let base = record(
+            "proposed",
+            "2024-01-01",
+            "[db]",
+            "{relates_to: [DEC-002]}",
+            "Body",
+        );
+        let ours = record(
+            "accepted",
+            "2024-02-01",
+            "[db, infra]",
+            "{relates_to: [DEC-002], depends_on: [DEC-003]}",
+            "Body",
+        );
+        let theirs = record(
+            "proposed",
+            "2024-03-01",
+            "[db, storage]",
+            "{depends_on: [DEC-004]}",
+            "Body",
+        );
+        let result = merge_records(&base, &ours, &theirs).unwrap();
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+
+        let merged = Record::parse_content(&result.content, PathBuf::new()).unwrap();
+        assert_eq!(merged.frontmatter.tags, vec!["db", "infra", "storage"]);
+        let links = merged.frontmatter.links.all_links();
+        // Their side removed relates_to DEC-002
+        assert_eq!(
+            links,
+            vec![("depends_on", "DEC-003"), ("depends_on", "DEC-004")]
+        );
+        assert_eq!(merged.status().to_string(), "accepted");
+        assert_eq!(merged.frontmatter.updated.to_string(), "2024-03-01");
+    }
+
+    #[test]
+    fn test_merge_flags_conflicting_status_and_body() {
+        let base = record("proposed", "2024-01-01", "[]", "{}", "Original");
+        let ours = record("accepted", "2024-01-01", "[]", "{}", "Ours");
+        let theirs = record("rejected", "2024-01-01", "[]", "{}", "Theirs");
+        let result = merge_records(&base, &ours, &theirs).unwrap();
+        assert_eq!(result.conflicts, vec!["status", "body"]);
+        assert!(result
+            .content
+            .contains("<<<<<<< ours\nstatus: accepted\n=======\nstatus: rejected\n>>>>>>> theirs"));
+        assert!(result
+            .content
+            .contains("<<<<<<< ours\nOurs\n=======\nTheirs\n>>>>>>> theirs"));
+    }
+
+    #[test]
+    fn test_merge_keeps_unchanged_fields_as_written() {
+        let file = |status: &str| {
+            format!(
+                "---\ntype: decision\nid: DEC-001\ntitle: \"Use Postgres: v2\"\nstatus: {}\ncreated: 2024-01-01\nupdated: 2024-01-01\nauthors: [alice]\ntags: [db, infra]\nlinks: {{relates_to: [DEC-002]}}\n---\n\nBody\n",
+                status
+            )
+        };
+        let base = file("proposed");
+        let theirs = file("accepted");
+        let result = merge_records(&base, &base, &theirs).unwrap();
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        assert_eq!(result.content, theirs);
+    }
+
+    #[test]
+    fn test_merge_adds_toml_fields_before_tables() {
+        let base = "+++\ntype = \"decision\"\nid = \"DEC-001\"\ntitle = \"Use Postgres\"\nstatus = \"proposed\"\ncreated = 2024-01-01\nupdated = 2024-01-01\ntags = [\"db\"]\n\n[links]\nrelates_to = [\"DEC-002\"]\n+++\n\nBody\n";
+        let ours = base.replace("tags = [\"db\"]\n", "");
+        let theirs = base.replace("tags = [\"db\"]", "tags = [\"db\"]\nauthors = [\"alice\"]");
+        let result = merge_records(base, &ours, &theirs).unwrap();
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        assert_eq!(
+            result.content,
+            base.replace("tags = [\"db\"]", "authors = [\"alice\"]")
+        );
+    }
+}
@@ -1,15 +1,34 @@
 pub mod authors;
+mod cache;
+pub mod centrality;
+pub mod code_refs;
 pub mod d2;
+pub mod diff;
+pub mod frontmatter;
+pub mod governance;
 mod graph;
+pub mod history;
+pub mod merge;
+pub mod pattern;
+pub mod query;
 mod record;
+pub mod references;
+pub mod search;
 pub mod teams;
+#[cfg(test)]
+mod test_support;
 pub mod users;
 pub mod validation;
+pub mod workflow;
 
 pub use authors::AuthorsConfig;
 pub use d2::{graph_to_d2, D2Renderer};
-pub use graph::{DependencyPath, Graph};
-pub use record::{Record, RecordType, Status};
+pub use frontmatter::FrontmatterFormat;
+pub use graph::{DependencyPath, Graph, RecordPath, SearchResult};
+pub use record::{
+    register_custom_types, register_link_types, CustomType, LinkMeta, LinkType, Links, Record,
+    RecordType, Status,
+};
 pub use teams::TeamsConfig;
 pub use users::UsersConfig;
 pub use validation::{ValidationError, ValidationOptions};
@@ -0,0 +1,717 @@
+//! Path queries over the record graph, in a small subset of Cypher:
+//!
+//! ```text
+//! match (s:strategy)-[:enables*1..3]->(d:decision {status:accepted}) return d
+//! match (a)<-[:depends_on]-(b), (b)-->(c:adr) return a, c limit 10
+//! ```
+//!
+//! A node is `(var:label {key:value, ...} "query")`, where every part is
+//! optional. The label is a record type or a record ID, properties are the
+//! filters of the `dg search` query language (`tag:`, `created:`,
+//! `field.<name>:` ...) and the quoted string is a full search query. An
+//! edge is `-[:type|type*min..max]->`, `<-[...]-` or `-[...]-` for either
+//! direction; `-->`, `<--` and `--` match any single link. Rows are distinct
+//! and sorted by ID.
+
+use super::query::{Query, QueryError};
+use super::{Graph, Record, RecordType};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Out,
+    In,
+    Both,
+}
+
+#[derive(Debug)]
+struct NodePattern {
+    /// Index into `PathQuery::variables`
+    var: Option<usize>,
+    id: Option<String>,
+    query: Option<Query>,
+}
+
+#[derive(Debug)]
+struct EdgePattern {
+    direction: Direction,
+    /// Allowed link types; empty allows any
+    types: Vec<String>,
+    min: usize,
+    /// `None` for no upper bound
+    max: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Pattern {
+    nodes: Vec<NodePattern>,
+    /// `edges[i]` joins `nodes[i]` and `nodes[i + 1]`
+    edges: Vec<EdgePattern>,
+}
+
+/// A parsed `match ... return ...` query
+#[derive(Debug)]
+pub struct PathQuery {
+    patterns: Vec<Pattern>,
+    variables: Vec<String>,
+    returns: Vec<usize>,
+    limit: Option<usize>,
+}
+
+/// Record IDs bound to each variable
+type Binding = Vec<Option<String>>;
+
+impl PathQuery {
+    pub fn parse(input: &str) -> Result<PathQuery, QueryError> {
+        Parser { input, pos: 0 }.parse()
+    }
+
+    /// Names of the returned variables, in order
+    pub fn columns(&self) -> Vec<&str> {
+        self.returns
+            .iter()
+            .map(|&var| self.variables[var].as_str())
+            .collect()
+    }
+
+    /// Records bound to the returned variables, one row per distinct match
+    pub fn run<'a>(&self, graph: &'a Graph) -> Vec<Vec<&'a Record>> {
+        let candidates: Vec<Vec<Option<HashSet<String>>>> = self
+            .patterns
+            .iter()
+            .map(|pattern| {
+                pattern
+                    .nodes
+                    .iter()
+                    .map(|node| node_candidates(graph, node))
+                    .collect()
+            })
+            .collect();
+
+        let mut bindings: Vec<Binding> = vec![vec![None; self.variables.len()]];
+        for (pattern, candidates) in self.patterns.iter().zip(&candidates) {
+            let mut next = Vec::new();
+            for binding in bindings {
+                let matcher = Matcher {
+                    graph,
+                    pattern,
+                    candidates,
+                };
+                for start in matcher.starts(&binding) {
+                    let mut binding = binding.clone();
+                    if matcher.bind(0, &start, &mut binding) {
+                        matcher.walk(0, &start, binding, &mut next);
+                    }
+                }
+            }
+            bindings = next;
+        }
+
+        let rows: BTreeSet<Vec<String>> = bindings
+            .into_iter()
+            .map(|binding| {
+                self.returns
+                    .iter()
+                    .map(|&var| binding[var].clone().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        rows.into_iter()
+            .take(self.limit.unwrap_or(usize::MAX))
+            .filter_map(|row| row.iter().map(|id| graph.get(id)).collect())
+            .collect()
+    }
+}
+
+/// JSON rows keyed by column name, as printed by `dg query` and served by `/api/query`
+pub fn rows_to_json(columns: &[&str], rows: &[Vec<&Record>]) -> Vec<serde_json::Value> {
+    rows.iter()
+        .map(|row| {
+            let mut object = serde_json::Map::new();
+            for (column, record) in columns.iter().zip(row) {
+                object.insert(
+                    column.to_string(),
+                    serde_json::json!({
+                        "id": record.id(),
+                        "title": record.title(),
+                        "type": record.record_type().to_string(),
+                        "status": record.status().to_string(),
+                    }),
+                );
+            }
+            serde_json::Value::Object(object)
+        })
+        .collect()
+}
+
+/// IDs a node pattern allows, or `None` for any record
+fn node_candidates(graph: &Graph, node: &NodePattern) -> Option<HashSet<String>> {
+    let mut ids: Option<HashSet<String>> = node.query.as_ref().map(|query| {
+        query
+            .run(graph, true)
+            .into_iter()
+            .map(|hit| hit.record.id().to_string())
+            .collect()
+    });
+    if let Some(id) = &node.id {
+        let exact = graph
+            .all_records()
+            .find(|r| r.id().eq_ignore_ascii_case(id))
+            .map(|r| r.id().to_string());
+        ids = Some(
+            exact
+                .into_iter()
+                .filter(|id| ids.as_ref().is_none_or(|ids| ids.contains(id)))
+                .collect(),
+        );
+    }
+    ids
+}
+
+struct Matcher<'a> {
+    graph: &'a Graph,
+    pattern: &'a Pattern,
+    candidates: &'a [Option<HashSet<String>>],
+}
+
+impl Matcher<'_> {
+    /// IDs the first node can start from
+    fn starts(&self, binding: &Binding) -> Vec<String> {
+        let node = &self.pattern.nodes[0];
+        if let Some(id) = node.var.and_then(|var| binding[var].clone()) {
+            return vec![id];
+        }
+        let mut ids: Vec<String> = match &self.candidates[0] {
+            Some(ids) => ids.iter().cloned().collect(),
+            None => self.graph.records.keys().cloned().collect(),
+        };
+        ids.sort();
+        ids
+    }
+
+    /// Bind node `index` to `id` if it satisfies the node and earlier bindings
+    fn bind(&self, index: usize, id: &str, binding: &mut Binding) -> bool {
+        if self.candidates[index]
+            .as_ref()
+            .is_some_and(|ids| !ids.contains(id))
+        {
+            return false;
+        }
+        match self.pattern.nodes[index].var {
+            Some(var) => match &binding[var] {
+                Some(bound) => bound == id,
+                None => {
+                    binding[var] = Some(id.to_string());
+                    true
+                }
+            },
+            None => true,
+        }
+    }
+
+    fn walk(&self, edge: usize, from: &str, binding: Binding, out: &mut Vec<Binding>) {
+        if edge == self.pattern.edges.len() {
+            out.push(binding);
+            return;
+        }
+        for to in self.reach(from, &self.pattern.edges[edge]) {
+            let mut next = binding.clone();
+            if self.bind(edge + 1, &to, &mut next) {
+                self.walk(edge + 1, &to, next, out);
+            }
+        }
+    }
+
+    /// Records reachable from `from` in `min..=max` hops along matching links
+    fn reach(&self, from: &str, edge: &EdgePattern) -> Vec<String> {
+        let mut ends = BTreeSet::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([(from.to_string(), 0)]);
+        seen.insert((from.to_string(), 0));
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if depth >= edge.min {
+                ends.insert(id.clone());
+            }
+            if edge.max.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            // Without an upper bound, depths past `min` are all alike
+            let next_depth = match edge.max {
+                Some(_) => depth + 1,
+                None => (depth + 1).min(edge.min),
+            };
+            for next in self.step(&id, edge) {
+                if seen.insert((next.clone(), next_depth)) {
+                    queue.push_back((next, next_depth));
+                }
+            }
+        }
+        ends.into_iter().collect()
+    }
+
+    fn step(&self, id: &str, edge: &EdgePattern) -> Vec<String> {
+        let allowed =
+            |link_type: &str| edge.types.is_empty() || edge.types.iter().any(|t| t == link_type);
+        let mut next = Vec::new();
+        if edge.direction != Direction::In {
+            next.extend(
+                self.graph
+                    .outgoing_edges(id)
+                    .into_iter()
+                    .filter(|e| allowed(&e.link_type))
+                    .map(|e| e.to.clone()),
+            );
+        }
+        if edge.direction != Direction::Out {
+            next.extend(
+                self.graph
+                    .incoming_edges(id)
+                    .into_iter()
+                    .filter(|e| allowed(&e.link_type))
+                    .map(|e| e.from.clone()),
+            );
+        }
+        next.retain(|id| self.graph.get(id).is_some());
+        next
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, position: usize, message: impl Into<String>) -> QueryError {
+        QueryError::new(self.input, position, message)
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    /// Consume `token` after optional whitespace
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str, context: &str) -> Result<(), QueryError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(self.pos, format!("expected '{}' {}", token, context)))
+        }
+    }
+
+    /// Consume a case-insensitive keyword followed by a word boundary
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let matches = rest.len() >= keyword.len()
+            && rest[..keyword.len()].eq_ignore_ascii_case(keyword)
+            && !rest[keyword.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        if matches {
+            self.pos += keyword.len();
+        }
+        matches
+    }
+
+    /// A run of name characters (letters, digits, `_`, `-` and `.`)
+    fn name(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+            .unwrap_or(self.rest().len());
+        if len == 0 || self.rest().starts_with('-') {
+            return None;
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Some(name)
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        self.skip_whitespace();
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        let number = self.rest()[..len].parse().ok()?;
+        self.pos += len;
+        Some(number)
+    }
+
+    /// A double-quoted string, after the opening quote has been seen
+    fn quoted(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => text.push(escaped),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        Err(self.error(start, "unterminated quote"))
+    }
+
+    fn parse(mut self) -> Result<PathQuery, QueryError> {
+        let mut query = PathQuery {
+            patterns: Vec::new(),
+            variables: Vec::new(),
+            returns: Vec::new(),
+            limit: None,
+        };
+
+        if !self.eat_keyword("match") {
+            return Err(self.error(self.pos, "expected 'match'"));
+        }
+        loop {
+            let pattern = self.parse_pattern(&mut query.variables)?;
+            query.patterns.push(pattern);
+            if !self.eat(",") {
+                break;
+            }
+        }
+
+        if !self.eat_keyword("return") {
+            return Err(self.error(self.pos, "expected ',' or 'return' after a pattern"));
+        }
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let name = self
+                .name()
+                .ok_or_else(|| self.error(start, "expected a variable to return"))?;
+            let var = query
+                .variables
+                .iter()
+                .position(|v| *v == name)
+                .ok_or_else(|| self.error(start, format!("unknown variable '{}'", name)))?;
+            query.returns.push(var);
+            if !self.eat(",") {
+                break;
+            }
+        }
+
+        if self.eat_keyword("limit") {
+            query.limit = Some(
+                self.number()
+                    .ok_or_else(|| self.error(self.pos, "expected a number after 'limit'"))?,
+            );
+        }
+        self.skip_whitespace();
+        if !self.rest().is_empty() {
+            return Err(self.error(self.pos, "unexpected input after the query"));
+        }
+        Ok(query)
+    }
+
+    fn parse_pattern(&mut self, variables: &mut Vec<String>) -> Result<Pattern, QueryError> {
+        let mut pattern = Pattern {
+            nodes: vec![self.parse_node(variables)?],
+            edges: Vec::new(),
+        };
+        loop {
+            self.skip_whitespace();
+            if !(self.rest().starts_with('-') || self.rest().starts_with("<-")) {
+                break;
+            }
+            pattern.edges.push(self.parse_edge()?);
+            pattern.nodes.push(self.parse_node(variables)?);
+        }
+        Ok(pattern)
+    }
+
+    fn parse_node(&mut self, variables: &mut Vec<String>) -> Result<NodePattern, QueryError> {
+        self.expect("(", "to start a node")?;
+        let mut node = NodePattern {
+            var: None,
+            id: None,
+            query: None,
+        };
+
+        if let Some(name) = self.name() {
+            node.var = Some(match variables.iter().position(|v| *v == name) {
+                Some(var) => var,
+                None => {
+                    variables.push(name);
+                    variables.len() - 1
+                }
+            });
+        }
+
+        let mut query = Query::default();
+        if self.eat(":") {
+            self.skip_whitespace();
+            let start = self.pos;
+            let label = self
+                .name()
+                .ok_or_else(|| self.error(start, "expected a record type or ID after ':'"))?;
+            if RecordType::from_str(&label).is_some() {
+                query
+                    .with_filter("type", &label)
+                    .map_err(|e| self.error(start, e))?;
+            } else if looks_like_id(&label) {
+                node.id = Some(label);
+            } else {
+                return Err(self.error(start, format!("unknown record type '{}'", label)));
+            }
+        }
+
+        if self.eat("{") {
+            while !self.eat("}") {
+                self.skip_whitespace();
+                let start = self.pos;
+                let key = self
+                    .name()
+                    .ok_or_else(|| self.error(start, "expected a property name"))?;
+                self.expect(":", "after a property name")?;
+                self.skip_whitespace();
+                let value_start = self.pos;
+                let value = if self.rest().starts_with('"') {
+                    self.quoted()?
+                } else {
+                    let len = self.rest().find([',', '}']).unwrap_or(self.rest().len());
+                    let value = self.rest()[..len].trim().to_string();
+                    self.pos += len;
+                    value
+                };
+                query
+                    .with_filter(&key, &value)
+                    .map_err(|e| self.error(value_start, e))?;
+                if !self.eat(",") {
+                    self.expect("}", "to close the properties")?;
+                    break;
+                }
+            }
+        }
+
+        self.skip_whitespace();
+        if self.rest().starts_with('"') {
+            let start = self.pos;
+            let text = self.quoted()?;
+            let parsed = Query::parse(&text).map_err(|e| {
+                self.error(
+                    start + 1 + e.position,
+                    format!("in node query: {}", e.message),
+                )
+            })?;
+            query = query.and(parsed);
+        }
+
+        if !query.is_empty() {
+            node.query = Some(query);
+        }
+        self.expect(")", "to close the node")?;
+        Ok(node)
+    }
+
+    fn parse_edge(&mut self) -> Result<EdgePattern, QueryError> {
+        let start = self.pos;
+        let incoming = self.eat("<-");
+        if !incoming {
+            self.expect("-", "to start a link")?;
+        }
+
+        let mut edge = EdgePattern {
+            direction: Direction::Both,
+            types: Vec::new(),
+            min: 1,
+            max: Some(1),
+        };
+        if self.eat("[") {
+            // A relationship variable is accepted for familiarity, but not bound
+            self.name();
+            if self.eat(":") {
+                loop {
+                    self.eat(":");
+                    self.skip_whitespace();
+                    let at = self.pos;
+                    let name = self
+                        .name()
+                        .ok_or_else(|| self.error(at, "expected a link type"))?;
+                    edge.types.push(name);
+                    if !self.eat("|") {
+                        break;
+                    }
+                }
+            }
+            if self.eat("*") {
+                let min = self.number();
+                if self.eat("..") {
+                    edge.min = min.unwrap_or(1);
+                    edge.max = self.number();
+                } else {
+                    edge.min = min.unwrap_or(1);
+                    edge.max = min;
+                }
+                if edge.max.is_some_and(|max| max < edge.min) {
+                    return Err(self.error(start, "link length range is empty"));
+                }
+            }
+            self.expect("]", "to close the link")?;
+        }
+
+        let outgoing = self.eat("->");
+        if !outgoing {
+            self.expect("-", "to end the link")?;
+        }
+        edge.direction = match (incoming, outgoing) {
+            (false, true) => Direction::Out,
+            (true, false) => Direction::In,
+            (false, false) => Direction::Both,
+            (true, true) => return Err(self.error(start, "a link can't point both ways")),
+        };
+        Ok(edge)
+    }
+}
+
+/// `DEC-001`, `dec-001` or a draft ID such as `DEC-NEW-001`
+fn looks_like_id(label: &str) -> bool {
+    label.rsplit_once('-').is_some_and(|(prefix, number)| {
+        !prefix.is_empty() && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::{self, graph_of};
+
+    fn record(id: &str, record_type: &str, status: &str, links: &str) -> Record {
+        let frontmatter = format!(
+            "type: {}\ntitle: Record {}\nstatus: {}\ncreated: 2025-01-01\nupdated: 2025-01-01\ntags: []\nlinks:\n{}",
+            record_type, id, status, links
+        );
+        test_support::record(id, &frontmatter, "Body.")
+    }
+
+    /// STR-001 enables STR-002 enables DEC-001 and DEC-002; DEC-003 depends on DEC-001
+    fn graph() -> Graph {
+        graph_of(&[
+            record("STR-001", "strategy", "accepted", "  enables: [STR-002]"),
+            record(
+                "STR-002",
+                "strategy",
+                "accepted",
+                "  enables: [DEC-001, DEC-002]",
+            ),
+            record("DEC-001", "decision", "accepted", "  relates_to: [DEC-002]"),
+            record("DEC-002", "decision", "proposed", "  relates_to: []"),
+            record("DEC-003", "decision", "accepted", "  depends_on: [DEC-001]"),
+        ])
+    }
+
+    fn run(query: &str) -> Vec<String> {
+        let graph = graph();
+        PathQuery::parse(query)
+            .unwrap()
+            .run(&graph)
+            .iter()
+            .map(|row| row.iter().map(|r| r.id()).collect::<Vec<_>>().join(" "))
+            .collect()
+    }
+
+    #[test]
+    fn test_variable_length_paths_with_filters() {
+        assert_eq!(
+            run("match (s:strategy)-[:enables*1..3]->(d:decision {status:accepted}) return d"),
+            vec!["DEC-001"]
+        );
+        assert_eq!(
+            run("match (s:strategy)-[:enables*1..3]->(d:decision) return s, d"),
+            vec![
+                "STR-001 DEC-001",
+                "STR-001 DEC-002",
+                "STR-002 DEC-001",
+                "STR-002 DEC-002"
+            ]
+        );
+        assert_eq!(
+            run("match (s:STR-001)-[:enables*2]->(d) return d"),
+            vec!["DEC-001", "DEC-002"]
+        );
+        assert_eq!(
+            run("MATCH (s:strategy)-[:enables*]->(d) RETURN d LIMIT 1"),
+            vec!["DEC-001"]
+        );
+    }
+
+    #[test]
+    fn test_directions_and_joins() {
+        assert_eq!(
+            run("match (a:DEC-001)<--(b) return b"),
+            vec!["DEC-003", "STR-002"]
+        );
+        assert_eq!(
+            run("match (a:DEC-002)-[:relates_to]-(b) return b"),
+            vec!["DEC-001"]
+        );
+        assert_eq!(
+            run("match (d)<-[:depends_on]-(x), (s)-[:enables]->(d) return d, s, x"),
+            vec!["DEC-001 STR-002 DEC-003"]
+        );
+        assert_eq!(
+            run(
+                "match (d:decision \"-status:proposed\")-[:relates_to|depends_on]->(t) return d, t"
+            ),
+            vec!["DEC-001 DEC-002", "DEC-003 DEC-001"]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = PathQuery::parse("match (s:strategy)-[:enables]->(d) return x").unwrap_err();
+        assert_eq!(err.message, "unknown variable 'x'");
+        assert_eq!(err.column(), 43);
+
+        let err = PathQuery::parse("match (s:planet) return s").unwrap_err();
+        assert_eq!(err.message, "unknown record type 'planet'");
+        assert_eq!(err.column(), 10);
+
+        let err = PathQuery::parse("match (d {status:done}) return d").unwrap_err();
+        assert!(err.message.starts_with("unknown status 'done'"));
+        assert_eq!(err.column(), 18);
+
+        let message = |q: &str| PathQuery::parse(q).unwrap_err().message;
+        assert_eq!(message("(s) return s"), "expected 'match'");
+        assert_eq!(
+            message("match (s return s"),
+            "expected ')' to close the node"
+        );
+        assert_eq!(
+            message("match (a)<-[:x]->(b) return a"),
+            "a link can't point both ways"
+        );
+        assert_eq!(
+            message("match (a)-[*3..1]->(b) return a"),
+            "link length range is empty"
+        );
+        assert_eq!(
+            message("match (a) return a extra"),
+            "unexpected input after the query"
+        );
+    }
+}
@@ -0,0 +1,1003 @@
+//! Boolean query language shared by `dg search`, `dg list` and `/api/records`.
+//!
+//! Words and "quoted phrases" match record text, `key:value` terms filter on
+//! metadata. Terms are ANDed unless joined with `OR`, a leading `-` (or `NOT`)
+//! negates a term, and parentheses group:
+//!
+//! ```text
+//! (tag:auth OR tag:security) -status:deprecated created:>2025-01-01
+//! "event sourcing" links:depends_on=STR-001 field.severity=high updated:<30d
+//! ```
+
+use super::references::MENTIONS;
+use super::search::{tokenize, Snippet};
+use super::{Graph, LinkType, Record, RecordType, SearchResult, Status};
+use chrono::{Local, NaiveDate};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+const FILTER_KEYS: &[&str] = &[
+    "type", "t", "status", "s", "tag", "author", "a", "title", "id", "core", "f", "created",
+    "updated", "links", "has",
+];
+
+/// A query that failed to parse, pointing at the offending part of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    pub input: String,
+    /// Byte offset into `input`
+    pub position: usize,
+}
+
+impl QueryError {
+    pub(crate) fn new(input: &str, position: usize, message: impl Into<String>) -> QueryError {
+        QueryError {
+            message: message.into(),
+            input: input.to_string(),
+            position,
+        }
+    }
+
+    /// Character column of the error, starting at 1
+    pub fn column(&self) -> usize {
+        self.input[..self.position].chars().count() + 1
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "invalid query: {} (column {})",
+            self.message,
+            self.column()
+        )?;
+        writeln!(f, "  {}", self.input)?;
+        write!(f, "  {}^", " ".repeat(self.column() - 1))
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    fn test<T: Ord>(self, left: T, right: T) -> bool {
+        match self {
+            Cmp::Eq => left == right,
+            Cmp::Lt => left < right,
+            Cmp::Le => left <= right,
+            Cmp::Gt => left > right,
+            Cmp::Ge => left >= right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateField {
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateValue {
+    Date(NaiveDate),
+    /// Age in days, so `updated:<30d` means "updated less than 30 days ago"
+    Age(i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Type(RecordType),
+    Status(Status),
+    Tag(String),
+    Author(String),
+    Title(String),
+    Id(String),
+    Core(bool),
+    Date(DateField, Cmp, DateValue),
+    /// Outgoing link, optionally restricted by type and target
+    Links {
+        link_type: Option<String>,
+        target: Option<String>,
+    },
+    /// Outgoing link of this type, or a frontmatter field with this name
+    Has(String),
+    /// Frontmatter field equal to a value (any element, for lists)
+    Field(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Word(String),
+    Phrase(String),
+    Filter(Filter),
+}
+
+impl Expr {
+    /// Words and phrases that count towards ranking (not under a negation)
+    fn ranked_text<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Expr::And(parts) | Expr::Or(parts) => {
+                parts.iter().for_each(|part| part.ranked_text(out))
+            }
+            Expr::Word(text) | Expr::Phrase(text) => out.push(text),
+            Expr::Not(_) | Expr::Filter(_) => {}
+        }
+    }
+
+    fn all_text<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Expr::And(parts) | Expr::Or(parts) => parts.iter().for_each(|part| part.all_text(out)),
+            Expr::Not(inner) => inner.all_text(out),
+            Expr::Word(text) | Expr::Phrase(text) => out.push(text),
+            Expr::Filter(_) => {}
+        }
+    }
+}
+
+/// A parsed query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    expr: Option<Expr>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        let tokens = lex(input)?;
+        if tokens.is_empty() {
+            return Ok(Query { expr: None });
+        }
+
+        let mut parser = Parser {
+            input,
+            tokens,
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(parser.error(token.start, "unmatched ')'"));
+        }
+        Ok(Query { expr: Some(expr) })
+    }
+
+    /// True when the query matches every record
+    pub fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    /// True when results are ranked by text relevance rather than sorted by ID
+    pub fn has_text(&self) -> bool {
+        let mut text = Vec::new();
+        if let Some(expr) = &self.expr {
+            expr.ranked_text(&mut text);
+        }
+        !text.is_empty()
+    }
+
+    /// Narrow the query with one more `key:value` filter, as given by CLI
+    /// options such as `--tag`
+    pub fn and_filter(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        self.with_filter(key, value)
+            .map_err(|e| anyhow::anyhow!("--{}: {}", key, e))
+    }
+
+    /// Like `and_filter`, with the bare error message
+    pub(crate) fn with_filter(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let filter = Expr::Filter(build_filter(&key.to_lowercase(), value)?);
+        self.expr = Some(match self.expr.take() {
+            Some(Expr::And(mut parts)) => {
+                parts.push(filter);
+                Expr::And(parts)
+            }
+            Some(expr) => Expr::And(vec![expr, filter]),
+            None => filter,
+        });
+        Ok(())
+    }
+
+    /// A query matching records that match both
+    pub fn and(self, other: Query) -> Query {
+        let expr = match (self.expr, other.expr) {
+            (Some(a), Some(b)) => Some(Expr::And(vec![a, b])),
+            (a, b) => a.or(b),
+        };
+        Query { expr }
+    }
+
+    /// Records matching the query: ranked by relevance when it contains text,
+    /// otherwise sorted by ID. Words match bodies only with `include_content`.
+    pub fn run<'a>(&self, graph: &'a Graph, include_content: bool) -> Vec<SearchResult<'a>> {
+        self.run_at(graph, include_content, Local::now().date_naive())
+    }
+
+    fn run_at<'a>(
+        &self,
+        graph: &'a Graph,
+        include_content: bool,
+        today: NaiveDate,
+    ) -> Vec<SearchResult<'a>> {
+        let mut records: Vec<&Record> = graph.all_records().collect();
+        records.sort_by(|a, b| a.id().cmp(b.id()));
+        let Some(expr) = &self.expr else {
+            return records
+                .into_iter()
+                .map(|record| SearchResult {
+                    record,
+                    score: 0.0,
+                    snippet: None,
+                })
+                .collect();
+        };
+
+        let mut text = Vec::new();
+        expr.all_text(&mut text);
+        let mut hits = HashMap::new();
+        for query in text {
+            let by_id: HashMap<String, Score> = graph
+                .search_hits(query, include_content)
+                .into_iter()
+                .map(|hit| {
+                    let score = Score {
+                        value: hit.score,
+                        terms: hit.terms,
+                    };
+                    (hit.id, score)
+                })
+                .collect();
+            hits.insert(query.to_string(), by_id);
+        }
+
+        let eval = Eval {
+            graph,
+            hits,
+            include_content,
+            today,
+        };
+        let mut results: Vec<SearchResult> = records
+            .into_iter()
+            .filter_map(|record| {
+                let mut score = Score::default();
+                if !eval.matches(expr, record, &mut score) {
+                    return None;
+                }
+                let snippet = if include_content && !score.terms.is_empty() {
+                    Snippet::build(&record.content, &score.terms, 120)
+                } else {
+                    None
+                };
+                Some(SearchResult {
+                    record,
+                    score: score.value,
+                    snippet,
+                })
+            })
+            .collect();
+
+        if self.has_text() {
+            results.sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then_with(|| a.record.id().cmp(b.record.id()))
+            });
+        }
+        results
+    }
+}
+
+/// Relevance collected from the words and phrases a record matched
+#[derive(Debug, Default)]
+struct Score {
+    value: f64,
+    terms: HashSet<String>,
+}
+
+impl Score {
+    fn add(&mut self, other: &Score) {
+        self.value += other.value;
+        self.terms.extend(other.terms.iter().cloned());
+    }
+}
+
+struct Eval<'a> {
+    graph: &'a Graph,
+    /// Full-text hits for each word and phrase in the query, by record ID
+    hits: HashMap<String, HashMap<String, Score>>,
+    include_content: bool,
+    today: NaiveDate,
+}
+
+impl Eval<'_> {
+    /// Whether `record` matches `expr`, adding the relevance of matched text to `score`
+    fn matches(&self, expr: &Expr, record: &Record, score: &mut Score) -> bool {
+        match expr {
+            Expr::And(parts) => {
+                let mut all = Score::default();
+                for part in parts {
+                    if !self.matches(part, record, &mut all) {
+                        return false;
+                    }
+                }
+                score.add(&all);
+                true
+            }
+            Expr::Or(parts) => {
+                let mut any = false;
+                for part in parts {
+                    let mut branch = Score::default();
+                    if self.matches(part, record, &mut branch) {
+                        score.add(&branch);
+                        any = true;
+                    }
+                }
+                any
+            }
+            Expr::Not(inner) => !self.matches(inner, record, &mut Score::default()),
+            Expr::Word(word) => {
+                // Words made only of stopwords don't narrow anything
+                if tokenize(word).is_empty() {
+                    return true;
+                }
+                match self.hits[word.as_str()].get(record.id()) {
+                    Some(hit) => {
+                        score.add(hit);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Expr::Phrase(phrase) => {
+                if !normalize(&self.searchable_text(record)).contains(&normalize(phrase)) {
+                    return false;
+                }
+                if let Some(hit) = self.hits[phrase.as_str()].get(record.id()) {
+                    score.add(hit);
+                }
+                true
+            }
+            Expr::Filter(filter) => self.matches_filter(filter, record),
+        }
+    }
+
+    fn searchable_text(&self, record: &Record) -> String {
+        let mut text = format!(
+            "{}\n{}\n{}",
+            record.id(),
+            record.title(),
+            record.frontmatter.tags.join("\n")
+        );
+        if self.include_content {
+            text.push('\n');
+            text.push_str(&record.content);
+        }
+        text
+    }
+
+    fn matches_filter(&self, filter: &Filter, record: &Record) -> bool {
+        let fm = &record.frontmatter;
+        let contains = |haystack: &str, needle: &str| haystack.to_lowercase().contains(needle);
+        match filter {
+            Filter::Type(t) => record.record_type() == t,
+            Filter::Status(s) => record.status() == s,
+            Filter::Tag(tag) => fm.tags.iter().any(|t| contains(t, tag)),
+            Filter::Author(author) => fm.authors.iter().any(|a| contains(a, author)),
+            Filter::Title(title) => contains(record.title(), title),
+            Filter::Id(id) => contains(record.id(), id),
+            Filter::Core(core) => fm.core == *core,
+            Filter::Date(field, cmp, value) => {
+                let date = match field {
+                    DateField::Created => fm.created,
+                    DateField::Updated => fm.updated,
+                };
+                match value {
+                    DateValue::Date(value) => cmp.test(date, *value),
+                    DateValue::Age(days) => cmp.test((self.today - date).num_days(), *days),
+                }
+            }
+            Filter::Links { link_type, target } => {
+                self.graph.outgoing_edges(record.id()).iter().any(|edge| {
+                    link_type.as_ref().is_none_or(|t| edge.link_type == *t)
+                        && target
+                            .as_ref()
+                            .is_none_or(|id| edge.to.eq_ignore_ascii_case(id))
+                })
+            }
+            Filter::Has(name) => {
+                !self
+                    .graph
+                    .outgoing_edges_of_type(record.id(), name)
+                    .is_empty()
+                    || field_values(record, name).is_some_and(|values| !values.is_empty())
+            }
+            Filter::Field(name, value) => field_values(record, name)
+                .is_some_and(|values| values.iter().any(|v| v.eq_ignore_ascii_case(value))),
+        }
+    }
+}
+
+/// Lowercased text with runs of whitespace collapsed, for phrase matching
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Values of a frontmatter field as strings, or `None` when the record lacks it
+fn field_values(record: &Record, name: &str) -> Option<Vec<String>> {
+    let fm = &record.frontmatter;
+    let values = match name {
+        "id" => vec![fm.id.clone()],
+        "title" => vec![fm.title.clone()],
+        "type" => vec![fm.r#type.name().to_string()],
+        "status" => vec![fm.status.to_string()],
+        "created" => vec![fm.created.to_string()],
+        "updated" => vec![fm.updated.to_string()],
+        "authors" => fm.authors.clone(),
+        "tags" => fm.tags.clone(),
+        "core" => vec![fm.core.to_string()],
+        _ => {
+            let mut values = Vec::new();
+            yaml_strings(fm.extra.get(name)?, &mut values);
+            values
+        }
+    };
+    Some(values)
+}
+
+fn yaml_strings(value: &serde_yaml::Value, out: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::String(s) => out.push(s.clone()),
+        serde_yaml::Value::Number(n) => out.push(n.to_string()),
+        serde_yaml::Value::Bool(b) => out.push(b.to_string()),
+        serde_yaml::Value::Sequence(items) => items.iter().for_each(|v| yaml_strings(v, out)),
+        serde_yaml::Value::Tagged(tagged) => yaml_strings(&tagged.value, out),
+        serde_yaml::Value::Null | serde_yaml::Value::Mapping(_) => {}
+    }
+}
+
+/// Build the filter for `key:value`, with an error message for bad values
+fn build_filter(key: &str, value: &str) -> Result<Filter, String> {
+    if value.is_empty() {
+        return Err(format!("missing value after '{}:'", key));
+    }
+    let lower = value.to_lowercase();
+    let filter = match key {
+        "type" | "t" => Filter::Type(
+            RecordType::from_str(value).ok_or_else(|| format!("unknown record type '{}'", value))?,
+        ),
+        "status" | "s" => Filter::Status(Status::from_str(value).ok_or_else(|| {
+            format!(
+                "unknown status '{}' (expected draft, proposed, accepted, rejected, deprecated, superseded, active, open, closed, resolved or cancelled)",
+                value
+            )
+        })?),
+        "tag" => Filter::Tag(lower),
+        "author" | "a" => Filter::Author(lower),
+        "title" => Filter::Title(lower),
+        "id" => Filter::Id(lower),
+        "core" | "f" => Filter::Core(match lower.as_str() {
+            "true" | "yes" | "1" => true,
+            "false" | "no" | "0" => false,
+            _ => return Err(format!("expected true or false, got '{}'", value)),
+        }),
+        "created" | "updated" => {
+            let field = if key == "created" {
+                DateField::Created
+            } else {
+                DateField::Updated
+            };
+            let (cmp, rest) = [
+                (">=", Cmp::Ge),
+                ("<=", Cmp::Le),
+                (">", Cmp::Gt),
+                ("<", Cmp::Lt),
+                ("=", Cmp::Eq),
+            ]
+            .iter()
+            .find_map(|(op, cmp)| value.strip_prefix(op).map(|rest| (*cmp, rest)))
+            .unwrap_or((Cmp::Eq, value));
+            Filter::Date(field, cmp, parse_date_value(rest)?)
+        }
+        "links" => {
+            let (link_type, target) = match value.split_once('=') {
+                Some((link_type, target)) => {
+                    if target.is_empty() {
+                        return Err(format!("missing record ID after '{}='", link_type));
+                    }
+                    let link_type = (!link_type.is_empty() && link_type != "*")
+                        .then(|| link_type.to_string());
+                    (link_type, Some(target.to_string()))
+                }
+                None if is_link_type(value) => (Some(value.to_string()), None),
+                None => (None, Some(value.to_string())),
+            };
+            Filter::Links { link_type, target }
+        }
+        "has" => Filter::Has(value.to_string()),
+        _ => match key.strip_prefix("field.") {
+            Some(name) if !name.is_empty() => Filter::Field(name.to_string(), value.to_string()),
+            _ => return Err(format!("unknown filter '{}'", key)),
+        },
+    };
+    Ok(filter)
+}
+
+fn is_link_type(name: &str) -> bool {
+    name == MENTIONS || LinkType::lookup(name).is_some()
+}
+
+fn parse_date_value(value: &str) -> Result<DateValue, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(DateValue::Date(date));
+    }
+    let unit_days = match value.chars().last() {
+        Some('d') => Some(1),
+        Some('w') => Some(7),
+        Some('m') => Some(30),
+        Some('y') => Some(365),
+        _ => None,
+    };
+    let count = value[..value.len().saturating_sub(1)].parse::<i64>().ok();
+    match (count, unit_days) {
+        (Some(count), Some(days)) => Ok(DateValue::Age(count * days)),
+        _ => Err(format!(
+            "expected a date (YYYY-MM-DD) or an age such as 30d, 2w, 6m or 1y, got '{}'",
+            value
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Or,
+    And,
+    Not,
+    Phrase(String),
+    /// A bare word or `key:value`; quoted parts start at `quoted_from`
+    Word {
+        text: String,
+        quoted_from: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Lexeme {
+    kind: TokenKind,
+    /// Byte offset in the input
+    start: usize,
+}
+
+fn lex(input: &str) -> Result<Vec<Lexeme>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                TokenKind::LParen
+            }
+            ')' => {
+                chars.next();
+                TokenKind::RParen
+            }
+            '-' if input[start + 1..]
+                .chars()
+                .next()
+                .is_some_and(|next| !next.is_whitespace() && next != ')') =>
+            {
+                chars.next();
+                TokenKind::Not
+            }
+            '"' => {
+                chars.next();
+                TokenKind::Phrase(read_quoted(input, start, &mut chars)?)
+            }
+            _ => {
+                let mut text = String::new();
+                let mut quoted_from = None;
+                while let Some(&(at, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        quoted_from.get_or_insert(text.len());
+                        text.push_str(&read_quoted(input, at, &mut chars)?);
+                    } else {
+                        text.push(c);
+                    }
+                }
+                match text.as_str() {
+                    "OR" if quoted_from.is_none() => TokenKind::Or,
+                    "AND" if quoted_from.is_none() => TokenKind::And,
+                    "NOT" if quoted_from.is_none() => TokenKind::Not,
+                    _ => TokenKind::Word { text, quoted_from },
+                }
+            }
+        };
+        tokens.push(Lexeme { kind, start });
+    }
+    Ok(tokens)
+}
+
+/// Read up to the closing quote, after the opening one at `start`
+fn read_quoted(
+    input: &str,
+    start: usize,
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+) -> Result<String, QueryError> {
+    let mut text = String::new();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => return Ok(text),
+            '\\' if chars.peek().is_some_and(|&(_, next)| next == '"') => {
+                chars.next();
+                text.push('"');
+            }
+            c => text.push(c),
+        }
+    }
+    Err(QueryError::new(input, start, "unterminated quote"))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Lexeme>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, position: usize, message: impl Into<String>) -> QueryError {
+        QueryError::new(self.input, position, message)
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    /// Whether the next token can start a term
+    fn at_term(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(
+                TokenKind::LParen | TokenKind::Not | TokenKind::Phrase(_) | TokenKind::Word { .. }
+            )
+        )
+    }
+
+    /// Position to report for a missing term: the next token, or the end
+    fn here(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|t| t.start)
+            .unwrap_or(self.input.len())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut branches = vec![self.parse_and()?];
+        while self.peek() == Some(&TokenKind::Or) {
+            self.pos += 1;
+            if !self.at_term() {
+                return Err(self.error(self.here(), "expected a term after OR"));
+            }
+            branches.push(self.parse_and()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.remove(0)
+        } else {
+            Expr::Or(branches)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        if !self.at_term() {
+            let message = match self.peek() {
+                Some(TokenKind::Or) => "expected a term before OR",
+                Some(TokenKind::And) => "expected a term before AND",
+                Some(TokenKind::RParen) => "unmatched ')'",
+                _ => "expected a term",
+            };
+            return Err(self.error(self.here(), message));
+        }
+
+        let mut parts = Vec::new();
+        loop {
+            match self.peek() {
+                Some(TokenKind::And) => {
+                    self.pos += 1;
+                    if !self.at_term() {
+                        return Err(self.error(self.here(), "expected a term after AND"));
+                    }
+                }
+                _ if self.at_term() => {}
+                _ => break,
+            }
+            parts.push(self.parse_unary()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            Expr::And(parts)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        if self.peek() == Some(&TokenKind::Not) {
+            let start = self.tokens[self.pos].start;
+            self.pos += 1;
+            if !self.at_term() {
+                let operator = if self.input[start..].starts_with('-') {
+                    "'-'"
+                } else {
+                    "NOT"
+                };
+                return Err(self.error(start, format!("expected a term after {}", operator)));
+            }
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, QueryError> {
+        let token = self.tokens[self.pos].clone();
+        self.pos += 1;
+        match token.kind {
+            TokenKind::LParen => {
+                if self.peek() == Some(&TokenKind::RParen) {
+                    return Err(self.error(token.start, "empty parentheses"));
+                }
+                let expr = self.parse_or()?;
+                if self.peek() != Some(&TokenKind::RParen) {
+                    return Err(self.error(token.start, "unclosed '('"));
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            TokenKind::Phrase(phrase) => {
+                if phrase.trim().is_empty() {
+                    return Err(self.error(token.start, "empty phrase"));
+                }
+                Ok(Expr::Phrase(phrase))
+            }
+            TokenKind::Word { text, quoted_from } => {
+                self.parse_word(text, quoted_from, token.start)
+            }
+            _ => unreachable!("parse_primary is only called at a term"),
+        }
+    }
+
+    /// A word is a `key:value` filter when it starts with a name followed by
+    /// `:` (or `=` for `field.<name>`), and a search term otherwise
+    fn parse_word(
+        &self,
+        text: String,
+        quoted_from: Option<usize>,
+        start: usize,
+    ) -> Result<Expr, QueryError> {
+        let unquoted = &text[..quoted_from.unwrap_or(text.len())];
+        let separator = unquoted.find([':', '=']);
+        let Some(separator) = separator else {
+            return Ok(Expr::Word(text));
+        };
+        let key = &unquoted[..separator];
+        let is_name = key.starts_with(|c: char| c.is_ascii_alphabetic())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+        let is_field = key.starts_with("field.");
+        if !is_name || (unquoted[separator..].starts_with('=') && !is_field) {
+            return Ok(Expr::Word(text));
+        }
+
+        let key = key.to_lowercase();
+        if !is_field && !FILTER_KEYS.contains(&key.as_str()) {
+            return Err(self.error(
+                start,
+                format!(
+                    "unknown filter '{}:' (filters: type, status, tag, author, title, id, core, created, updated, links, has, field.<name>; quote the word to search for it)",
+                    key
+                ),
+            ));
+        }
+        let value = &text[separator + 1..];
+        build_filter(&key, value)
+            .map(Expr::Filter)
+            .map_err(|message| self.error(start + separator + 1, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::{graph_of, record};
+
+    fn graph() -> Graph {
+        graph_of(&[
+            record(
+                "STR-001",
+                "type: strategy\ntitle: Expand to Europe\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\ntags: [growth]",
+                "We expand to France first.",
+            ),
+            record(
+                "DEC-001",
+                "type: decision\ntitle: Use Postgres\nstatus: accepted\ncreated: 2025-02-01\nupdated: 2025-06-01\ntags: [database, infra]\nauthors: [alice]\nlinks:\n  depends_on: [STR-001]",
+                "Event sourcing needs a durable store.",
+            ),
+            record(
+                "DEC-002",
+                "type: decision\ntitle: Drop the legacy cache\nstatus: deprecated\ncreated: 2023-05-01\nupdated: 2023-05-01\ntags: [legacy, infra]\nseverity: high\nlinks:\n  conflicts_with: [DEC-001]",
+                "The cache is not event sourcing.",
+            ),
+            record(
+                "ADR-001",
+                "type: adr\ntitle: Event sourcing for orders\nstatus: proposed\ncreated: 2025-06-10\nupdated: 2025-06-10\ntags: [architecture]\nseverity: low\ncore: true",
+                "Orders use event sourcing backed by Postgres.",
+            ),
+        ])
+    }
+
+    fn ids(query: &str) -> Vec<String> {
+        ids_with(query, true)
+    }
+
+    fn ids_with(query: &str, include_content: bool) -> Vec<String> {
+        let graph = graph();
+        let today = NaiveDate::from_ymd_opt(2025, 6, 20).unwrap();
+        Query::parse(query)
+            .unwrap()
+            .run_at(&graph, include_content, today)
+            .iter()
+            .map(|hit| hit.record.id().to_string())
+            .collect()
+    }
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    fn error(query: &str) -> QueryError {
+        Query::parse(query).unwrap_err()
+    }
+
+    #[test]
+    fn test_filters_and_by_default() {
+        assert_eq!(ids("type:decision tag:infra"), vec!["DEC-001", "DEC-002"]);
+        assert_eq!(ids("type:dec status:accepted"), vec!["DEC-001"]);
+        assert_eq!(ids("author:ali"), vec!["DEC-001"]);
+        assert_eq!(ids("core:true"), vec!["ADR-001"]);
+        assert_eq!(ids(""), vec!["ADR-001", "DEC-001", "DEC-002", "STR-001"]);
+    }
+
+    #[test]
+    fn test_or_not_and_parentheses() {
+        assert_eq!(
+            ids("tag:growth OR tag:architecture"),
+            vec!["ADR-001", "STR-001"]
+        );
+        assert_eq!(ids("tag:infra -tag:legacy"), vec!["DEC-001"]);
+        assert_eq!(ids("type:decision NOT status:deprecated"), vec!["DEC-001"]);
+        assert_eq!(
+            ids("(tag:legacy OR tag:growth) AND -type:strategy"),
+            vec!["DEC-002"]
+        );
+        // AND binds tighter than OR
+        assert_eq!(
+            ids("tag:growth OR type:adr status:proposed"),
+            vec!["ADR-001", "STR-001"]
+        );
+    }
+
+    #[test]
+    fn test_phrases_and_ranked_words() {
+        assert_eq!(
+            sorted(ids("\"event sourcing\"")),
+            vec!["ADR-001", "DEC-001", "DEC-002"]
+        );
+        assert_eq!(ids("\"sourcing event\""), Vec::<String>::new());
+        assert_eq!(
+            sorted(ids("\"event sourcing\" -\"not event\"")),
+            vec!["ADR-001", "DEC-001"]
+        );
+        // Title matches rank first; body matches need include_content
+        assert_eq!(ids("postgres")[0], "DEC-001");
+        assert_eq!(ids_with("postgres", false), vec!["DEC-001"]);
+        assert_eq!(ids("postgres OR france").len(), 3);
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(ids("created:>2025-01-01"), vec!["ADR-001", "DEC-001"]);
+        assert_eq!(ids("created:<=2023-05-01"), vec!["DEC-002"]);
+        assert_eq!(ids("created:2024-01-01"), vec!["STR-001"]);
+        // Relative to 2025-06-20: updated within the last 30 days
+        assert_eq!(ids("updated:<30d"), vec!["ADR-001", "DEC-001"]);
+        assert_eq!(ids("updated:>1y"), vec!["DEC-002", "STR-001"]);
+    }
+
+    #[test]
+    fn test_links_has_and_fields() {
+        assert_eq!(ids("links:depends_on=STR-001"), vec!["DEC-001"]);
+        assert_eq!(ids("links:depends_on=str-001"), vec!["DEC-001"]);
+        assert_eq!(ids("links:DEC-001"), vec!["DEC-002"]);
+        assert_eq!(ids("links:*=DEC-001"), vec!["DEC-002"]);
+        assert_eq!(ids("has:conflicts_with"), vec!["DEC-002"]);
+        assert_eq!(ids("has:severity"), vec!["ADR-001", "DEC-002"]);
+        assert_eq!(ids("field.severity=high"), vec!["DEC-002"]);
+        assert_eq!(ids("field.severity:LOW"), vec!["ADR-001"]);
+        assert_eq!(ids("field.tags=legacy"), vec!["DEC-002"]);
+        assert_eq!(ids("field.title=\"Use Postgres\""), vec!["DEC-001"]);
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        let err = error("tag:a (tag:b OR tag:c");
+        assert_eq!(err.message, "unclosed '('");
+        assert_eq!(err.column(), 7);
+        assert_eq!(
+            err.to_string(),
+            "invalid query: unclosed '(' (column 7)\n  tag:a (tag:b OR tag:c\n        ^"
+        );
+
+        assert_eq!(error("tag:a)").message, "unmatched ')'");
+        assert_eq!(error("()").message, "empty parentheses");
+        assert_eq!(error("a OR").message, "expected a term after OR");
+        assert_eq!(error("OR a").message, "expected a term before OR");
+        assert_eq!(error("\"open phrase").message, "unterminated quote");
+        assert_eq!(error("tag:").message, "missing value after 'tag:'");
+
+        let err = error("status:done");
+        assert!(err.message.starts_with("unknown status 'done'"));
+        assert_eq!(err.column(), 8);
+        let err = error("x created:>yesterday");
+        assert!(err.message.contains("expected a date"));
+        assert_eq!(err.column(), 11);
+        assert!(error("colour:red")
+            .message
+            .starts_with("unknown filter 'colour:'"));
+    }
+
+    #[test]
+    fn test_words_that_are_not_filters() {
+        let query = Query::parse("DEC-001 a=b \"tag:x\"").unwrap();
+        assert_eq!(
+            query.expr,
+            Some(Expr::And(vec![
+                Expr::Word("DEC-001".into()),
+                Expr::Word("a=b".into()),
+                Expr::Phrase("tag:x".into()),
+            ]))
+        );
+        assert!(query.has_text());
+        assert!(!Query::parse("tag:x -foo").unwrap().has_text());
+    }
+
+    #[test]
+    fn test_and_filter_narrows_query() {
+        let graph = graph();
+        let mut query = Query::parse("tag:infra OR tag:growth").unwrap();
+        query.and_filter("type", "decision").unwrap();
+        let results = query.run(&graph, false);
+        assert_eq!(results.len(), 2);
+        assert!(query.and_filter("status", "nope").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+//! Inline references to other records in markdown bodies: `[[DEC-001]]`,
+//! `[[DEC-001|label]]`, section links like `[[DEC-001#alternatives]]`,
+//! transclusions like `![[POL-001#retention-rules]]` and, when
+//! `[references] bare_ids` is enabled in dg.toml, plain IDs such as DEC-001
+
+use regex::{Captures, Regex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Edge type for references found in record bodies
+pub const MENTIONS: &str = "mentions";
+
+static BARE_IDS: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable treating bare IDs in bodies as references
+pub fn set_bare_ids(enabled: bool) {
+    BARE_IDS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn bare_ids_enabled() -> bool {
+    BARE_IDS.load(Ordering::Relaxed)
+}
+
+/// Matches `[[ID]]`, `[[ID#heading]]`, `[[ID|label]]` and the `![[...]]` embed form
+pub fn wiki_link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(!)?\[\[\s*([A-Za-z][A-Za-z0-9_-]*)\s*(?:#\s*([^\]|]*?)\s*)?(?:\|\s*([^\]]*?)\s*)?\]\]",
+        )
+        .unwrap()
+    })
+}
+
+/// Matches record IDs such as DEC-001 or DEC-NEW-20240115120000
+pub fn bare_id_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Z][A-Z0-9]*-(?:NEW-)?\d+\b").unwrap())
+}
+
+/// The parts of a `[[...]]` reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub id: String,
+    /// Heading slug after `#`, normalized with [`slugify`]
+    pub anchor: Option<String>,
+    pub label: Option<String>,
+    /// Written as `![[...]]`: embed the target instead of linking to it
+    pub embed: bool,
+}
+
+impl WikiLink {
+    /// Build from a match of [`wiki_link_regex`]
+    pub fn from_captures(cap: &Captures) -> WikiLink {
+        let text = |i: usize| {
+            cap.get(i)
+                .map(|m| m.as_str().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        WikiLink {
+            id: cap[2].to_string(),
+            anchor: text(3).map(|a| slugify(&a)).filter(|a| !a.is_empty()),
+            label: text(4),
+            embed: cap.get(1).is_some(),
+        }
+    }
+}
+
+/// A reference to another record found in a body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodyReference {
+    pub id: String,
+    pub anchor: Option<String>,
+    pub label: Option<String>,
+    /// 1-based line within the body
+    pub line: usize,
+    /// Written as `[[ID]]` rather than a bare ID
+    pub explicit: bool,
+    /// Written as `![[ID]]`
+    pub embed: bool,
+}
+
+/// Find references in a markdown body. Fenced code blocks and inline code are
+/// skipped so examples don't turn into edges.
+pub fn find_references(body: &str, bare_ids: bool) -> Vec<BodyReference> {
+    static INLINE_CODE: OnceLock<Regex> = OnceLock::new();
+    let inline_code = INLINE_CODE.get_or_init(|| Regex::new(r"`[^`]*`").unwrap());
+    let mut refs = Vec::new();
+    let mut in_fence = false;
+
+    for (i, line) in body.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = inline_code.replace_all(line, "");
+        for cap in wiki_link_regex().captures_iter(&line) {
+            let link = WikiLink::from_captures(&cap);
+            refs.push(BodyReference {
+                id: link.id,
+                anchor: link.anchor,
+                label: link.label,
+                line: i + 1,
+                explicit: true,
+                embed: link.embed,
+            });
+        }
+
+        if bare_ids {
+            let rest = wiki_link_regex().replace_all(&line, "");
+            for m in bare_id_regex().find_iter(&rest) {
+                refs.push(BodyReference {
+                    id: m.as_str().to_string(),
+                    anchor: None,
+                    label: None,
+                    line: i + 1,
+                    explicit: false,
+                    embed: false,
+                });
+            }
+        }
+    }
+
+    refs
+}
+
+/// Heading anchor for a heading's text: lowercase, punctuation dropped,
+/// whitespace runs turned into dashes (same rule as the web UI's table of contents)
+pub fn slugify(text: &str) -> String {
+    let kept: String = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c.is_whitespace() || *c == '-')
+        .collect();
+    let dashed = kept.split_whitespace().collect::<Vec<_>>().join("-");
+    let mut slug = String::new();
+    for c in dashed.chars() {
+        if !(c == '-' && slug.ends_with('-')) {
+            slug.push(c);
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Assigns unique anchors to headings in document order; repeats get `-1`, `-2`, ...
+#[derive(Debug, Default)]
+pub struct SlugCounter {
+    seen: std::collections::HashMap<String, usize>,
+}
+
+impl SlugCounter {
+    pub fn unique(&mut self, text: &str) -> String {
+        let slug = slugify(text);
+        let count = self.seen.entry(slug.clone()).or_default();
+        let unique = if *count == 0 {
+            slug
+        } else {
+            format!("{}-{}", slug, count)
+        };
+        *count += 1;
+        unique
+    }
+}
+
+/// A markdown heading in a record body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: usize,
+    pub text: String,
+    pub slug: String,
+    /// 1-based line within the body
+    pub line: usize,
+}
+
+/// ATX headings (`## Title`) outside fenced code blocks
+pub fn headings(body: &str) -> Vec<Heading> {
+    let mut result = Vec::new();
+    let mut slugs = SlugCounter::default();
+    let mut in_fence = false;
+
+    for (i, line) in body.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let rest = &trimmed[level..];
+        if level == 0 || level > 6 || !(rest.is_empty() || rest.starts_with(' ')) {
+            continue;
+        }
+        let text = rest.trim().trim_end_matches('#').trim().to_string();
+        result.push(Heading {
+            level,
+            slug: slugs.unique(&text),
+            text,
+            line: i + 1,
+        });
+    }
+
+    result
+}
+
+/// Line range `[start, end)` (1-based) of the section under the heading with
+/// `slug`, from the heading itself up to the next heading of the same or a
+/// higher level
+pub fn section_lines(body: &str, slug: &str) -> Option<(usize, usize)> {
+    let all = headings(body);
+    let pos = all.iter().position(|h| h.slug == slug)?;
+    let heading = &all[pos];
+    let end = all[pos + 1..]
+        .iter()
+        .find(|h| h.level <= heading.level)
+        .map(|h| h.line)
+        .unwrap_or(body.lines().count() + 1);
+    Some((heading.line, end))
+}
+
+/// Markdown of the section under the heading with `slug`, heading included
+pub fn section(body: &str, slug: &str) -> Option<String> {
+    let (start, end) = section_lines(body, slug)?;
+    Some(
+        body.lines()
+            .skip(start - 1)
+            .take(end - start)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wiki_links_with_labels() {
+        let body = "As part of [[STR-001]] and [[DEC-002|the pricing call]].\n";
+        let refs = find_references(body, false);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].id, "STR-001");
+        assert_eq!(refs[0].label, None);
+        assert_eq!(refs[1].id, "DEC-002");
+        assert_eq!(refs[1].label.as_deref(), Some("the pricing call"));
+        assert!(refs.iter().all(|r| r.explicit && r.line == 1));
+    }
+
+    #[test]
+    fn test_code_is_skipped() {
+        let body =
+            "Use `[[DEC-001]]` syntax.\n\n```markdown\n[[DEC-002]]\n```\n\nSee [[DEC-003]]\n";
+        let ids: Vec<_> = find_references(body, true)
+            .into_iter()
+            .map(|r| (r.id, r.line))
+            .collect();
+        assert_eq!(ids, vec![("DEC-003".to_string(), 7)]);
+    }
+
+    #[test]
+    fn test_bare_ids_only_when_enabled() {
+        let body = "As part of STR-001, see [[DEC-002]].\n";
+        assert_eq!(find_references(body, false).len(), 1);
+
+        let refs = find_references(body, true);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[1].id, "STR-001");
+        assert!(!refs[1].explicit);
+    }
+
+    #[test]
+    fn test_anchors_and_embeds() {
+        let body = "See [[DEC-001#Alternatives Considered|the options]].\n\n![[POL-001#retention-rules]]\n";
+        let refs = find_references(body, false);
+        assert_eq!(refs[0].anchor.as_deref(), Some("alternatives-considered"));
+        assert_eq!(refs[0].label.as_deref(), Some("the options"));
+        assert!(!refs[0].embed);
+        assert_eq!(refs[1].id, "POL-001");
+        assert_eq!(refs[1].anchor.as_deref(), Some("retention-rules"));
+        assert!(refs[1].embed);
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Retention Rules"), "retention-rules");
+        assert_eq!(slugify("  Q&A: what's next?  "), "qa-whats-next");
+        assert_eq!(slugify("A -- B"), "a-b");
+    }
+
+    #[test]
+    fn test_sections() {
+        let body = "# Policy\n\n## Retention Rules\n\nKeep 30 days.\n\n### Exceptions\n\nLegal holds.\n\n## Scope\n\nAll data.\n\n```\n## Not a heading\n```\n";
+        let slugs: Vec<_> = headings(body).into_iter().map(|h| h.slug).collect();
+        assert_eq!(
+            slugs,
+            vec!["policy", "retention-rules", "exceptions", "scope"]
+        );
+
+        let rules = section(body, "retention-rules").unwrap();
+        assert!(rules.starts_with("## Retention Rules"));
+        assert!(rules.contains("Legal holds."));
+        assert!(!rules.contains("## Scope"));
+        assert!(section(body, "not-a-heading").is_none());
+    }
+
+    #[test]
+    fn test_duplicate_heading_slugs() {
+        let slugs: Vec<_> = headings("## Notes\n\n## Notes\n")
+            .into_iter()
+            .map(|h| h.slug)
+            .collect();
+        assert_eq!(slugs, vec!["notes", "notes-1"]);
+    }
+}
@@ -0,0 +1,598 @@
+//! Ranked full-text search over records.
+//!
+//! An inverted index maps stemmed tokens from each record's ID, title, tags
+//! and body to the records containing them. Queries are scored with BM25,
+//! boosted by the field a term appears in. Query terms also match longer
+//! terms that start with them, and fall back to fuzzy matching (small edit
+//! distance) when nothing matches exactly.
+
+use super::cache::{content_hash, read_cache_file, write_cache_file};
+use super::Record;
+use colored::Colorize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Bump when tokenizing or the persisted layout changes
+const INDEX_FORMAT: u32 = 1;
+const INDEX_FILE: &str = "search.json";
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Score multiplier for a term matched by prefix rather than exactly
+const PREFIX_WEIGHT: f64 = 0.7;
+/// Score multiplier for a term matched by edit distance
+const FUZZY_WEIGHT: f64 = 0.4;
+/// Shortest query term expanded to longer terms by prefix
+const MIN_PREFIX_LEN: usize = 3;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "in", "is", "it", "of", "on", "or",
+    "that", "the", "this", "to", "with",
+];
+
+/// Part of a record a term was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    Id,
+    Title,
+    Tags,
+    Body,
+}
+
+impl Field {
+    const ALL: [Field; 4] = [Field::Id, Field::Title, Field::Tags, Field::Body];
+
+    fn boost(self) -> f64 {
+        match self {
+            Field::Id => 3.0,
+            Field::Title => 2.5,
+            Field::Tags => 2.0,
+            Field::Body => 1.0,
+        }
+    }
+
+    fn slot(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Posting {
+    doc: u32,
+    field: Field,
+    tf: u32,
+}
+
+/// Inverted index over all records in a graph
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: String,
+    /// Hash of everything indexed, to tell when a persisted index is stale
+    fingerprint: String,
+    ids: Vec<String>,
+    /// Token count per document and field
+    lengths: Vec<[u32; 4]>,
+    avg_lengths: [f64; 4],
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+/// A record matching a query
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub id: String,
+    pub score: f64,
+    /// Index terms the query matched in this record, for highlighting
+    pub terms: HashSet<String>,
+}
+
+fn index_version() -> String {
+    format!("{}-{}", INDEX_FORMAT, env!("CARGO_PKG_VERSION"))
+}
+
+fn fingerprint<'a>(records: impl Iterator<Item = &'a Record>) -> String {
+    let mut text = String::new();
+    for record in records {
+        text.push_str(record.id());
+        text.push('\0');
+        text.push_str(record.title());
+        text.push('\0');
+        text.push_str(&record.frontmatter.tags.join("\0"));
+        text.push('\0');
+        text.push_str(&record.content);
+        text.push('\u{1}');
+    }
+    content_hash(text.as_bytes())
+}
+
+impl SearchIndex {
+    /// Index records, sorted by ID
+    pub fn build(records: &[&Record]) -> SearchIndex {
+        let mut index = SearchIndex {
+            version: index_version(),
+            fingerprint: fingerprint(records.iter().copied()),
+            ..Default::default()
+        };
+
+        for (doc, record) in records.iter().enumerate() {
+            let mut lengths = [0u32; 4];
+            let tags = record.frontmatter.tags.join(" ");
+            for field in Field::ALL {
+                let text = match field {
+                    Field::Id => record.id(),
+                    Field::Title => record.title(),
+                    Field::Tags => &tags,
+                    Field::Body => &record.content,
+                };
+                let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+                for token in tokenize(text) {
+                    *counts.entry(token.term).or_default() += 1;
+                    lengths[field.slot()] += 1;
+                }
+                for (term, tf) in counts {
+                    index.postings.entry(term).or_default().push(Posting {
+                        doc: doc as u32,
+                        field,
+                        tf,
+                    });
+                }
+            }
+            index.ids.push(record.id().to_string());
+            index.lengths.push(lengths);
+        }
+
+        let docs = index.lengths.len().max(1) as f64;
+        for field in Field::ALL {
+            let total: u64 = index.lengths.iter().map(|l| l[field.slot()] as u64).sum();
+            index.avg_lengths[field.slot()] = (total as f64 / docs).max(1.0);
+        }
+        index
+    }
+
+    /// Reuse the index persisted in the docs dir's cache when it matches the
+    /// records, otherwise build and persist a new one
+    pub fn load_or_build(
+        docs_dir: Option<&Path>,
+        records: &HashMap<String, Record>,
+        force: bool,
+    ) -> SearchIndex {
+        let mut sorted: Vec<&Record> = records.values().collect();
+        sorted.sort_by(|a, b| a.id().cmp(b.id()));
+
+        if let (Some(dir), false) = (docs_dir, force) {
+            let current = fingerprint(sorted.iter().copied());
+            let persisted = read_cache_file(dir, INDEX_FILE)
+                .and_then(|bytes| serde_json::from_slice::<SearchIndex>(&bytes).ok())
+                .filter(|index| index.version == index_version() && index.fingerprint == current);
+            if let Some(index) = persisted {
+                return index;
+            }
+        }
+
+        let index = SearchIndex::build(&sorted);
+        if let Some(dir) = docs_dir {
+            // Only an optimization; searching still works without it
+            if let Ok(bytes) = serde_json::to_vec(&index) {
+                let _ = write_cache_file(dir, INDEX_FILE, &bytes);
+            }
+        }
+        index
+    }
+
+    /// Records matching every term of `query`, best first. Bodies are only
+    /// searched when `include_content` is set.
+    pub fn search(&self, query: &str, include_content: bool) -> Vec<SearchHit> {
+        let mut terms: Vec<String> = Vec::new();
+        for token in tokenize(query) {
+            if !terms.contains(&token.term) {
+                terms.push(token.term);
+            }
+        }
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let docs = self.ids.len() as f64;
+        let mut scores: HashMap<u32, (f64, Vec<bool>, HashSet<String>)> = HashMap::new();
+
+        for (i, query_term) in terms.iter().enumerate() {
+            for (term, weight) in self.expand(query_term) {
+                let postings = &self.postings[&term];
+                let mut doc_ids: Vec<u32> = postings.iter().map(|p| p.doc).collect();
+                doc_ids.dedup();
+                let df = doc_ids.len() as f64;
+                let idf = (1.0 + (docs - df + 0.5) / (df + 0.5)).ln();
+
+                for posting in postings {
+                    if posting.field == Field::Body && !include_content {
+                        continue;
+                    }
+                    let slot = posting.field.slot();
+                    let len = self.lengths[posting.doc as usize][slot] as f64;
+                    let tf = posting.tf as f64;
+                    let norm =
+                        tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / self.avg_lengths[slot]));
+
+                    let entry = scores
+                        .entry(posting.doc)
+                        .or_insert_with(|| (0.0, vec![false; terms.len()], HashSet::new()));
+                    entry.0 += weight * idf * posting.field.boost() * norm;
+                    entry.1[i] = true;
+                    entry.2.insert(term.clone());
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter(|(_, (_, matched, _))| matched.iter().all(|m| *m))
+            .map(|(doc, (score, _, terms))| SearchHit {
+                id: self.ids[doc as usize].clone(),
+                score,
+                terms,
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        hits
+    }
+
+    /// Index terms a query term matches, with their score weight
+    fn expand(&self, query_term: &str) -> Vec<(String, f64)> {
+        let mut matches = Vec::new();
+        if self.postings.contains_key(query_term) {
+            matches.push((query_term.to_string(), 1.0));
+        }
+        if query_term.chars().count() >= MIN_PREFIX_LEN {
+            for term in self
+                .postings
+                .range(query_term.to_string()..)
+                .map(|(term, _)| term)
+                .take_while(|term| term.starts_with(query_term))
+                .filter(|term| term.as_str() != query_term)
+            {
+                // Closer in length ranks higher: "auth" suits "authn" more than "authorization"
+                let ratio = query_term.len() as f64 / term.len() as f64;
+                matches.push((term.clone(), PREFIX_WEIGHT * (0.5 + 0.5 * ratio)));
+            }
+        }
+        if !matches.is_empty() {
+            return matches;
+        }
+
+        let max_distance = match query_term.chars().count() {
+            0..=3 => return matches,
+            4..=7 => 1,
+            _ => 2,
+        };
+        for term in self.postings.keys() {
+            if edit_distance(query_term, term, max_distance).is_some() {
+                matches.push((term.clone(), FUZZY_WEIGHT));
+            }
+        }
+        matches
+    }
+}
+
+/// A word in some text, with its byte range and index term
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub term: String,
+}
+
+/// Split text into lowercase, stemmed words, dropping stopwords. Record IDs
+/// like DEC-001 stay whole so searching for one doesn't match every record
+/// numbered 001.
+pub fn tokenize(text: &str) -> Vec<Token> {
+    static ID_RE: OnceLock<Regex> = OnceLock::new();
+    let id_re = ID_RE.get_or_init(|| Regex::new(r"(?i)\b[a-z][a-z0-9]*-(?:new-)?\d+\b").unwrap());
+
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    for id in id_re.find_iter(text) {
+        tokenize_words(text, pos, id.start(), &mut tokens);
+        tokens.push(Token {
+            start: id.start(),
+            end: id.end(),
+            term: id.as_str().to_lowercase(),
+        });
+        pos = id.end();
+    }
+    tokenize_words(text, pos, text.len(), &mut tokens);
+    tokens
+}
+
+fn tokenize_words(text: &str, from: usize, to: usize, tokens: &mut Vec<Token>) {
+    let mut start = None;
+    for (i, c) in text[from..to]
+        .char_indices()
+        .map(|(i, c)| (i + from, c))
+        .chain(std::iter::once((to, ' ')))
+    {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                let word = text[s..i].to_lowercase();
+                if !STOPWORDS.contains(&word.as_str()) {
+                    tokens.push(Token {
+                        start: s,
+                        end: i,
+                        term: stem(&word),
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Light English suffix stripping so "policies", "deciding" and "decided"
+/// meet "policy" and "decide"
+pub fn stem(word: &str) -> String {
+    if word.len() <= 3 || !word.is_ascii() {
+        return word.to_string();
+    }
+    if let Some(base) = word.strip_suffix("ies") {
+        return format!("{}y", base);
+    }
+    if word.ends_with("sses") {
+        return word[..word.len() - 2].to_string();
+    }
+    for suffix in ["ing", "ed"] {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() >= 3 && base.chars().any(is_vowel) {
+                return undouble(base);
+            }
+        }
+    }
+    if word.ends_with('s')
+        && !word.ends_with("ss")
+        && !word.ends_with("us")
+        && !word.ends_with("is")
+    {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// "running" -> "runn" -> "run", but keep "ll", "ss" and "zz" ("falling" -> "fall")
+fn undouble(base: &str) -> String {
+    let bytes = base.as_bytes();
+    let n = bytes.len();
+    if n >= 2 && bytes[n - 1] == bytes[n - 2] && !matches!(bytes[n - 1], b'l' | b's' | b'z') {
+        return base[..n - 1].to_string();
+    }
+    base.to_string()
+}
+
+/// Levenshtein distance, or None once it exceeds `max`
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        if current.iter().min().copied().unwrap_or(0) > max {
+            return None;
+        }
+        previous = current;
+    }
+    let distance = previous[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+/// A line of a record body around matched terms
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Snippet {
+    pub text: String,
+    /// Byte ranges of matched words in `text`
+    pub highlights: Vec<(usize, usize)>,
+}
+
+impl Snippet {
+    /// The body line with the most matches for `terms`, cut to about `width` characters
+    pub fn build(body: &str, terms: &HashSet<String>, width: usize) -> Option<Snippet> {
+        let mut best: Option<(usize, &str)> = None;
+        let mut in_fence = false;
+        for line in body.lines() {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                continue;
+            }
+            let count = tokenize(line)
+                .iter()
+                .filter(|t| terms.contains(&t.term))
+                .count();
+            if count > 0 && best.is_none_or(|(c, _)| count > c) {
+                best = Some((count, line));
+            }
+        }
+
+        let line = best?.1;
+        let line = line
+            .trim()
+            .trim_start_matches(['#', '-', '*', '>', '|'])
+            .trim();
+        let first = tokenize(line)
+            .into_iter()
+            .find(|t| terms.contains(&t.term))?;
+
+        // Window of `width` characters, starting a little before the first match
+        let chars: Vec<(usize, char)> = line.char_indices().collect();
+        let first_char = chars
+            .iter()
+            .position(|(i, _)| *i == first.start)
+            .unwrap_or(0);
+        let from = first_char.saturating_sub(width / 4);
+        let to = (from + width).min(chars.len());
+        let from = to.saturating_sub(width).min(from);
+        let start = chars.get(from).map(|(i, _)| *i).unwrap_or(0);
+        let end = chars.get(to).map(|(i, _)| *i).unwrap_or(line.len());
+
+        let mut text = String::new();
+        if start > 0 {
+            text.push('…');
+        }
+        let offset = text.len();
+        text.push_str(&line[start..end]);
+        let highlights = tokenize(&line[start..end])
+            .into_iter()
+            .filter(|t| terms.contains(&t.term))
+            .map(|t| (t.start + offset, t.end + offset))
+            .collect();
+        if end < line.len() {
+            text.push('…');
+        }
+
+        Some(Snippet { text, highlights })
+    }
+
+    fn render(&self, mark: impl Fn(&str) -> String, plain: impl Fn(&str) -> String) -> String {
+        let mut out = String::new();
+        let mut pos = 0;
+        for (start, end) in &self.highlights {
+            out.push_str(&plain(&self.text[pos..*start]));
+            out.push_str(&mark(&self.text[*start..*end]));
+            pos = *end;
+        }
+        out.push_str(&plain(&self.text[pos..]));
+        out
+    }
+
+    /// HTML with matches wrapped in `<mark>`
+    pub fn to_html(&self) -> String {
+        self.render(
+            |s| format!("<mark>{}</mark>", htmlescape::encode_minimal(s)),
+            htmlescape::encode_minimal,
+        )
+    }
+
+    /// Terminal text with matches highlighted
+    pub fn to_terminal(&self) -> String {
+        self.render(
+            |s| s.yellow().bold().to_string(),
+            |s| s.dimmed().to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support;
+
+    fn record(id: &str, title: &str, tags: &str, body: &str) -> Record {
+        let frontmatter = format!(
+            "type: decision\ntitle: {}\nstatus: accepted\ncreated: 2024-01-01\nupdated: 2024-01-01\ntags: [{}]",
+            title, tags
+        );
+        test_support::record(id, &frontmatter, body)
+    }
+
+    fn index(records: &[Record]) -> SearchIndex {
+        SearchIndex::build(&records.iter().collect::<Vec<_>>())
+    }
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_stemming() {
+        assert_eq!(stem("policies"), "policy");
+        assert_eq!(stem("decided"), "decid");
+        assert_eq!(stem("deciding"), "decid");
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("falling"), "fall");
+        assert_eq!(stem("status"), "status");
+        assert_eq!(stem("databases"), "database");
+    }
+
+    #[test]
+    fn test_title_and_tag_matches_rank_higher() {
+        let records = [
+            record(
+                "DEC-001",
+                "Hiring plan",
+                "",
+                "We mention pricing once in passing.",
+            ),
+            record(
+                "DEC-002",
+                "Pricing tiers",
+                "pricing",
+                "Pricing for three tiers.",
+            ),
+            record("DEC-003", "Enterprise deals", "pricing", "Contracts."),
+        ];
+        let hits = index(&records).search("pricing", true);
+        assert_eq!(ids(&hits), vec!["DEC-002", "DEC-003", "DEC-001"]);
+
+        // Bodies only count when asked to
+        let hits = index(&records).search("pricing", false);
+        assert_eq!(ids(&hits), vec!["DEC-002", "DEC-003"]);
+    }
+
+    #[test]
+    fn test_ids_are_single_terms() {
+        let terms: Vec<_> = tokenize("See DEC-001 and adr-new-20240101.")
+            .into_iter()
+            .map(|t| t.term)
+            .collect();
+        assert_eq!(terms, vec!["see", "dec-001", "adr-new-20240101"]);
+    }
+
+    #[test]
+    fn test_all_terms_must_match() {
+        let records = [
+            record("DEC-001", "Use PostgreSQL", "database", ""),
+            record("DEC-002", "Use Redis for caching", "database", ""),
+        ];
+        let hits = index(&records).search("database redis", false);
+        assert_eq!(ids(&hits), vec!["DEC-002"]);
+    }
+
+    #[test]
+    fn test_prefix_and_fuzzy_matching() {
+        let records = [
+            record("DEC-001", "Use PostgreSQL", "", ""),
+            record("DEC-002", "Compression algorithm", "", ""),
+        ];
+        let index = index(&records);
+        assert_eq!(ids(&index.search("postgres", false)), vec!["DEC-001"]);
+        assert_eq!(ids(&index.search("compresion", false)), vec!["DEC-002"]);
+        assert!(index.search("zebra", false).is_empty());
+    }
+
+    #[test]
+    fn test_snippet_highlights_matches() {
+        let body = "# Context\n\nWe compared vendors.\n\nThe retention policy keeps logs for 30 days; retention is reviewed yearly.\n";
+        let terms: HashSet<String> = ["retention".to_string()].into();
+        let snippet = Snippet::build(body, &terms, 200).unwrap();
+        assert!(snippet.text.starts_with("The retention policy"));
+        assert_eq!(snippet.highlights.len(), 2);
+        assert_eq!(
+            snippet.to_html(),
+            "The <mark>retention</mark> policy keeps logs for 30 days; <mark>retention</mark> is reviewed yearly."
+        );
+    }
+}
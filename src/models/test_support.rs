@@ -0,0 +1,20 @@
+//! Records and graphs for unit tests
+
+use super::{Graph, Record};
+use std::path::PathBuf;
+
+/// Parse a record from its ID, the rest of its frontmatter and its body
+pub fn record(id: &str, frontmatter: &str, body: &str) -> Record {
+    let content = format!("---\nid: {}\n{}\n---\n\n{}\n", id, frontmatter, body);
+    Record::parse_content(&content, PathBuf::from(format!("{}.md", id))).unwrap()
+}
+
+/// A graph of the given records, keyed by ID
+pub fn graph_of(records: &[Record]) -> Graph {
+    Graph::from_records(
+        records
+            .iter()
+            .map(|record| (record.id().to_string(), record.clone()))
+            .collect(),
+    )
+}
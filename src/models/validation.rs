@@ -1,6 +1,8 @@
 //! Shared validation logic for records and graphs
 
-use super::{Graph, Record, RecordType, TeamsConfig, UsersConfig};
+use super::references;
+use super::workflow::WorkflowError;
+use super::{Graph, LinkType, Links, Record, RecordType, Status, TeamsConfig, UsersConfig};
 use regex::Regex;
 use std::collections::HashSet;
 
@@ -22,6 +24,42 @@ pub enum ValidationError {
         link_type: String,
         expected_inverse: String,
     },
+    UnknownLinkType {
+        id: String,
+        link_type: String,
+    },
+    BrokenReference {
+        id: String,
+        target: String,
+        line: usize,
+    },
+    BrokenAnchor {
+        id: String,
+        target: String,
+        anchor: String,
+        line: usize,
+    },
+    TransclusionCycle {
+        id: String,
+        line: usize,
+        cycle: Vec<String>,
+    },
+    LinkCycle {
+        id: String,
+        link_type: String,
+        cycle: Vec<String>,
+    },
+    StaleLink {
+        id: String,
+        link_type: String,
+        target: String,
+        target_status: String,
+        successors: Vec<String>,
+    },
+    WorkflowViolation {
+        id: String,
+        error: WorkflowError,
+    },
     OrphanedRecord {
         id: String,
     },
@@ -59,6 +97,16 @@ pub enum ValidationError {
     DraftRecord {
         id: String,
     },
+    UnknownCodeAnnotation {
+        keyword: String,
+        target: String,
+    },
+    StaleCodeAnnotation {
+        keyword: String,
+        target: String,
+        target_status: String,
+        successors: Vec<String>,
+    },
     CodeBlockMissingLanguage {
         id: String,
         line: usize,
@@ -129,6 +177,64 @@ impl std::fmt::Display for ValidationError {
                 "{}: {} link to {} but {} doesn't have {} back",
                 from, link_type, to, to, expected_inverse
             ),
+            ValidationError::UnknownLinkType { id, link_type } => write!(
+                f,
+                "{}: unknown link type '{}' (declare it under [links.{}] in dg.toml)",
+                id, link_type, link_type
+            ),
+            ValidationError::BrokenReference { id, target, line } => write!(
+                f,
+                "{}: line {}: reference [[{}]] points to a record that doesn't exist",
+                id, line, target
+            ),
+            ValidationError::BrokenAnchor {
+                id,
+                target,
+                anchor,
+                line,
+            } => write!(
+                f,
+                "{}: line {}: reference [[{}#{}]] points to a heading that doesn't exist",
+                id, line, target, anchor
+            ),
+            ValidationError::TransclusionCycle { id, line, cycle } => write!(
+                f,
+                "{}: line {}: transclusion cycle: {}",
+                id,
+                line,
+                cycle.join(" → ")
+            ),
+            ValidationError::LinkCycle {
+                id,
+                link_type,
+                cycle,
+            } => write!(f, "{}: {} cycle: {}", id, link_type, cycle.join(" → ")),
+            ValidationError::StaleLink {
+                id,
+                link_type,
+                target,
+                target_status,
+                successors,
+            } => {
+                if successors.is_empty() {
+                    write!(
+                        f,
+                        "{}: {} {}, which is {}",
+                        id, link_type, target, target_status
+                    )
+                } else {
+                    write!(
+                        f,
+                        "{}: {} {}, which is superseded by {} (use 'dg retarget {}')",
+                        id,
+                        link_type,
+                        target,
+                        successors.join(", "),
+                        id
+                    )
+                }
+            }
+            ValidationError::WorkflowViolation { id, error } => write!(f, "{}: {}", id, error),
             ValidationError::OrphanedRecord { id } => {
                 write!(f, "{}: orphaned record (no links)", id)
             }
@@ -172,6 +278,29 @@ impl std::fmt::Display for ValidationError {
             ValidationError::DraftRecord { id } => {
                 write!(f, "{}: draft record (use 'dg finalize' before merging)", id)
             }
+            ValidationError::UnknownCodeAnnotation { keyword, target } => write!(
+                f,
+                " @{} {} points to a record that doesn't exist",
+                keyword, target
+            ),
+            ValidationError::StaleCodeAnnotation {
+                keyword,
+                target,
+                target_status,
+                successors,
+            } => {
+                if successors.is_empty() {
+                    write!(f, " @{} {}, which is {}", keyword, target, target_status)
+                } else {
+                    write!(
+                        f,
+                        " @{} {}, which is superseded by {}",
+                        keyword,
+                        target,
+                        successors.join(", ")
+                    )
+                }
+            }
             ValidationError::CodeBlockMissingLanguage { id, line } => {
                 write!(
                     f,
@@ -267,7 +396,8 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
-use crate::serve::config::ValidationConfig;
+use crate::serve::config::{ValidationConfig, WorkflowConfig};
+use std::collections::HashMap;
 
 /// Validation options
 #[derive(Debug, Default)]
@@ -296,6 +426,10 @@ pub struct ValidationOptions {
     pub validation_config: Option<ValidationConfig>,
     /// Enable semantic validation checks
     pub check_semantic: bool,
+    /// Status workflows from dg.toml, keyed by type name
+    pub workflows: HashMap<String, WorkflowConfig>,
+    /// Status on the base ref of records changed since, keyed by ID
+    pub committed_statuses: HashMap<String, Status>,
 }
 
 impl ValidationOptions {
@@ -318,6 +452,8 @@ impl ValidationOptions {
             teams_config: None,
             validation_config: None,
             check_semantic: true,
+            workflows: HashMap::new(),
+            committed_statuses: HashMap::new(),
         }
     }
 }
@@ -332,8 +468,13 @@ pub fn validate_record(
     let id = record.id().to_string();
     let valid_ids: HashSet<_> = graph.records.keys().cloned().collect();
 
-    // Check for broken links
+    // Check for broken links and [[ID]] references
     errors.extend(check_broken_links(record, &valid_ids));
+    errors.extend(check_broken_references(record, &valid_ids));
+    errors.extend(check_broken_anchors(record, graph));
+    errors.extend(check_transclusion_cycles(record, graph));
+    errors.extend(check_link_cycles(record, graph));
+    errors.extend(check_stale_links(record, graph));
 
     // Check for missing title
     if record.title().is_empty() {
@@ -366,8 +507,9 @@ pub fn validate_record(
         }
     }
 
-    // Check supersedes has inverse
-    errors.extend(check_supersedes_inverse(record, graph));
+    // Check links with declared inverses are mirrored, and link types are known
+    errors.extend(check_inverse_links(record, graph));
+    errors.extend(check_unknown_link_types(record));
 
     // Check for meaningful content (strict)
     if opts.require_content {
@@ -405,6 +547,15 @@ pub fn validate_record(
         errors.extend(check_code_blocks(record));
     }
 
+    // Check status against the type's workflow
+    if let Some(workflow) = opts.workflows.get(record.record_type().name()) {
+        errors.extend(check_workflow(
+            record,
+            workflow,
+            opts.committed_statuses.get(&id),
+        ));
+    }
+
     // Check semantic validation rules from config
     if opts.check_semantic {
         if let Some(ref validation_config) = opts.validation_config {
@@ -432,31 +583,253 @@ pub fn check_broken_links(record: &Record, valid_ids: &HashSet<String>) -> Vec<V
         .collect()
 }
 
-/// Check that supersedes links have inverse superseded_by
-pub fn check_supersedes_inverse(record: &Record, graph: &Graph) -> Vec<ValidationError> {
-    let id = record.id().to_string();
+/// Check that `[[ID]]` references in the body point to existing records
+pub fn check_broken_references(
+    record: &Record,
+    valid_ids: &HashSet<String>,
+) -> Vec<ValidationError> {
+    references::find_references(&record.content, false)
+        .into_iter()
+        .filter(|r| !valid_ids.contains(&r.id))
+        .map(|r| ValidationError::BrokenReference {
+            id: record.id().to_string(),
+            target: r.id,
+            line: r.line,
+        })
+        .collect()
+}
+
+/// Check that `[[ID#heading]]` references point to a heading in the target
+pub fn check_broken_anchors(record: &Record, graph: &Graph) -> Vec<ValidationError> {
+    references::find_references(&record.content, false)
+        .into_iter()
+        .filter_map(|r| {
+            let anchor = r.anchor?;
+            let target = graph.get(&r.id)?;
+            let found = references::headings(&target.content)
+                .iter()
+                .any(|h| h.slug == anchor);
+            (!found).then(|| ValidationError::BrokenAnchor {
+                id: record.id().to_string(),
+                target: r.id,
+                anchor,
+                line: r.line,
+            })
+        })
+        .collect()
+}
+
+/// Check that `![[ID#heading]]` embeds never lead back to the embed itself
+pub fn check_transclusion_cycles(record: &Record, graph: &Graph) -> Vec<ValidationError> {
+    embeds_in(record, None)
+        .into_iter()
+        .filter_map(|origin| {
+            let mut path = vec![record.id().to_string(), embed_label(&origin)];
+            let mut visited = HashSet::new();
+            find_transclusion_cycle(graph, record, origin.line, &origin, &mut path, &mut visited)
+                .then(|| ValidationError::TransclusionCycle {
+                    id: record.id().to_string(),
+                    line: origin.line,
+                    cycle: path,
+                })
+        })
+        .collect()
+}
+
+/// Check that acyclic link types (depends_on, refines, supersedes and any
+/// declared `acyclic` in dg.toml) never lead back to the record. Each cycle is
+/// reported once, on its lowest ID.
+pub fn check_link_cycles(record: &Record, graph: &Graph) -> Vec<ValidationError> {
+    let mut link_types: Vec<&str> = graph
+        .outgoing_edges(record.id())
+        .iter()
+        .map(|e| e.link_type.as_str())
+        .filter(|t| LinkType::lookup(t).is_some_and(|t| t.acyclic))
+        .collect();
+    link_types.sort();
+    link_types.dedup();
+
+    link_types
+        .into_iter()
+        .filter_map(|link_type| {
+            let cycle = graph.find_cycle(record.id(), &[link_type])?;
+            let lowest = cycle.iter().min()?;
+            (lowest == record.id()).then(|| ValidationError::LinkCycle {
+                id: record.id().to_string(),
+                link_type: link_type.to_string(),
+                cycle,
+            })
+        })
+        .collect()
+}
+
+/// Live records that build on a superseded or deprecated record, with the
+/// records that now stand in for it
+pub fn check_stale_links(record: &Record, graph: &Graph) -> Vec<ValidationError> {
+    if record.status().is_inactive() {
+        return Vec::new();
+    }
     record
         .frontmatter
         .links
-        .supersedes
-        .iter()
-        .filter_map(|target| {
-            graph.get(target).and_then(|target_record| {
-                if !target_record.frontmatter.links.superseded_by.contains(&id) {
-                    Some(ValidationError::MissingInverseLink {
-                        from: id.clone(),
-                        to: target.clone(),
-                        link_type: "supersedes".to_string(),
-                        expected_inverse: "superseded_by".to_string(),
-                    })
-                } else {
-                    None
-                }
+        .all_links()
+        .into_iter()
+        .filter(|(link_type, target)| {
+            Links::BUILDS_ON_TYPES.contains(link_type) && graph.is_stale(target)
+        })
+        .filter_map(|(link_type, target)| {
+            let target_record = graph.get(target)?;
+            let successors: Vec<String> = graph
+                .effective_records(target)
+                .into_iter()
+                .filter(|s| s != target)
+                .collect();
+            Some(ValidationError::StaleLink {
+                id: record.id().to_string(),
+                link_type: link_type.to_string(),
+                target: target.to_string(),
+                target_status: target_record.status().to_string(),
+                successors,
             })
         })
         .collect()
 }
 
+/// Embeds in a record's body, or only those inside the section under `anchor`
+fn embeds_in(record: &Record, anchor: Option<&str>) -> Vec<references::BodyReference> {
+    let range = match anchor {
+        Some(anchor) => match references::section_lines(&record.content, anchor) {
+            Some((start, end)) => start..end,
+            None => return Vec::new(),
+        },
+        None => 0..usize::MAX,
+    };
+    references::find_references(&record.content, false)
+        .into_iter()
+        .filter(|r| r.embed && range.contains(&r.line))
+        .collect()
+}
+
+fn embed_label(embed: &references::BodyReference) -> String {
+    match &embed.anchor {
+        Some(anchor) => format!("{}#{}", embed.id, anchor),
+        None => embed.id.clone(),
+    }
+}
+
+/// Depth-first walk through embeds; true once one lands on a part of `start`
+/// that contains the originating embed line. `path` ends up holding the cycle.
+fn find_transclusion_cycle(
+    graph: &Graph,
+    start: &Record,
+    origin_line: usize,
+    embed: &references::BodyReference,
+    path: &mut Vec<String>,
+    visited: &mut HashSet<String>,
+) -> bool {
+    if embed.id == start.id() {
+        let closes = match &embed.anchor {
+            Some(anchor) => references::section_lines(&start.content, anchor)
+                .is_some_and(|(first, end)| (first..end).contains(&origin_line)),
+            None => true,
+        };
+        if closes {
+            return true;
+        }
+    }
+    if !visited.insert(embed_label(embed)) {
+        return false;
+    }
+    let Some(target) = graph.get(&embed.id) else {
+        return false;
+    };
+    for next in embeds_in(target, embed.anchor.as_deref()) {
+        path.push(embed_label(&next));
+        if find_transclusion_cycle(graph, start, origin_line, &next, path, visited) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Check that links with a declared inverse (or symmetric links) are mirrored
+/// on the target record, e.g. supersedes -> superseded_by
+pub fn check_inverse_links(record: &Record, graph: &Graph) -> Vec<ValidationError> {
+    let id = record.id().to_string();
+    record
+        .frontmatter
+        .links
+        .all_links()
+        .into_iter()
+        .filter_map(|(link_type, target)| {
+            let link_def = LinkType::lookup(link_type)?;
+            let inverse = link_def.inverse_name()?;
+            let target_record = graph.get(target)?;
+            let has_inverse = target_record
+                .frontmatter
+                .links
+                .all_links()
+                .iter()
+                .any(|(t, to)| *t == inverse && *to == id);
+            if has_inverse {
+                None
+            } else {
+                Some(ValidationError::MissingInverseLink {
+                    from: id.clone(),
+                    to: target.to_string(),
+                    link_type: link_type.to_string(),
+                    expected_inverse: inverse.to_string(),
+                })
+            }
+        })
+        .collect()
+}
+
+/// Check for link types that are neither built in nor declared in dg.toml
+pub fn check_unknown_link_types(record: &Record) -> Vec<ValidationError> {
+    record
+        .frontmatter
+        .links
+        .custom
+        .keys()
+        .filter(|link_type| LinkType::lookup(link_type).is_none())
+        .map(|link_type| ValidationError::UnknownLinkType {
+            id: record.id().to_string(),
+            link_type: link_type.clone(),
+        })
+        .collect()
+}
+
+/// Check a record's status is part of its workflow and, if it changed since
+/// the last commit, that the change is a legal transition
+pub fn check_workflow(
+    record: &Record,
+    workflow: &WorkflowConfig,
+    committed_status: Option<&Status>,
+) -> Vec<ValidationError> {
+    let record_type = record.record_type().name();
+    let result = match committed_status {
+        Some(from) if from != record.status() => {
+            workflow.check_transition(record_type, from, record.status(), None)
+        }
+        _ if workflow.allows(record.status()) => Ok(()),
+        _ => Err(WorkflowError::StatusNotAllowed {
+            record_type: record_type.to_string(),
+            status: record.status().to_string(),
+            allowed: workflow.statuses.clone(),
+        }),
+    };
+
+    match result {
+        Ok(()) => vec![],
+        Err(error) => vec![ValidationError::WorkflowViolation {
+            id: record.id().to_string(),
+            error,
+        }],
+    }
+}
+
 /// Check for meaningful content
 fn check_content(record: &Record) -> Vec<ValidationError> {
     let mut errors = Vec::new();
@@ -594,16 +967,20 @@ fn check_principle_conflicts(record: &Record, graph: &Graph) -> Vec<ValidationEr
     let core: Vec<_> = graph.core_records();
 
     // Check if this record has conflicts_with any core record
-    for conflict_id in &record.frontmatter.links.conflicts_with {
-        if let Some(conflict_record) = graph.get(conflict_id) {
+    for conflict in &record.frontmatter.links.conflicts_with {
+        if let Some(conflict_record) = graph.get(&conflict.id) {
             if conflict_record.frontmatter.core {
+                let mut message = format!(
+                    "This record conflicts with '{}' which is a core principle",
+                    conflict_record.title()
+                );
+                if let Some(note) = &conflict.meta.note {
+                    message.push_str(&format!(" ({})", note));
+                }
                 errors.push(ValidationError::PrincipleConflict {
                     id: id.to_string(),
-                    conflicts_with: conflict_id.clone(),
-                    message: format!(
-                        "This record conflicts with '{}' which is a core principle",
-                        conflict_record.title()
-                    ),
+                    conflicts_with: conflict.id.clone(),
+                    message,
                 });
             }
         }
@@ -615,7 +992,8 @@ fn check_principle_conflicts(record: &Record, graph: &Graph) -> Vec<ValidationEr
             .frontmatter
             .links
             .conflicts_with
-            .contains(&id.to_string())
+            .iter()
+            .any(|t| t.id == id)
         {
             errors.push(ValidationError::PrincipleConflict {
                 id: id.to_string(),
@@ -1116,6 +1494,7 @@ mod mention_tests {
     fn make_test_record(content: &str) -> Record {
         Record {
             path: std::path::PathBuf::from("test.md"),
+            format: Default::default(),
             frontmatter: Frontmatter {
                 r#type: RecordType::Decision,
                 id: "DEC-001".to_string(),
@@ -1180,6 +1559,7 @@ mod mention_tests {
     fn make_incident_record(content: &str) -> Record {
         Record {
             path: std::path::PathBuf::from("test.md"),
+            format: Default::default(),
             frontmatter: Frontmatter {
                 r#type: RecordType::Incident,
                 id: "INC-001".to_string(),
@@ -1467,11 +1847,14 @@ mod semantic_validation_tests {
     }
 
     fn make_adr_record(content: &str, status: Status, extra: HashMap<String, Value>) -> Record {
-        let mut links = Links::default();
-        links.implements = vec!["DEC-001".to_string()];
+        let links = Links {
+            implements: vec!["DEC-001".into()],
+            ..Default::default()
+        };
 
         Record {
             path: std::path::PathBuf::from("test.md"),
+            format: Default::default(),
             frontmatter: Frontmatter {
                 r#type: RecordType::Adr,
                 id: "ADR-001".to_string(),
@@ -1496,6 +1879,7 @@ mod semantic_validation_tests {
     ) -> Record {
         Record {
             path: std::path::PathBuf::from("test.md"),
+            format: Default::default(),
             frontmatter: Frontmatter {
                 r#type: RecordType::Incident,
                 id: "INC-001".to_string(),
@@ -1519,11 +1903,13 @@ mod semantic_validation_tests {
 
         let record = make_incident_record("# Timeline\n\nDetails here.", Status::Open, extra);
 
-        let mut config = ValidationConfig::default();
-        config.incident = Some(ValidationRules {
-            required_fields: vec!["severity".to_string(), "impact".to_string()],
+        let config = ValidationConfig {
+            incident: Some(ValidationRules {
+                required_fields: vec!["severity".to_string(), "impact".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(
@@ -1537,11 +1923,13 @@ mod semantic_validation_tests {
         let record =
             make_incident_record("# Timeline\n\nDetails here.", Status::Open, HashMap::new());
 
-        let mut config = ValidationConfig::default();
-        config.incident = Some(ValidationRules {
-            required_fields: vec!["severity".to_string(), "impact".to_string()],
+        let config = ValidationConfig {
+            incident: Some(ValidationRules {
+                required_fields: vec!["severity".to_string(), "impact".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert_eq!(errors.len(), 2, "Should fail for 2 missing fields");
@@ -1558,11 +1946,13 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.adr = Some(ValidationRules {
-            required_links: vec!["implements".to_string(), "depends_on".to_string()],
+        let config = ValidationConfig {
+            adr: Some(ValidationRules {
+                required_links: vec!["implements".to_string(), "depends_on".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(
@@ -1576,11 +1966,13 @@ mod semantic_validation_tests {
         let mut record = make_adr_record("# Context\n\nDetails.", Status::Proposed, HashMap::new());
         record.frontmatter.links = Links::default(); // Clear links
 
-        let mut config = ValidationConfig::default();
-        config.adr = Some(ValidationRules {
-            required_links: vec!["implements".to_string(), "depends_on".to_string()],
+        let config = ValidationConfig {
+            adr: Some(ValidationRules {
+                required_links: vec!["implements".to_string(), "depends_on".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert_eq!(
@@ -1604,15 +1996,17 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.adr = Some(ValidationRules {
-            required_sections: vec![
-                "Context".to_string(),
-                "Decision".to_string(),
-                "Consequences".to_string(),
-            ],
+        let config = ValidationConfig {
+            adr: Some(ValidationRules {
+                required_sections: vec![
+                    "Context".to_string(),
+                    "Decision".to_string(),
+                    "Consequences".to_string(),
+                ],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(errors.is_empty(), "Should pass with all required sections");
@@ -1626,15 +2020,17 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.adr = Some(ValidationRules {
-            required_sections: vec![
-                "Context".to_string(),
-                "Decision".to_string(),
-                "Consequences".to_string(),
-            ],
+        let config = ValidationConfig {
+            adr: Some(ValidationRules {
+                required_sections: vec![
+                    "Context".to_string(),
+                    "Decision".to_string(),
+                    "Consequences".to_string(),
+                ],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(
@@ -1651,15 +2047,17 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.adr = Some(ValidationRules {
-            required_sections: vec![
-                "Context".to_string(),
-                "Decision".to_string(),
-                "Consequences".to_string(),
-            ],
+        let config = ValidationConfig {
+            adr: Some(ValidationRules {
+                required_sections: vec![
+                    "Context".to_string(),
+                    "Decision".to_string(),
+                    "Consequences".to_string(),
+                ],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert_eq!(errors.len(), 2, "Should fail for 2 missing sections");
@@ -1676,11 +2074,13 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.incident = Some(ValidationRules {
-            resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+        let config = ValidationConfig {
+            incident: Some(ValidationRules {
+                resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(
@@ -1697,11 +2097,13 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.incident = Some(ValidationRules {
-            resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+        let config = ValidationConfig {
+            incident: Some(ValidationRules {
+                resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(
@@ -1718,11 +2120,13 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.incident = Some(ValidationRules {
-            resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+        let config = ValidationConfig {
+            incident: Some(ValidationRules {
+                resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert_eq!(
@@ -1743,11 +2147,13 @@ mod semantic_validation_tests {
             HashMap::new(),
         );
 
-        let mut config = ValidationConfig::default();
-        config.adr = Some(ValidationRules {
-            resolved_requires: vec!["Decision".to_string(), "Consequences".to_string()],
+        let config = ValidationConfig {
+            adr: Some(ValidationRules {
+                resolved_requires: vec!["Decision".to_string(), "Consequences".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert_eq!(
@@ -1780,13 +2186,15 @@ mod semantic_validation_tests {
             extra,
         );
 
-        let mut config = ValidationConfig::default();
-        config.incident = Some(ValidationRules {
-            required_fields: vec!["severity".to_string()],
-            required_sections: vec!["Timeline".to_string()],
-            resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+        let config = ValidationConfig {
+            incident: Some(ValidationRules {
+                required_fields: vec!["severity".to_string()],
+                required_sections: vec!["Timeline".to_string()],
+                resolved_requires: vec!["Root Cause".to_string(), "Remediation".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
         let errors = check_semantic_rules(&record, &config);
         assert!(errors.is_empty(), "Should pass all combined validations");
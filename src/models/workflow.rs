@@ -0,0 +1,324 @@
+//! Per-type status workflows declared under `[workflows.<type>]` in dg.toml
+
+use super::{Status, UsersConfig};
+use crate::serve::config::{TransitionConfig, WorkflowConfig};
+
+/// Why a status change was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkflowError {
+    /// The status is not part of the type's workflow
+    StatusNotAllowed {
+        record_type: String,
+        status: String,
+        allowed: Vec<String>,
+    },
+    /// No transition leads from the current status to the new one
+    IllegalTransition {
+        record_type: String,
+        from: String,
+        to: String,
+        next: Vec<String>,
+    },
+    /// The transition is limited to roles the current user doesn't have
+    MissingRole {
+        from: String,
+        to: String,
+        roles: Vec<String>,
+        user: Option<String>,
+    },
+}
+
+impl std::fmt::Display for WorkflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowError::StatusNotAllowed {
+                record_type,
+                status,
+                allowed,
+            } => write!(
+                f,
+                "'{}' is not a valid status for {} records (allowed: {})",
+                status,
+                record_type,
+                allowed.join(", ")
+            ),
+            WorkflowError::IllegalTransition {
+                record_type,
+                from,
+                to,
+                next,
+            } => {
+                write!(
+                    f,
+                    "{} records cannot move from '{}' to '{}'",
+                    record_type, from, to
+                )?;
+                if next.is_empty() {
+                    write!(f, " ('{}' has no legal next states)", from)
+                } else {
+                    write!(f, " (legal next states: {})", next.join(", "))
+                }
+            }
+            WorkflowError::MissingRole {
+                from,
+                to,
+                roles,
+                user,
+            } => {
+                write!(
+                    f,
+                    "moving from '{}' to '{}' requires role: {}",
+                    from,
+                    to,
+                    roles.join(", ")
+                )?;
+                match user {
+                    Some(user) => write!(f, " (current user '{}' has none of them)", user),
+                    None => write!(
+                        f,
+                        " (current user not found in dg.toml [users]; set DG_USER)"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// The person changing a status, matched against `[users]` in dg.toml
+#[derive(Debug, Clone, Default)]
+pub struct Actor {
+    /// Username key in dg.toml, if the current user could be matched
+    pub username: Option<String>,
+    /// Roles and teams the user belongs to
+    pub roles: Vec<String>,
+}
+
+impl Actor {
+    /// Resolve the current user from `DG_USER`, then git's user.email and user.name
+    pub fn current(users: &UsersConfig) -> Actor {
+        let identities = std::env::var("DG_USER")
+            .ok()
+            .into_iter()
+            .chain(git_config("user.email"))
+            .chain(git_config("user.name"))
+            .filter(|s| !s.is_empty());
+
+        for identity in identities {
+            let found = users.users.iter().find(|(username, user)| {
+                username.eq_ignore_ascii_case(&identity)
+                    || user.email.as_deref() == Some(identity.as_str())
+                    || user.name.as_deref() == Some(identity.as_str())
+                    || user.github.as_deref() == Some(identity.as_str())
+            });
+            if let Some((username, user)) = found {
+                return Actor {
+                    username: Some(username.clone()),
+                    roles: user.roles.iter().chain(&user.teams).cloned().collect(),
+                };
+            }
+        }
+
+        Actor::default()
+    }
+}
+
+fn git_config(key: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["config", key])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+impl WorkflowConfig {
+    /// Whether records of this type may have the given status
+    pub fn allows(&self, status: &Status) -> bool {
+        let status = status.to_string();
+        self.statuses
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(&status))
+    }
+
+    fn transitions_from<'a>(
+        &'a self,
+        from: &'a str,
+    ) -> impl Iterator<Item = &'a TransitionConfig> + 'a {
+        self.transitions
+            .iter()
+            .filter(move |t| t.from == "*" || t.from.eq_ignore_ascii_case(from))
+    }
+
+    /// Statuses reachable in one step from `from`
+    pub fn next_states(&self, from: &Status) -> Vec<String> {
+        let from = from.to_string();
+        let candidates: Vec<&String> = if self.transitions.is_empty() {
+            self.statuses.iter().collect()
+        } else {
+            self.transitions_from(&from).flat_map(|t| &t.to).collect()
+        };
+
+        let mut next: Vec<String> = Vec::new();
+        for status in candidates {
+            let status = status.to_lowercase();
+            if status != from && !next.contains(&status) {
+                next.push(status);
+            }
+        }
+        next
+    }
+
+    /// Check a status change. Role restrictions are only enforced when an
+    /// actor is given (lint has no way to know who made a change).
+    pub fn check_transition(
+        &self,
+        record_type: &str,
+        from: &Status,
+        to: &Status,
+        actor: Option<&Actor>,
+    ) -> Result<(), WorkflowError> {
+        if from == to {
+            return Ok(());
+        }
+        if !self.allows(to) {
+            return Err(WorkflowError::StatusNotAllowed {
+                record_type: record_type.to_string(),
+                status: to.to_string(),
+                allowed: self.statuses.clone(),
+            });
+        }
+        if self.transitions.is_empty() {
+            return Ok(());
+        }
+
+        let from_name = from.to_string();
+        let to_name = to.to_string();
+        let matching: Vec<&TransitionConfig> = self
+            .transitions_from(&from_name)
+            .filter(|t| t.to.iter().any(|s| s.eq_ignore_ascii_case(&to_name)))
+            .collect();
+
+        if matching.is_empty() {
+            return Err(WorkflowError::IllegalTransition {
+                record_type: record_type.to_string(),
+                from: from_name,
+                to: to_name,
+                next: self.next_states(from),
+            });
+        }
+
+        let Some(actor) = actor else {
+            return Ok(());
+        };
+        if matching.iter().any(|t| t.roles.is_empty()) {
+            return Ok(());
+        }
+        let roles: Vec<String> = matching.iter().flat_map(|t| t.roles.clone()).collect();
+        if roles.iter().any(|r| actor.roles.contains(r)) {
+            Ok(())
+        } else {
+            Err(WorkflowError::MissingRole {
+                from: from_name,
+                to: to_name,
+                roles,
+                user: actor.username.clone(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn incident_workflow() -> WorkflowConfig {
+        toml::from_str(
+            r#"
+statuses = ["open", "resolved", "closed"]
+
+[[transitions]]
+from = "open"
+to = ["resolved"]
+
+[[transitions]]
+from = "resolved"
+to = ["closed", "open"]
+roles = ["sre"]
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_allowed_transition() {
+        let wf = incident_workflow();
+        assert!(wf
+            .check_transition("incident", &Status::Open, &Status::Resolved, None)
+            .is_ok());
+        assert_eq!(wf.next_states(&Status::Resolved), vec!["closed", "open"]);
+    }
+
+    #[test]
+    fn test_status_not_in_workflow() {
+        let wf = incident_workflow();
+        let err = wf
+            .check_transition("incident", &Status::Open, &Status::Superseded, None)
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::StatusNotAllowed { .. }));
+        assert!(err.to_string().contains("allowed: open, resolved, closed"));
+    }
+
+    #[test]
+    fn test_illegal_transition_lists_next_states() {
+        let wf = incident_workflow();
+        let err = wf
+            .check_transition("incident", &Status::Open, &Status::Closed, None)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "incident records cannot move from 'open' to 'closed' (legal next states: resolved)"
+        );
+    }
+
+    #[test]
+    fn test_roles_enforced_for_actor() {
+        let wf = incident_workflow();
+        let outsider = Actor {
+            username: Some("jared".to_string()),
+            roles: vec!["pm".to_string()],
+        };
+        let sre = Actor {
+            username: Some("gilfoyle".to_string()),
+            roles: vec!["sre".to_string()],
+        };
+
+        let err = wf
+            .check_transition(
+                "incident",
+                &Status::Resolved,
+                &Status::Closed,
+                Some(&outsider),
+            )
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::MissingRole { .. }));
+        assert!(wf
+            .check_transition("incident", &Status::Resolved, &Status::Closed, Some(&sre))
+            .is_ok());
+        // Without an actor (e.g. lint) only the transition itself is checked
+        assert!(wf
+            .check_transition("incident", &Status::Resolved, &Status::Closed, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_no_transitions_allows_any_listed_status() {
+        let wf: WorkflowConfig =
+            toml::from_str(r#"statuses = ["draft", "proposed", "accepted"]"#).unwrap();
+        assert!(wf
+            .check_transition("decision", &Status::Accepted, &Status::Draft, None)
+            .is_ok());
+        assert_eq!(wf.next_states(&Status::Draft), vec!["proposed", "accepted"]);
+    }
+}
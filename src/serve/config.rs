@@ -2,10 +2,13 @@ use crate::models::authors::{AuthorInfo, AuthorsConfig};
 use crate::models::teams::{Team, TeamsConfig};
 use crate::models::users::{User, UsersConfig};
 use crate::models::validation::{validate_config, ValidationError};
-use crate::models::RecordType;
-use anyhow::Result;
+use crate::models::{
+    references, register_custom_types, register_link_types, CustomType, LinkType, RecordType,
+    Status,
+};
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
@@ -58,6 +61,9 @@ pub struct ValidationConfig {
     pub feedback: Option<ValidationRules>,
     #[serde(default)]
     pub legal: Option<ValidationRules>,
+    /// Rules for custom types, keyed by type name (e.g., `[validation.rfc]`)
+    #[serde(flatten)]
+    pub custom: HashMap<String, ValidationRules>,
 }
 
 impl ValidationConfig {
@@ -77,8 +83,224 @@ impl ValidationConfig {
             RecordType::Meeting => self.meeting.as_ref(),
             RecordType::Feedback => self.feedback.as_ref(),
             RecordType::Legal => self.legal.as_ref(),
+            RecordType::Custom(t) => self.custom.get(&t.name),
         }
     }
+
+    /// Whether any record type has semantic rules configured
+    pub fn has_rules(&self) -> bool {
+        RecordType::all()
+            .iter()
+            .any(|t| self.get_rules(t).is_some())
+            || !self.custom.is_empty()
+    }
+}
+
+/// User-defined record type declared under `[types.<name>]`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TypeConfig {
+    /// ID prefix (e.g., "RFC" for RFC-001)
+    pub prefix: String,
+
+    /// Human-readable name (default: capitalized type name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    /// Template file in .templates/ without extension (default: type name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Status for newly created records (default: proposed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_status: Option<String>,
+
+    /// Semantic validation rules, same keys as `[validation.<type>]`
+    #[serde(flatten)]
+    pub validation: ValidationRules,
+}
+
+impl TypeConfig {
+    /// Build the runtime type definition for a `[types.<name>]` entry
+    pub fn to_custom_type(&self, name: &str) -> Result<CustomType> {
+        let name = name.to_lowercase();
+        let prefix = self.prefix.trim().to_uppercase();
+
+        if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(anyhow!(
+                "type '{}': prefix must be ASCII letters, got '{}'",
+                name,
+                self.prefix
+            ));
+        }
+        if RecordType::BUILTIN
+            .iter()
+            .any(|t| t.name() == name || t.prefix() == prefix)
+        {
+            return Err(anyhow!(
+                "type '{}' ({}) collides with a built-in record type",
+                name,
+                prefix
+            ));
+        }
+
+        let default_status = match &self.default_status {
+            Some(s) => Status::from_str(s)
+                .ok_or_else(|| anyhow!("type '{}': unknown default_status '{}'", name, s))?,
+            None => Status::Proposed,
+        };
+
+        let display_name = self.display_name.clone().unwrap_or_else(|| {
+            let mut chars = name.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        });
+
+        Ok(CustomType {
+            template: self.template.clone().unwrap_or_else(|| name.clone()),
+            name,
+            prefix,
+            display_name,
+            default_status,
+        })
+    }
+}
+
+/// How record bodies reference other records (`[references]`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReferencesConfig {
+    /// Treat bare IDs such as DEC-001 in bodies as references, not just `[[DEC-001]]`
+    #[serde(default)]
+    pub bare_ids: bool,
+}
+
+impl ReferencesConfig {
+    fn is_default(&self) -> bool {
+        !self.bare_ids
+    }
+}
+
+/// Git integration settings (`[git]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// Branch that shared record IDs live on, e.g. "origin/main"
+    #[serde(default = "default_base_ref")]
+    pub base: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig {
+            base: default_base_ref(),
+        }
+    }
+}
+
+impl GitConfig {
+    fn is_default(&self) -> bool {
+        self.base == default_base_ref()
+    }
+}
+
+fn default_base_ref() -> String {
+    "origin/main".to_string()
+}
+
+/// Link type declared under `[links.<name>]`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LinkTypeConfig {
+    /// Link type added back on the target (e.g., "blocked_by" for "blocks")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inverse: Option<String>,
+
+    /// Mirror the link with the same type on the target
+    #[serde(default)]
+    pub symmetric: bool,
+
+    /// Label shown in graphs and the web UI (default: the link type name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Report cycles along this link type in `dg lint` and order records by
+    /// it in `dg order` (default: true for depends_on, refines and supersedes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acyclic: Option<bool>,
+}
+
+impl LinkTypeConfig {
+    /// Build the runtime link type for a `[links.<name>]` entry
+    pub fn to_link_type(&self, name: &str) -> Result<LinkType> {
+        let is_valid_name = |n: &str| {
+            !n.is_empty()
+                && n.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        };
+        if !is_valid_name(name) {
+            return Err(anyhow!(
+                "link type '{}': names must be lowercase letters, digits and underscores",
+                name
+            ));
+        }
+        if let Some(inverse) = &self.inverse {
+            if self.symmetric {
+                return Err(anyhow!(
+                    "link type '{}': symmetric links cannot declare an inverse",
+                    name
+                ));
+            }
+            if !is_valid_name(inverse) || inverse == name {
+                return Err(anyhow!(
+                    "link type '{}': invalid inverse '{}'",
+                    name,
+                    inverse
+                ));
+            }
+        }
+
+        let acyclic = self
+            .acyclic
+            .unwrap_or_else(|| LinkType::acyclic_by_default(name));
+        if acyclic && self.symmetric {
+            return Err(anyhow!(
+                "link type '{}': symmetric links cannot be acyclic",
+                name
+            ));
+        }
+
+        Ok(LinkType {
+            name: name.to_string(),
+            inverse: self.inverse.clone(),
+            symmetric: self.symmetric,
+            label: self.label.clone().unwrap_or_else(|| name.to_string()),
+            acyclic,
+        })
+    }
+}
+
+/// Status workflow for a record type, declared under `[workflows.<type>]`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WorkflowConfig {
+    /// Statuses records of this type may have
+    pub statuses: Vec<String>,
+
+    /// Allowed transitions (default: any change between allowed statuses)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<TransitionConfig>,
+}
+
+/// One `[[workflows.<type>.transitions]]` entry
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TransitionConfig {
+    /// Status the record is in, or "*" for any status
+    pub from: String,
+
+    /// Statuses the record may move to
+    pub to: Vec<String>,
+
+    /// Roles or teams allowed to perform the transition (default: anyone)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
 }
 
 /// Root configuration loaded from dg.toml
@@ -103,6 +325,30 @@ pub struct DgConfig {
     /// Semantic validation rules per record type
     #[serde(default)]
     pub validation: ValidationConfig,
+
+    /// User-defined record types
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub types: HashMap<String, TypeConfig>,
+
+    /// User-defined link types
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub links: HashMap<String, LinkTypeConfig>,
+
+    /// Status workflows per record type
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub workflows: HashMap<String, WorkflowConfig>,
+
+    /// Inline references in record bodies
+    #[serde(default, skip_serializing_if = "ReferencesConfig::is_default")]
+    pub references: ReferencesConfig,
+
+    /// Git integration
+    #[serde(default, skip_serializing_if = "GitConfig::is_default")]
+    pub git: GitConfig,
+
+    /// Records that govern code paths, keyed by glob (`[governance]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub governance: BTreeMap<String, Vec<String>>,
 }
 
 impl DgConfig {
@@ -133,10 +379,7 @@ impl DgConfig {
             let site: SiteConfig = serde_yaml::from_str(&content)?;
             return Ok(DgConfig {
                 site,
-                authors: HashMap::new(),
-                users: HashMap::new(),
-                teams: HashMap::new(),
-                validation: ValidationConfig::default(),
+                ..Default::default()
             });
         }
 
@@ -162,9 +405,73 @@ impl DgConfig {
         }
     }
 
-    /// Get ValidationConfig
-    pub fn validation_config(&self) -> &ValidationConfig {
-        &self.validation
+    /// Get ValidationConfig, including rules declared on custom types.
+    /// An explicit `[validation.<type>]` section wins over `[types.<type>]` rules.
+    pub fn validation_config(&self) -> ValidationConfig {
+        let mut validation = self.validation.clone();
+        for (name, type_config) in &self.types {
+            let rules = &type_config.validation;
+            let has_rules = !rules.required_fields.is_empty()
+                || !rules.required_links.is_empty()
+                || !rules.required_sections.is_empty()
+                || !rules.resolved_requires.is_empty();
+            if has_rules {
+                validation
+                    .custom
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| rules.clone());
+            }
+        }
+        validation
+    }
+
+    /// Get the status workflow for a record type, if one is configured
+    pub fn workflow(&self, record_type: &RecordType) -> Option<&WorkflowConfig> {
+        self.workflows.get(record_type.name())
+    }
+
+    /// Register `[types.<name>]` entries so records of those types can be parsed.
+    /// Invalid type declarations are reported and skipped.
+    pub fn register_types(&self) {
+        let mut names: Vec<_> = self.types.keys().collect();
+        names.sort();
+
+        let mut custom: Vec<CustomType> = Vec::new();
+        for name in names {
+            match self.types[name].to_custom_type(name) {
+                Ok(t) if custom.iter().any(|c| c.prefix == t.prefix) => eprintln!(
+                    "Warning: dg.toml: type '{}' reuses prefix {} of another custom type",
+                    t.name, t.prefix
+                ),
+                Ok(t) => custom.push(t),
+                Err(e) => eprintln!("Warning: dg.toml: {}", e),
+            }
+        }
+        register_custom_types(custom);
+    }
+
+    /// Apply `[references]` settings used when building body `mentions` edges
+    pub fn register_references(&self) {
+        references::set_bare_ids(self.references.bare_ids);
+    }
+
+    /// Register `[links.<name>]` entries so `dg link` and validation know about
+    /// them. Invalid declarations are reported and skipped.
+    pub fn register_link_types(&self) {
+        let mut names: Vec<_> = self.links.keys().collect();
+        names.sort();
+
+        let link_types = names
+            .into_iter()
+            .filter_map(|name| match self.links[name].to_link_type(name) {
+                Ok(t) => Some(t),
+                Err(e) => {
+                    eprintln!("Warning: dg.toml: {}", e);
+                    None
+                }
+            })
+            .collect();
+        register_link_types(link_types);
     }
 
     /// Get the path to the config file
@@ -1,5 +1,7 @@
+use crate::models::centrality::Centrality;
 use crate::models::d2::D2Renderer;
-use crate::models::{Graph, TeamsConfig, UsersConfig};
+use crate::models::references;
+use crate::models::{Graph, LinkType, RecordType, TeamsConfig, UsersConfig};
 use crate::serve::config::{DgConfig, SiteConfig};
 use crate::serve::templates::create_environment;
 use anyhow::Result;
@@ -54,21 +56,9 @@ fn avatar_color_class(name: &str) -> &'static str {
 
 /// Helper to convert type code to display name (same as server.rs)
 fn type_to_display_name(type_code: &str) -> &'static str {
-    match type_code {
-        "decision" => "Decision",
-        "strategy" => "Strategy",
-        "policy" => "Policy",
-        "customer" => "Customer",
-        "opportunity" => "Opportunity",
-        "process" => "Process",
-        "hiring" => "Hiring",
-        "adr" => "Architecture",
-        "incident" => "Incident",
-        "runbook" => "Runbook",
-        "meeting" => "Meeting",
-        "legal" => "Legal",
-        _ => "Other",
-    }
+    RecordType::from_prefix(type_code)
+        .map(|t| t.display_name())
+        .unwrap_or("Other")
 }
 
 pub fn generate_site(
@@ -112,7 +102,7 @@ pub fn generate_site(
     // Generate index page
     let index_tmpl = env.get_template("index.html")?;
     let mut records: Vec<_> = graph.all_records().collect();
-    records.sort_by(|a, b| b.frontmatter.updated.cmp(&a.frontmatter.updated));
+    records.sort_by_key(|r| std::cmp::Reverse(r.frontmatter.updated));
     let records_data: Vec<_> = records.iter().map(|r| record_to_context(r)).collect();
 
     let mut type_codes: Vec<_> = records_data
@@ -154,7 +144,7 @@ pub fn generate_site(
 
         // Add content as HTML using pulldown-cmark
         let content_html =
-            markdown_to_html_with_mentions(&record.content, &valid_mentions, base_url);
+            markdown_to_html_with_mentions(&record.content, &valid_mentions, Some(graph), base_url);
         // Linkify action item owners in tables with Notion-style embeds
         let content_html =
             linkify_action_item_owners(&content_html, &users_config, &teams_config, base_url);
@@ -167,14 +157,16 @@ pub fn generate_site(
         let links: Vec<_> = record
             .frontmatter
             .links
-            .all_links()
+            .all_targets()
             .iter()
             .map(|(lt, target)| {
-                let title = graph.get(target).map(|r| r.title().to_string());
+                let title = graph.get(&target.id).map(|r| r.title().to_string());
                 serde_json::json!({
                     "type": lt,
-                    "target": target,
+                    "label": LinkType::label_for(lt),
+                    "target": target.id,
                     "title": title,
+                    "meta": target.meta.summary(),
                 })
             })
             .collect();
@@ -205,11 +197,15 @@ pub fn generate_site(
                 "core": r.frontmatter.core,
             })
         }).collect::<Vec<_>>(),
-        "edges": graph.edges.iter().map(|e| {
+        "edges": graph.edges().iter().map(|e| {
             serde_json::json!({
                 "source": e.from,
                 "target": e.to,
                 "type": e.link_type,
+                "label": LinkType::label_for(&e.link_type),
+                "note": e.meta.note,
+                "since": e.meta.since,
+                "strength": e.meta.strength,
             })
         }).collect::<Vec<_>>(),
     });
@@ -234,11 +230,15 @@ pub fn generate_site(
                 "core": r.frontmatter.core,
             })
         }).collect::<Vec<_>>(),
-        "edges": graph.edges.iter().map(|e| {
+        "edges": graph.edges().iter().map(|e| {
             serde_json::json!({
                 "source": e.from,
                 "target": e.to,
                 "type": e.link_type,
+                "label": LinkType::label_for(&e.link_type),
+                "note": e.meta.note,
+                "since": e.meta.since,
+                "strength": e.meta.strength,
             })
         }).collect::<Vec<_>>(),
     });
@@ -253,34 +253,7 @@ pub fn generate_site(
 
     // Generate stats page
     let stats_tmpl = env.get_template("stats.html")?;
-    let stats = graph.stats();
-    let core_count = graph.core_records().len();
-
-    let by_type: Vec<_> = stats
-        .by_type
-        .iter()
-        .map(|(t, c)| {
-            serde_json::json!({
-                "type": t,
-                "type_display": type_to_display_name(t),
-                "count": c
-            })
-        })
-        .collect();
-
-    let by_status: Vec<_> = stats
-        .by_status
-        .iter()
-        .map(|(s, c)| serde_json::json!({ "status": s, "count": c }))
-        .collect();
-
-    let stats_ctx = serde_json::json!({
-        "total_records": stats.total_records,
-        "total_edges": stats.total_edges,
-        "core": core_count,
-        "by_type": by_type,
-        "by_status": by_status,
-    });
+    let stats_ctx = stats_context(graph);
     let stats_html = stats_tmpl.render(context! {
         site => &site_config,
         has_users => has_users,
@@ -662,11 +635,15 @@ pub fn generate_site(
                 "updated": r.frontmatter.updated.to_string(),
             })
         }).collect::<Vec<_>>(),
-        "edges": graph.edges.iter().map(|e| {
+        "edges": graph.edges().iter().map(|e| {
             serde_json::json!({
                 "source": e.from,
                 "target": e.to,
                 "type": e.link_type,
+                "label": LinkType::label_for(&e.link_type),
+                "note": e.meta.note,
+                "since": e.meta.since,
+                "strength": e.meta.strength,
             })
         }).collect::<Vec<_>>(),
     });
@@ -760,6 +737,77 @@ fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Counts and centrality for the stats page
+pub fn stats_context(graph: &Graph) -> serde_json::Value {
+    let stats = graph.stats();
+    let core_count = graph.core_records().len();
+
+    let by_type: Vec<_> = stats
+        .by_type
+        .iter()
+        .map(|(t, c)| {
+            serde_json::json!({
+                "type": t,
+                "type_display": type_to_display_name(t),
+                "count": c
+            })
+        })
+        .collect();
+
+    let by_status: Vec<_> = stats
+        .by_status
+        .iter()
+        .map(|(s, c)| serde_json::json!({ "status": s, "count": c }))
+        .collect();
+
+    let centrality = Centrality::compute(graph);
+    let title = |id: &str| graph.get(id).map(|r| r.title().to_string());
+    let central: Vec<_> = centrality
+        .records
+        .iter()
+        .filter(|r| r.in_degree + r.out_degree > 0)
+        .take(10)
+        .map(|r| {
+            serde_json::json!({
+                "id": r.id,
+                "title": title(&r.id),
+                "in_degree": r.in_degree,
+                "out_degree": r.out_degree,
+                "betweenness": format!("{:.2}", r.betweenness),
+                "pagerank": format!("{:.3}", r.pagerank),
+            })
+        })
+        .collect();
+    let keystones: Vec<_> = centrality
+        .articulation_points
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id,
+                "title": title(id),
+                "disconnects": centrality.get(id).map_or(0, |r| r.disconnects),
+            })
+        })
+        .collect();
+    let suggestions: Vec<_> = centrality
+        .core_suggestions(graph)
+        .iter()
+        .map(|r| serde_json::json!({ "id": r.id, "title": title(&r.id) }))
+        .collect();
+
+    serde_json::json!({
+        "total_records": stats.total_records,
+        "total_edges": stats.total_edges,
+        "core": core_count,
+        "by_type": by_type,
+        "by_status": by_status,
+        "components": centrality.components.len(),
+        "central": central,
+        "keystones": keystones,
+        "core_suggestions": suggestions,
+    })
+}
+
 fn record_to_context(record: &crate::models::Record) -> serde_json::Map<String, serde_json::Value> {
     let mut map = serde_json::Map::new();
     map.insert(
@@ -818,14 +866,32 @@ fn record_to_context(record: &crate::models::Record) -> serde_json::Map<String,
 /// Convert markdown to HTML using pulldown-cmark (without mention validation)
 #[allow(dead_code)]
 pub fn markdown_to_html(md: &str) -> String {
-    markdown_to_html_with_mentions(md, &HashSet::new(), "")
+    markdown_to_html_with_mentions(md, &HashSet::new(), None, "")
 }
 
-/// Convert markdown to HTML with validated @mentions
+/// Convert markdown to HTML with validated @mentions and `[[ID]]` record
+/// references (checked against `graph` when given). With a graph,
+/// `![[ID#heading]]` embeds the rendered section of another record.
 pub fn markdown_to_html_with_mentions(
     md: &str,
     valid_mentions: &HashSet<String>,
+    graph: Option<&Graph>,
     base_url: &str,
+) -> String {
+    render_markdown(md, valid_mentions, graph, base_url, &mut Vec::new(), true)
+}
+
+/// Render markdown, transcluding embeds. `stack` holds the `ID#heading`
+/// sections currently being embedded, to stop cycles. Heading anchors are
+/// only added at the top level so embedded headings don't clash with the
+/// record's own.
+fn render_markdown(
+    md: &str,
+    valid_mentions: &HashSet<String>,
+    graph: Option<&Graph>,
+    base_url: &str,
+    stack: &mut Vec<String>,
+    heading_ids: bool,
 ) -> String {
     // Strip HTML comments before rendering
     let comment_re = Regex::new(r"<!--[\s\S]*?-->").unwrap();
@@ -846,8 +912,123 @@ pub fn markdown_to_html_with_mentions(
     // Render D2 code blocks to SVG (server-side)
     let html_output = render_d2_blocks(&html_output);
 
+    // Link [[ID]] references to their records; embeds are rendered now but
+    // spliced in last so mentions inside them aren't linked twice
+    let mut embedded: Vec<String> = Vec::new();
+    let html_output = linkify_record_refs(&html_output, graph, base_url, &mut |link| {
+        let html = transclude(link, valid_mentions, graph?, base_url, stack);
+        embedded.push(html);
+        Some(format!("\u{1}transclusion-{}\u{1}", embedded.len() - 1))
+    });
+
     // Linkify @mentions (only valid ones if validation set provided)
-    linkify_mentions(&html_output, valid_mentions, base_url)
+    let mut html_output = linkify_mentions(&html_output, valid_mentions, base_url);
+
+    if heading_ids {
+        html_output = add_heading_ids(&html_output);
+    }
+
+    for (i, html) in embedded.iter().enumerate() {
+        let placeholder = format!("\u{1}transclusion-{}\u{1}", i);
+        html_output = html_output
+            .replace(&format!("<p>{}</p>", placeholder), html)
+            .replace(&placeholder, html);
+    }
+    html_output
+}
+
+/// Render the embedded section of another record
+fn transclude(
+    link: &references::WikiLink,
+    valid_mentions: &HashSet<String>,
+    graph: &Graph,
+    base_url: &str,
+    stack: &mut Vec<String>,
+) -> String {
+    let key = match &link.anchor {
+        Some(anchor) => format!("{}#{}", link.id, anchor),
+        None => link.id.clone(),
+    };
+    let error = |message: String| {
+        format!(
+            r#"<div class="transclusion-error border border-red-500/40 text-red-400 rounded px-3 py-2 my-4 text-sm">{}</div>"#,
+            htmlescape::encode_minimal(&message)
+        )
+    };
+
+    let Some(record) = graph.get(&link.id) else {
+        return error(format!("Cannot embed {}: record not found", key));
+    };
+    if stack.contains(&key) {
+        let mut cycle = stack.clone();
+        cycle.push(key);
+        return error(format!("Transclusion cycle: {}", cycle.join(" → ")));
+    }
+    let markdown = match &link.anchor {
+        Some(anchor) => match references::section(&record.content, anchor) {
+            Some(section) => section,
+            None => return error(format!("Cannot embed {}: no such heading", key)),
+        },
+        None => record.content.clone(),
+    };
+
+    stack.push(key.clone());
+    let inner = render_markdown(
+        &markdown,
+        valid_mentions,
+        Some(graph),
+        base_url,
+        stack,
+        false,
+    );
+    stack.pop();
+
+    let href = match &link.anchor {
+        Some(anchor) => format!("{}/records/{}#{}", base_url, link.id, anchor),
+        None => format!("{}/records/{}", base_url, link.id),
+    };
+    format!(
+        r#"<div class="transclusion border-l-2 border-piper-light/40 pl-4 my-4" data-source="{}">
+{}<div class="transclusion-source text-xs text-slate-500"><a href="{}" class="record-ref hover:underline">{}</a></div>
+</div>"#,
+        key,
+        inner,
+        href,
+        htmlescape::encode_minimal(&reference_text(record, link.anchor.as_deref()))
+    )
+}
+
+/// Default link text for a reference: the ID, plus the heading for section links
+fn reference_text(record: &crate::models::Record, anchor: Option<&str>) -> String {
+    let heading = anchor.and_then(|anchor| {
+        references::headings(&record.content)
+            .into_iter()
+            .find(|h| h.slug == anchor)
+    });
+    match heading {
+        Some(h) => format!("{} › {}", record.id(), h.text),
+        None => record.id().to_string(),
+    }
+}
+
+/// Give headings the anchors `[[ID#heading]]` links point at
+fn add_heading_ids(html: &str) -> String {
+    let heading_re = Regex::new(r"(?s)<h([1-6])>(.*?)</h([1-6])>").unwrap();
+    let tag_re = Regex::new(r"<[^>]*>").unwrap();
+    let mut slugs = references::SlugCounter::default();
+
+    heading_re
+        .replace_all(html, |caps: &regex::Captures| {
+            let text = html_unescape(&tag_re.replace_all(&caps[2], ""));
+            format!(
+                r#"<h{} id="{}">{}</h{}>"#,
+                &caps[1],
+                slugs.unique(&text),
+                &caps[2],
+                &caps[3]
+            )
+        })
+        .to_string()
 }
 
 /// Render D2 code blocks to inline SVG
@@ -1040,6 +1221,97 @@ fn html_unescape(s: &str) -> String {
         .replace("&#39;", "'")
 }
 
+/// Convert `[[ID]]`, `[[ID#heading]]` and `[[ID|label]]` (and bare IDs, if
+/// enabled) to record links. Existing links, code and tag attributes are left
+/// untouched; references to missing records or headings are marked instead of
+/// linked. `![[...]]` embeds are handed to `embed`, falling back to a link
+/// when it returns None.
+fn linkify_record_refs(
+    html: &str,
+    graph: Option<&Graph>,
+    base_url: &str,
+    embed: &mut dyn FnMut(&references::WikiLink) -> Option<String>,
+) -> String {
+    let bare_ids = graph.is_some() && references::bare_ids_enabled();
+    let token_re = Regex::new(
+        r"(?s)<a\b.*?</a>|<pre\b.*?</pre>|<code\b.*?</code>|<[^>]*>|!?\[\[[^\]]*\]\]|\b[A-Z][A-Z0-9]*-(?:NEW-)?\d+\b",
+    )
+    .unwrap();
+
+    token_re
+        .replace_all(html, |caps: &regex::Captures| {
+            let token = &caps[0];
+            let link = match references::wiki_link_regex().captures(token) {
+                Some(cap) if cap[0].len() == token.len() => {
+                    references::WikiLink::from_captures(&cap)
+                }
+                _ if bare_ids && !token.starts_with(['<', '[', '!']) => {
+                    match graph.and_then(|g| g.get(token)) {
+                        Some(_) => references::WikiLink {
+                            id: token.to_string(),
+                            anchor: None,
+                            label: None,
+                            embed: false,
+                        },
+                        None => return token.to_string(),
+                    }
+                }
+                _ => return token.to_string(),
+            };
+
+            if link.embed {
+                if let Some(html) = embed(&link) {
+                    return html;
+                }
+            }
+
+            let href = match &link.anchor {
+                Some(anchor) => format!("{}/records/{}#{}", base_url, link.id, anchor),
+                None => format!("{}/records/{}", base_url, link.id),
+            };
+            let target = match &link.anchor {
+                Some(anchor) => format!("{}#{}", link.id, anchor),
+                None => link.id.clone(),
+            };
+            let Some(graph) = graph else {
+                let text = link.label.unwrap_or(target);
+                return format!(
+                    r#"<a href="{}" class="record-ref text-piper-light hover:underline" title="">{}</a>"#,
+                    href, text
+                );
+            };
+            let Some(record) = graph.get(&link.id) else {
+                let text = link.label.unwrap_or(target);
+                return format!(
+                    r#"<span class="record-ref-missing text-red-400" title="Record not found: {}">{}</span>"#,
+                    link.id, text
+                );
+            };
+
+            let heading_exists = link.anchor.as_ref().is_none_or(|anchor| {
+                references::headings(&record.content)
+                    .iter()
+                    .any(|h| &h.slug == anchor)
+            });
+            let text = link.label.clone().unwrap_or_else(|| {
+                htmlescape::encode_minimal(&reference_text(record, link.anchor.as_deref()))
+            });
+            if !heading_exists {
+                return format!(
+                    r#"<span class="record-ref-missing text-red-400" title="Heading not found: {}">{}</span>"#,
+                    target, text
+                );
+            }
+            format!(
+                r#"<a href="{}" class="record-ref text-piper-light hover:underline" title="{}">{}</a>"#,
+                href,
+                htmlescape::encode_attribute(record.title()),
+                text
+            )
+        })
+        .to_string()
+}
+
 /// Convert @username mentions to clickable links (only if user/team exists)
 fn linkify_mentions(html: &str, valid_mentions: &HashSet<String>, base_url: &str) -> String {
     let mention_re = Regex::new(r"@([a-zA-Z][a-zA-Z0-9_-]*)").unwrap();
@@ -1292,6 +1564,27 @@ After.
         );
     }
 
+    #[test]
+    fn test_wiki_links_rendered() {
+        let md = "As part of [[STR-001]] and [[DEC-002|the pricing call]].\n\n`[[DEC-003]]`\n";
+        let html = markdown_to_html(md);
+
+        assert!(html.contains(r#"<a href="/records/STR-001" class="record-ref"#));
+        assert!(html.contains(r#"/records/DEC-002" class="record-ref text-piper-light hover:underline" title="">the pricing call</a>"#));
+        // References inside code stay literal
+        assert!(html.contains("<code>[[DEC-003]]</code>"));
+    }
+
+    #[test]
+    fn test_heading_ids_and_section_links() {
+        let md = "## Alternatives Considered\n\nSee [[DEC-001#Alternatives Considered]].\n\n## Notes\n\n## Notes\n";
+        let html = markdown_to_html(md);
+
+        assert!(html.contains(r#"<h2 id="alternatives-considered">Alternatives Considered</h2>"#));
+        assert!(html.contains(r#"<h2 id="notes-1">Notes</h2>"#));
+        assert!(html.contains(r#"href="/records/DEC-001#alternatives-considered""#));
+    }
+
     #[test]
     fn test_html_unescape() {
         assert_eq!(html_unescape("a &lt; b"), "a < b");
@@ -1,9 +1,15 @@
 use crate::git::GitHistory;
+use crate::models::history::RecordHistory;
+use crate::models::pattern::{rows_to_json, PathQuery};
+use crate::models::query::Query as RecordQuery;
 use crate::models::teams::TeamsConfig;
 use crate::models::users::UsersConfig;
-use crate::models::{graph_to_d2, AuthorsConfig, D2Renderer, Graph};
-use crate::serve::config::{DgConfig, SiteConfig};
-use crate::serve::generator::{linkify_action_item_owners, markdown_to_html_with_mentions};
+use crate::models::workflow::{Actor, WorkflowError};
+use crate::models::{graph_to_d2, AuthorsConfig, D2Renderer, Graph, LinkType, RecordType};
+use crate::serve::config::{DgConfig, SiteConfig, WorkflowConfig};
+use crate::serve::generator::{
+    linkify_action_item_owners, markdown_to_html_with_mentions, stats_context,
+};
 use crate::serve::templates::create_environment;
 use anyhow::Result;
 use axum::{
@@ -40,6 +46,7 @@ pub struct AppState {
     authors_config: AuthorsConfig,
     users_config: UsersConfig,
     teams_config: TeamsConfig,
+    workflows: HashMap<String, WorkflowConfig>,
     valid_mentions: std::collections::HashSet<String>,
 }
 
@@ -74,6 +81,7 @@ pub async fn run_server(
         authors_config: dg_config.authors_config(),
         users_config: dg_config.users_config(),
         teams_config: dg_config.teams_config(),
+        workflows: dg_config.workflows.clone(),
         valid_mentions,
     });
 
@@ -98,8 +106,11 @@ pub async fn run_server(
             get(api_record).put(save_record_handler),
         )
         .route("/api/records/{id}/raw", get(api_record_raw))
+        .route("/api/records/{id}/history", get(api_record_history))
         .route("/api/render", axum::routing::post(api_render))
         .route("/api/graph", get(api_graph))
+        .route("/api/query", get(api_query))
+        .route("/api/path", get(api_path))
         .route("/diagrams/{id}", get(diagram_handler))
         .route("/reload", get(reload_handler))
         // Embedded static assets (KaTeX) for offline support
@@ -212,7 +223,7 @@ async fn index_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
 
     // Sort records by updated date (newest first)
     let mut records: Vec<_> = graph.all_records().collect();
-    records.sort_by(|a, b| b.frontmatter.updated.cmp(&a.frontmatter.updated));
+    records.sort_by_key(|r| std::cmp::Reverse(r.frontmatter.updated));
 
     let records_data: Vec<_> = records.iter().map(|r| record_to_json(r)).collect();
 
@@ -280,7 +291,8 @@ async fn record_handler(
     let mut ctx = record_to_json(record);
 
     // Add content as HTML
-    let content_html = markdown_to_html_with_mentions(&record.content, &state.valid_mentions, "");
+    let content_html =
+        markdown_to_html_with_mentions(&record.content, &state.valid_mentions, Some(&graph), "");
     // Linkify action item owners in tables
     let content_html =
         linkify_action_item_owners(&content_html, &state.users_config, &state.teams_config, "");
@@ -293,14 +305,16 @@ async fn record_handler(
     let links: Vec<_> = record
         .frontmatter
         .links
-        .all_links()
+        .all_targets()
         .iter()
         .map(|(lt, target)| {
-            let title = graph.get(target).map(|r| r.title().to_string());
+            let title = graph.get(&target.id).map(|r| r.title().to_string());
             serde_json::json!({
                 "type": lt,
-                "target": target,
+                "label": LinkType::label_for(lt),
+                "target": target.id,
                 "title": title,
+                "meta": target.meta.summary(),
             })
         })
         .collect();
@@ -314,8 +328,10 @@ async fn record_handler(
             let title = graph.get(&edge.from).map(|r| r.title().to_string());
             serde_json::json!({
                 "type": &edge.link_type,
+                "label": LinkType::incoming_label_for(&edge.link_type),
                 "source": &edge.from,
                 "title": title,
+                "meta": edge.meta.summary(),
             })
         })
         .collect();
@@ -327,10 +343,10 @@ async fn record_handler(
         .links
         .superseded_by
         .iter()
-        .map(|id| {
-            let title = graph.get(id).map(|r| r.title().to_string());
+        .map(|target| {
+            let title = graph.get(&target.id).map(|r| r.title().to_string());
             serde_json::json!({
-                "id": id,
+                "id": target.id,
                 "title": title,
             })
         })
@@ -340,6 +356,43 @@ async fn record_handler(
         serde_json::Value::Array(superseded_by),
     );
 
+    // Candidates for the "how is this connected to" picker
+    let mut others: Vec<_> = graph
+        .all_records()
+        .filter(|r| r.id() != record.id())
+        .map(|r| serde_json::json!({ "id": r.id(), "title": r.title() }))
+        .collect();
+    others.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    ctx.insert(
+        "other_records".to_string(),
+        serde_json::Value::Array(others),
+    );
+
+    // The History tab reads from git through /api/records/{id}/history
+    let history = GitHistory::new(&state.docs_dir).ok();
+    ctx.insert(
+        "has_history".to_string(),
+        serde_json::Value::Bool(history.is_some()),
+    );
+
+    // Commits whose trailers name this record
+    let commits: Vec<_> = history
+        .and_then(|h| h.commits_for(record.id()).ok())
+        .unwrap_or_default()
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "commit": c.commit,
+                "short": c.short(),
+                "date": c.date.format("%Y-%m-%d").to_string(),
+                "author": c.author,
+                "message": c.message,
+                "files": c.files,
+            })
+        })
+        .collect();
+    ctx.insert("commits".to_string(), serde_json::Value::Array(commits));
+
     // Resolve author info with team memberships
     let resolved_authors: Vec<_> = record
         .frontmatter
@@ -400,32 +453,12 @@ async fn graph_page_handler(State(state): State<Arc<AppState>>) -> impl IntoResp
     let graph = state.graph.read().await;
     let env = create_environment();
 
-    let graph_data = serde_json::json!({
-        "nodes": graph.all_records().map(|r| {
-            // Resolve author names
-            let authors: Vec<String> = r.frontmatter.authors.iter().map(|username| {
-                state.users_config.get(username)
-                    .map(|u| u.display_name(username))
-                    .unwrap_or_else(|| username.to_string())
-            }).collect();
-            serde_json::json!({
-                "id": r.id(),
-                "title": r.title(),
-                "type": r.record_type().to_string(),
-                "type_name": r.record_type().display_name(),
-                "date": r.frontmatter.created.to_string(),
-                "authors": authors,
-                "core": r.frontmatter.core,
-            })
-        }).collect::<Vec<_>>(),
-        "edges": graph.edges.iter().map(|e| {
-            serde_json::json!({
-                "source": e.from,
-                "target": e.to,
-                "type": e.link_type,
-            })
-        }).collect::<Vec<_>>(),
-    });
+    let graph_data = graph_json(&graph, &state.users_config);
+    // Days records changed on, for the time slider
+    let history_dates: Vec<String> = GitHistory::new(&state.docs_dir)
+        .and_then(|h| h.record_change_dates())
+        .map(|dates| dates.iter().map(|d| d.to_string()).collect())
+        .unwrap_or_default();
 
     match env.get_template("graph.html") {
         Ok(tmpl) => {
@@ -434,6 +467,7 @@ async fn graph_page_handler(State(state): State<Arc<AppState>>) -> impl IntoResp
                 has_users => state.has_users(),
                 current_page => "graph",
                 graph_data => graph_data.to_string(),
+                history_dates => serde_json::to_string(&history_dates).unwrap_or_default(),
             }) {
                 Ok(html) => Html(html).into_response(),
                 Err(e) => (
@@ -465,11 +499,15 @@ async fn timeline_handler(State(state): State<Arc<AppState>>) -> impl IntoRespon
                 "core": r.frontmatter.core,
             })
         }).collect::<Vec<_>>(),
-        "edges": graph.edges.iter().map(|e| {
+        "edges": graph.edges().iter().map(|e| {
             serde_json::json!({
                 "source": e.from,
                 "target": e.to,
                 "type": e.link_type,
+                "label": LinkType::label_for(&e.link_type),
+                "note": e.meta.note,
+                "since": e.meta.since,
+                "strength": e.meta.strength,
             })
         }).collect::<Vec<_>>(),
     });
@@ -501,34 +539,7 @@ async fn timeline_handler(State(state): State<Arc<AppState>>) -> impl IntoRespon
 async fn stats_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let graph = state.graph.read().await;
     let env = create_environment();
-    let stats = graph.stats();
-    let core_count = graph.core_records().len();
-
-    let by_type: Vec<_> = stats
-        .by_type
-        .iter()
-        .map(|(t, c)| {
-            serde_json::json!({
-                "type": t,
-                "type_display": type_to_display_name(t),
-                "count": c
-            })
-        })
-        .collect();
-
-    let by_status: Vec<_> = stats
-        .by_status
-        .iter()
-        .map(|(s, c)| serde_json::json!({ "status": s, "count": c }))
-        .collect();
-
-    let stats_ctx = serde_json::json!({
-        "total_records": stats.total_records,
-        "total_edges": stats.total_edges,
-        "core": core_count,
-        "by_type": by_type,
-        "by_status": by_status,
-    });
+    let stats_ctx = stats_context(&graph);
 
     match env.get_template("stats.html") {
         Ok(tmpl) => {
@@ -557,10 +568,35 @@ async fn stats_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
 async fn api_records(
     State(state): State<Arc<AppState>>,
     Query(params): Query<HashMap<String, String>>,
-) -> impl IntoResponse {
+) -> Response {
     let graph = state.graph.read().await;
 
-    let mut records: Vec<_> = graph.all_records().collect();
+    // Query language: free text is ranked, with highlighted snippets
+    let query = match RecordQuery::parse(params.get("q").map(|q| q.as_str()).unwrap_or("")) {
+        Ok(query) => query,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.message,
+                    "column": e.column(),
+                })),
+            )
+                .into_response()
+        }
+    };
+    let mut search: HashMap<String, (f64, Option<String>)> = HashMap::new();
+    let mut records: Vec<_> = query
+        .run(&graph, true)
+        .into_iter()
+        .map(|hit| {
+            if query.has_text() {
+                let snippet = hit.snippet.as_ref().map(|s| s.to_html());
+                search.insert(hit.record.id().to_string(), (hit.score, snippet));
+            }
+            hit.record
+        })
+        .collect();
 
     // Filter by type
     if let Some(record_type) = params.get("type") {
@@ -583,8 +619,123 @@ async fn api_records(
         });
     }
 
-    let output: Vec<_> = records.iter().map(|r| record_to_json(r)).collect();
-    Json(output)
+    let output: Vec<_> = records
+        .iter()
+        .map(|r| {
+            let mut data = record_to_json(r);
+            if let Some((score, snippet)) = search.get(r.id()) {
+                data.insert("score".to_string(), serde_json::json!(score));
+                data.insert("snippet_html".to_string(), serde_json::json!(snippet));
+            }
+            data
+        })
+        .collect();
+    Json(output).into_response()
+}
+
+/// Path query over the graph: `/api/query?q=match (s)-[:enables]->(d) return d`
+async fn api_query(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let graph = state.graph.read().await;
+    let input = params.get("q").map(|q| q.as_str()).unwrap_or("");
+    match PathQuery::parse(input) {
+        Ok(query) => {
+            let rows = query.run(&graph);
+            Json(rows_to_json(&query.columns(), &rows)).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.message,
+                "column": e.column(),
+            })),
+        )
+            .into_response(),
+    }
+}
+
+async fn api_path(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let graph = state.graph.read().await;
+    let mut ids = Vec::new();
+    for key in ["from", "to"] {
+        let Some(id) = params.get(key) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("missing '{}' parameter", key) })),
+            )
+                .into_response();
+        };
+        match graph.get(id) {
+            Some(record) => ids.push(record.id().to_string()),
+            None => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({ "error": format!("Record not found: {}", id) })),
+                )
+                    .into_response()
+            }
+        }
+    }
+    let types: Vec<&str> = params
+        .get("types")
+        .map(|t| {
+            t.split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let directed = params.get("directed").is_some_and(|v| v == "true");
+
+    let paths = if params.get("all").is_some_and(|v| v == "true") {
+        let max_len = params
+            .get("max_len")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(6);
+        graph.simple_paths(&ids[0], &ids[1], &types, directed, max_len)
+    } else {
+        graph.shortest_paths(&ids[0], &ids[1], &types, directed)
+    };
+
+    Json(serde_json::json!({
+        "from": ids[0],
+        "to": ids[1],
+        "paths": paths,
+    }))
+    .into_response()
+}
+
+async fn api_record_history(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Response {
+    let graph = state.graph.read().await;
+    let Some(record) = graph.get(&id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Record not found: {}", id) })),
+        )
+            .into_response();
+    };
+
+    match RecordHistory::load(&state.docs_dir, record) {
+        Ok(history) => Json(serde_json::json!({
+            "id": record.id(),
+            "versions": history.versions,
+            "blame": history.blame(&record.content),
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("History not available: {}", e) })),
+        )
+            .into_response(),
+    }
 }
 
 async fn api_record(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
@@ -601,9 +752,17 @@ async fn api_record(State(state): State<Arc<AppState>>, Path(id): Path<String>)
             let links: Vec<_> = record
                 .frontmatter
                 .links
-                .all_links()
+                .all_targets()
                 .iter()
-                .map(|(lt, target)| serde_json::json!({ "type": lt, "target": target }))
+                .map(|(lt, target)| {
+                    serde_json::json!({
+                        "type": lt,
+                        "target": target.id,
+                        "note": target.meta.note,
+                        "since": target.meta.since,
+                        "strength": target.meta.strength,
+                    })
+                })
                 .collect();
             data.insert("links".to_string(), serde_json::Value::Array(links));
 
@@ -633,34 +792,65 @@ async fn api_render(
         }
     };
 
-    let html = markdown_to_html_with_mentions(markdown, &state.valid_mentions, "");
+    let graph = state.graph.read().await;
+    let html = markdown_to_html_with_mentions(markdown, &state.valid_mentions, Some(&graph), "");
     let html = linkify_action_item_owners(&html, &state.users_config, &state.teams_config, "");
     Json(serde_json::json!({ "html": html })).into_response()
 }
 
-async fn api_graph(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+async fn api_graph(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    // ?at=DATE|REV reads the graph as committed then
+    if let Some(at) = params.get("at") {
+        return match Graph::load_at(&state.docs_dir, at) {
+            Ok(graph) => Json(graph_json(&graph, &state.users_config)).into_response(),
+            Err(e) => (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+                .into_response(),
+        };
+    }
+
     let graph = state.graph.read().await;
+    Json(graph_json(&graph, &state.users_config)).into_response()
+}
 
-    let output = serde_json::json!({
+/// Nodes and edges as drawn by the graph page
+fn graph_json(graph: &Graph, users_config: &UsersConfig) -> serde_json::Value {
+    serde_json::json!({
         "nodes": graph.all_records().map(|r| {
+            // Resolve author names
+            let authors: Vec<String> = r.frontmatter.authors.iter().map(|username| {
+                users_config.get(username)
+                    .map(|u| u.display_name(username))
+                    .unwrap_or_else(|| username.to_string())
+            }).collect();
             serde_json::json!({
                 "id": r.id(),
                 "title": r.title(),
                 "type": r.record_type().to_string(),
+                "type_name": r.record_type().display_name(),
                 "status": r.status().to_string(),
+                "date": r.frontmatter.created.to_string(),
+                "authors": authors,
                 "core": r.frontmatter.core,
             })
         }).collect::<Vec<_>>(),
-        "edges": graph.edges.iter().map(|e| {
+        "edges": graph.edges().iter().map(|e| {
             serde_json::json!({
                 "source": e.from,
                 "target": e.to,
                 "type": e.link_type,
+                "label": LinkType::label_for(&e.link_type),
+                "note": e.meta.note,
+                "since": e.meta.since,
+                "strength": e.meta.strength,
             })
         }).collect::<Vec<_>>(),
-    });
-
-    Json(output)
+    })
 }
 
 async fn diagram_handler(
@@ -747,6 +937,17 @@ async fn edit_handler(
         }
     };
 
+    // With a workflow, offer only the current status and its legal next states
+    let status_options: Option<Vec<String>> =
+        state
+            .workflows
+            .get(record.record_type().name())
+            .map(|workflow| {
+                std::iter::once(record.status().to_string())
+                    .chain(workflow.next_states(record.status()))
+                    .collect()
+            });
+
     match env.get_template("edit.html") {
         Ok(tmpl) => match tmpl.render(context! {
             site => &state.site_config,
@@ -755,6 +956,7 @@ async fn edit_handler(
             record_id => &id,
             record_title => record.title(),
             raw_content => raw_content,
+            status_options => status_options,
         }) {
             Ok(html) => Html(html).into_response(),
             Err(e) => (
@@ -814,11 +1016,11 @@ async fn save_record_handler(
         }
     };
 
-    // Get current record path
-    let record_path = {
+    // Get current record path, frontmatter syntax and status
+    let (record_path, record_format, old_status) = {
         let graph = state.graph.read().await;
         match graph.get(&id) {
-            Some(record) => record.path.clone(),
+            Some(record) => (record.path.clone(), record.format, record.status().clone()),
             None => {
                 return (
                     StatusCode::NOT_FOUND,
@@ -854,8 +1056,51 @@ async fn save_record_handler(
                     .into_response();
             }
 
-            // Content is valid, move temp file to actual file
-            if let Err(e) = std::fs::rename(&temp_path, &record_path) {
+            // Enforce the type's status workflow. The server's own DG_USER or
+            // git identity is not the person editing, and requests carry no
+            // identity, so the editor acts as a user with no roles and
+            // role-restricted transitions are refused.
+            if let Some(workflow) = state.workflows.get(parsed.record_type().name()) {
+                if let Err(e) = workflow.check_transition(
+                    parsed.record_type().name(),
+                    &old_status,
+                    parsed.status(),
+                    Some(&Actor::default()),
+                ) {
+                    let _ = std::fs::remove_file(&temp_path);
+                    let (code, error) = match &e {
+                        WorkflowError::MissingRole { roles, .. } => (
+                            StatusCode::FORBIDDEN,
+                            format!(
+                                "this transition requires role {}; use `dg status`",
+                                roles.join(", ")
+                            ),
+                        ),
+                        _ => (StatusCode::BAD_REQUEST, e.to_string()),
+                    };
+                    return (
+                        code,
+                        Json(serde_json::json!({
+                            "error": error,
+                            "next_states": workflow.next_states(&old_status),
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+
+            // Content is valid, move temp file to actual file. The editor
+            // submits YAML, so rewrite in the file's own syntax if it differs.
+            let saved = if parsed.format == record_format {
+                std::fs::rename(&temp_path, &record_path).map_err(anyhow::Error::from)
+            } else {
+                let _ = std::fs::remove_file(&temp_path);
+                let mut converted = parsed.clone();
+                converted.path = record_path.clone();
+                converted.format = record_format;
+                converted.save()
+            };
+            if let Err(e) = saved {
                 let _ = std::fs::remove_file(&temp_path);
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -894,7 +1139,7 @@ async fn save_record_handler(
                 StatusCode::BAD_REQUEST,
                 Json(serde_json::json!({
                     "error": format!("Invalid record format: {}", e),
-                    "details": "Please check your frontmatter YAML syntax"
+                    "details": "Please check your frontmatter syntax"
                 })),
             )
                 .into_response()
@@ -1620,22 +1865,9 @@ async fn static_handler(Path(path): Path<String>) -> Response {
 }
 
 fn type_to_display_name(type_code: &str) -> String {
-    match type_code {
-        "DEC" => "Decision".to_string(),
-        "STR" => "Strategy".to_string(),
-        "POL" => "Policy".to_string(),
-        "CUS" => "Customer".to_string(),
-        "OPP" => "Opportunity".to_string(),
-        "PRC" => "Process".to_string(),
-        "HIR" => "Hiring".to_string(),
-        "ADR" => "Architecture".to_string(),
-        "INC" => "Incident".to_string(),
-        "RUN" => "Runbook".to_string(),
-        "MTG" => "Meeting".to_string(),
-        "FBK" => "Feedback".to_string(),
-        "LEG" => "Legal".to_string(),
-        other => other.to_string(),
-    }
+    RecordType::from_prefix(type_code)
+        .map(|t| t.display_name().to_string())
+        .unwrap_or_else(|| type_code.to_string())
 }
 
 /// Format date as "Jan 2017"
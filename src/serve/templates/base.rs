@@ -207,7 +207,7 @@ pub const BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
     // Record ID linkification with hover previews
     const quickPreview = {{ site.quick_preview | default(value=true) }};
     const recordCache = {};
-    const recordPattern = /\b(DEC|STR|POL|CUS|OPP|PRC|HIR|ADR|INC|RUN|MTG|FBK|LEG)-\d{3}\b/g;
+    const recordPattern = /\b({{ record_prefixes }})-\d{3}\b/g;
 
     function linkifyRecordIds() {
         const contentElements = document.querySelectorAll('.content, .card-meta, .preview-meta, td, .link-type');
@@ -168,6 +168,11 @@ pub const EDIT_TEMPLATE: &str = r##"{% extends "base.html" %}
                 <div class="flex-1">
                     <label for="fieldStatus" class="label text-xs opacity-60">Status</label>
                     <select id="fieldStatus" class="select select-bordered w-full">
+                        {% if status_options %}
+                        {% for status in status_options %}
+                        <option value="{{ status }}">{{ status | title }}</option>
+                        {% endfor %}
+                        {% else %}
                         <option value="proposed">Proposed</option>
                         <option value="draft">Draft</option>
                         <option value="accepted">Accepted</option>
@@ -177,6 +182,7 @@ pub const EDIT_TEMPLATE: &str = r##"{% extends "base.html" %}
                         <option value="open">Open</option>
                         <option value="resolved">Resolved</option>
                         <option value="cancelled">Cancelled</option>
+                        {% endif %}
                     </select>
                 </div>
                 <label for="fieldFoundational" class="flex items-center gap-2 cursor-pointer select-none h-[42px] whitespace-nowrap">
@@ -326,9 +332,12 @@ let originalFull = rawContent;
 let isDirty = false;
 
 // Parse frontmatter from raw content
+// (YAML between --- lines, or TOML between +++ lines)
 function parseFrontmatter(content) {
-    if (!content.startsWith('---')) return { frontmatter: {}, body: content };
-    const endIdx = content.indexOf('---', 3);
+    const isToml = content.startsWith('+++');
+    const delim = isToml ? '+++' : '---';
+    if (!content.startsWith(delim)) return { frontmatter: {}, body: content };
+    const endIdx = content.indexOf(delim, 3);
     if (endIdx === -1) return { frontmatter: {}, body: content };
 
     const yamlStr = content.substring(3, endIdx).trim();
@@ -346,18 +355,25 @@ function parseFrontmatter(content) {
         const trimmed = line.trim();
         if (!trimmed || trimmed.startsWith('#')) continue;
 
-        if (line.startsWith('  ') && inLinks) {
+        if (isToml && trimmed.startsWith('[') && !trimmed.includes('=')) {
+            // TOML table header: only [links] is editable here
+            inLinks = trimmed === '[links]';
+            if (inLinks) fm.links = {};
+            continue;
+        }
+
+        if (inLinks && (isToml || line.startsWith('  '))) {
             // Links sub-key
-            const match = trimmed.match(/^(\w+):\s*\[(.*)\]$/);
+            const match = trimmed.match(/^(\w+)\s*[:=]\s*\[(.*)\]$/);
             if (match) {
                 if (!fm.links) fm.links = {};
-                fm.links[match[1]] = match[2] ? match[2].split(',').map(s => s.trim()) : [];
+                fm.links[match[1]] = match[2] ? match[2].split(',').map(s => s.trim().replace(/^["']|["']$/g, '')) : [];
             }
             continue;
         }
 
         inLinks = false;
-        const colonIdx = trimmed.indexOf(':');
+        const colonIdx = trimmed.indexOf(isToml ? '=' : ':');
         if (colonIdx === -1) continue;
 
         const key = trimmed.substring(0, colonIdx).trim();
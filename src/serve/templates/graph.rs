@@ -41,16 +41,36 @@ pub const GRAPH_TEMPLATE: &str = r##"{% extends "base.html" %}
     flex-direction: column;
     gap: 4px;
 }
+.path-dim { opacity: 0.15; }
+line.path-edge { stroke: gold; stroke-width: 3; }
+#pathSummary {
+    display: none;
+    margin-bottom: 12px;
+    padding: 10px 14px;
+    border: 1px solid var(--primary, #007c43);
+    border-radius: 8px;
+    font-family: monospace;
+    font-size: 13px;
+    white-space: pre-line;
+    color: var(--text, #e2e8f0);
+}
 </style>
 {% endblock %}
 
 {% block content %}
+<div id="timeSlider" class="flex items-center gap-3 mb-3 text-sm" style="display: none;">
+    <label for="timeRange" class="opacity-60">As of</label>
+    <input id="timeRange" type="range" class="range range-xs flex-1" min="0" step="1">
+    <span id="timeLabel" class="font-mono w-24 text-right">now</span>
+</div>
+<div id="pathSummary"></div>
 <div class="graph-container" id="graph"></div>
 {% endblock %}
 
 {% block scripts %}
 <script>
-const data = {{ graph_data | safe }};
+let data = {{ graph_data | safe }};
+const historyDates = {{ history_dates | default("[]") | safe }};
 const container = document.getElementById('graph');
 const width = container.clientWidth;
 const height = container.clientHeight || 600;
@@ -70,70 +90,86 @@ const zoom = d3.zoom()
     .on('zoom', (e) => g.attr('transform', e.transform));
 svg.call(zoom);
 
-const simulation = d3.forceSimulation(data.nodes)
-    .force('link', d3.forceLink(data.edges).id(d => d.id).distance(100))
-    .force('charge', d3.forceManyBody().strength(-300))
-    .force('center', d3.forceCenter(width / 2, height / 2));
-
-const link = g.append('g')
-    .selectAll('line')
-    .data(data.edges)
-    .join('line')
-    .attr('stroke', '#666')
-    .attr('stroke-width', 1);
-
-const node = g.append('g')
-    .selectAll('g')
-    .data(data.nodes)
-    .join('g')
-    .call(d3.drag()
-        .on('start', dragstarted)
-        .on('drag', dragged)
-        .on('end', dragended));
-
-node.append('circle')
-    .attr('r', 28)
-    .attr('fill', d => color(d.type))
-    .attr('stroke', d => d.core ? 'gold' : '#333')
-    .attr('stroke-width', d => d.core ? 3 : 1);
-
-node.append('text')
-    .text(d => d.id)
-    .attr('text-anchor', 'middle')
-    .attr('dy', 5)
-    .attr('fill', '#fff')
-    .attr('font-size', '11px')
-    .attr('font-weight', '500');
-
 // Custom tooltip
 const tooltip = d3.select('body').append('div').attr('class', 'graph-tooltip');
-node.on('mouseenter', (e, d) => {
-    const authors = d.authors && d.authors.length > 0 ? d.authors.join(', ') : 'Unknown';
-    const date = d.date || 'No date';
-    tooltip.html(`
-        <div class="tooltip-type">${d.type_name || d.type}</div>
-        <div class="tooltip-title">${d.title}</div>
-        <div class="tooltip-meta">
-            <span>📅 ${date}</span>
-            <span>👤 ${authors}</span>
-        </div>
-    `).style('display', 'block');
-}).on('mousemove', (e) => {
-    tooltip.style('left', (e.clientX + 12) + 'px').style('top', (e.clientY + 12) + 'px');
-}).on('mouseleave', () => {
-    tooltip.style('display', 'none');
-});
-node.on('click', (e, d) => window.location.href = '/records/' + d.id);
-node.style('cursor', 'pointer');
-
-simulation.on('tick', () => {
-    link.attr('x1', d => d.source.x).attr('y1', d => d.source.y)
-        .attr('x2', d => d.target.x).attr('y2', d => d.target.y);
-    node.attr('transform', d => `translate(${d.x},${d.y})`);
-});
-
-// Fit all nodes in view after simulation settles
-simulation.on('end', fitToView);
+let simulation, link, node;
+
+// (Re)draw the graph from `data`
+function draw() {
+    if (simulation) simulation.stop();
+    g.selectAll('*').remove();
+
+    simulation = d3.forceSimulation(data.nodes)
+        .force('link', d3.forceLink(data.edges).id(d => d.id).distance(100))
+        .force('charge', d3.forceManyBody().strength(-300))
+        .force('center', d3.forceCenter(width / 2, height / 2));
+
+    link = g.append('g')
+        .selectAll('line')
+        .data(data.edges)
+        .join('line')
+        .attr('stroke', '#666')
+        .attr('stroke-width', 1)
+        .attr('stroke-dasharray', d => d.type === 'mentions' ? '3,3' : null);
+
+    link.append('title').text(d => [
+        `${d.source.id} ${d.label || d.type} ${d.target.id}`,
+        [d.since && `since ${d.since}`, d.strength].filter(Boolean).join(', '),
+        d.note,
+    ].filter(Boolean).join('\n'));
+
+    node = g.append('g')
+        .selectAll('g')
+        .data(data.nodes)
+        .join('g')
+        .call(d3.drag()
+            .on('start', dragstarted)
+            .on('drag', dragged)
+            .on('end', dragended));
+
+    node.append('circle')
+        .attr('r', 28)
+        .attr('fill', d => color(d.type))
+        .attr('stroke', d => d.core ? 'gold' : '#333')
+        .attr('stroke-width', d => d.core ? 3 : 1);
+
+    node.append('text')
+        .text(d => d.id)
+        .attr('text-anchor', 'middle')
+        .attr('dy', 5)
+        .attr('fill', '#fff')
+        .attr('font-size', '11px')
+        .attr('font-weight', '500');
+
+    node.on('mouseenter', (e, d) => {
+        const authors = d.authors && d.authors.length > 0 ? d.authors.join(', ') : 'Unknown';
+        const date = d.date || 'No date';
+        tooltip.html(`
+            <div class="tooltip-type">${d.type_name || d.type}</div>
+            <div class="tooltip-title">${d.title}</div>
+            <div class="tooltip-meta">
+                <span>📅 ${date}</span>
+                <span>👤 ${authors}</span>
+            </div>
+        `).style('display', 'block');
+    }).on('mousemove', (e) => {
+        tooltip.style('left', (e.clientX + 12) + 'px').style('top', (e.clientY + 12) + 'px');
+    }).on('mouseleave', () => {
+        tooltip.style('display', 'none');
+    });
+    node.on('click', (e, d) => window.location.href = '/records/' + d.id);
+    node.style('cursor', 'pointer');
+
+    simulation.on('tick', () => {
+        link.attr('x1', d => d.source.x).attr('y1', d => d.source.y)
+            .attr('x2', d => d.target.x).attr('y2', d => d.target.y);
+        node.attr('transform', d => `translate(${d.x},${d.y})`);
+    });
+
+    // Fit all nodes in view after simulation settles
+    simulation.on('end', fitToView);
+}
+draw();
 
 function fitToView() {
     if (data.nodes.length === 0) return;
@@ -154,6 +190,71 @@ function fitToView() {
     svg.transition().duration(500).call(zoom.transform, d3.zoomIdentity.translate(tx, ty).scale(scale));
 }
 
+// Highlight how two records are connected, e.g. /graph?from=CUS-001&to=STR-002
+const params = new URLSearchParams(window.location.search);
+if (params.get('from') && params.get('to')) {
+    highlightPath(params);
+}
+
+function highlightPath(params) {
+    const query = new URLSearchParams();
+    ['from', 'to', 'types', 'directed', 'all', 'max_len'].forEach(key => {
+        if (params.get(key)) query.set(key, params.get(key));
+    });
+    const summary = document.getElementById('pathSummary');
+    fetch('/api/path?' + query)
+        .then(r => r.json().then(body => ({ ok: r.ok, body })))
+        .then(({ ok, body }) => {
+            summary.style.display = 'block';
+            if (!ok) {
+                summary.textContent = body.error;
+                return;
+            }
+            if (body.paths.length === 0) {
+                summary.textContent = `${body.from} and ${body.to} are not connected.`;
+                return;
+            }
+            const onPath = new Set(body.paths.flatMap(p => p.nodes));
+            const pathLinks = new Set(body.paths.flatMap(p => p.links.map(l => `${l.from}|${l.link_type}|${l.to}`)));
+            const linkKey = d => `${d.source.id}|${d.type}|${d.target.id}`;
+            node.classed('path-dim', d => !onPath.has(d.id));
+            link.classed('path-dim', d => !pathLinks.has(linkKey(d)))
+                .classed('path-edge', d => pathLinks.has(linkKey(d)));
+            // One line per path, arrows showing which way each link was declared
+            summary.textContent = body.paths.map(p => p.nodes.map((id, i) => {
+                if (i === 0) return id;
+                const l = p.links[i - 1];
+                return (l.to === id ? ` ─${l.link_type}→ ` : ` ←${l.link_type}─ `) + id;
+            }).join('')).join('\n');
+        });
+}
+
+// Time slider: redraw the graph as committed on a past day
+if (historyDates.length > 0) {
+    const slider = document.getElementById('timeSlider');
+    const range = document.getElementById('timeRange');
+    const label = document.getElementById('timeLabel');
+    slider.style.display = 'flex';
+    range.max = historyDates.length;
+    range.value = historyDates.length;
+    range.addEventListener('input', () => {
+        label.textContent = historyDates[range.value] || 'now';
+    });
+    range.addEventListener('change', () => {
+        const at = historyDates[range.value];
+        fetch('/api/graph' + (at ? '?at=' + encodeURIComponent(at) : ''))
+            .then(r => r.json())
+            .then(body => {
+                if (body.error) {
+                    label.textContent = 'unavailable';
+                    return;
+                }
+                data = body;
+                draw();
+            });
+    });
+}
+
 function dragstarted(e) { if (!e.active) simulation.alphaTarget(0.3).restart(); e.subject.fx = e.subject.x; e.subject.fy = e.subject.y; }
 function dragged(e) { e.subject.fx = e.x; e.subject.fy = e.y; }
 function dragended(e) { if (!e.active) simulation.alphaTarget(0); e.subject.fx = null; e.subject.fy = null; }
@@ -5,13 +5,15 @@ pub const INDEX_TEMPLATE: &str = r##"{% extends "base.html" %}
 {% block head %}
 <style>
     .filter-btn.active { background: oklch(var(--p) / 0.2); border-color: oklch(var(--p)); color: oklch(var(--s)); }
+    .search-snippet mark { background: oklch(var(--wa) / 0.3); color: inherit; border-radius: 2px; }
 </style>
 {% endblock %}
 
 {% block content %}
 <h1 class="text-3xl font-bold mb-6">Records</h1>
 
-<input type="text" class="input input-bordered w-full mb-6" placeholder="Search records..." id="search">
+<input type="text" class="input input-bordered w-full mb-6" placeholder="Search records... (tag:auth OR type:adr -status:deprecated)" id="search">
+<p class="text-xs text-error -mt-4 mb-4 hidden" id="searchError"></p>
 
 <div class="flex gap-2 mb-6 flex-wrap items-center">
     <button class="btn btn-sm btn-outline filter-btn active" data-type="all">All</button>
@@ -41,6 +43,7 @@ pub const INDEX_TEMPLATE: &str = r##"{% extends "base.html" %}
             <span class="badge badge-sm flex-shrink-0 {% if record.status == 'accepted' or record.status == 'active' %}badge-success{% elif record.status == 'proposed' or record.status == 'draft' %}badge-warning{% elif record.status == 'open' %}badge-error{% elif record.status == 'rejected' %}badge-error badge-outline{% elif record.status == 'resolved' %}badge-info{% elif record.status == 'deprecated' %}badge-warning badge-outline{% elif record.status == 'superseded' %}badge-neutral{% else %}badge-neutral{% endif %}">{{ record.status | upper }}</span>
         </div>
         <h3 class="text-base font-semibold text-base-content flex-1">{{ record.title }}</h3>
+        <p class="search-snippet text-xs opacity-70 mt-2 hidden"></p>
         <div class="mt-auto pt-3">
             <div class="flex flex-wrap items-center gap-x-2 gap-y-1 text-xs opacity-50 mb-2">
                 <span class="badge badge-xs badge-ghost">{{ record.type_display }}</span>
@@ -195,7 +198,7 @@ function loadFromUrl() {
     }
 }
 
-search.addEventListener('input', () => { filterRecords(); updateUrl(); });
+search.addEventListener('input', () => { filterRecords(); runSearch(); updateUrl(); });
 sortBtn.addEventListener('click', cycleSortMode);
 filters.forEach(btn => {
     if (btn.id !== 'sort' && btn.id !== 'viewToggle' && btn.tagName === 'BUTTON' && btn.dataset.type) {
@@ -217,13 +220,67 @@ function cycleSortMode() {
     updateUrl();
 }
 
+// Ranked results from /api/records?q= (id -> {rank, snippet}); null while
+// unavailable, e.g. in a static build, where matching falls back to plain text
+let searchHits = null;
+let searchTimer = null;
+
+function runSearch() {
+    clearTimeout(searchTimer);
+    const query = search.value.trim();
+    if (!query) {
+        searchHits = null;
+        showSearchError(null);
+        filterRecords();
+        return;
+    }
+    searchTimer = setTimeout(async () => {
+        try {
+            const response = await fetch('/api/records?q=' + encodeURIComponent(query));
+            const results = await response.json();
+            if (search.value.trim() !== query) return;
+            if (response.status === 400) {
+                showSearchError(results.error + ' (column ' + results.column + ')');
+                searchHits = null;
+            } else {
+                if (!response.ok) throw new Error(response.statusText);
+                showSearchError(null);
+                searchHits = new Map(results.map((r, i) => [r.id, { rank: i, snippet: r.snippet_html }]));
+            }
+        } catch (e) {
+            showSearchError(null);
+            searchHits = null;
+        }
+        filterRecords();
+    }, 150);
+}
+
+function showSearchError(message) {
+    const el = document.getElementById('searchError');
+    el.textContent = message || '';
+    el.classList.toggle('hidden', !message);
+}
+
+function matchesSearch(r, query) {
+    if (!query) return true;
+    if (searchHits) return searchHits.has(r.dataset.id);
+    return r.textContent.toLowerCase().includes(query);
+}
+
 function filterRecords() {
     const query = search.value.toLowerCase();
     // Filter cards
     document.querySelectorAll('.card').forEach(r => {
         const matchesType = activeType === 'all' || r.dataset.type === activeType;
         const matchesStatus = activeStatus === 'all' || r.dataset.status === activeStatus;
-        const matchesQuery = !query || r.textContent.toLowerCase().includes(query);
+        const matchesQuery = matchesSearch(r, query);
+        const hit = query && searchHits ? searchHits.get(r.dataset.id) : null;
+        r.style.order = hit ? hit.rank : '';
+        const snippet = r.querySelector('.search-snippet');
+        if (snippet) {
+            snippet.innerHTML = hit && hit.snippet ? hit.snippet : '';
+            snippet.classList.toggle('hidden', !(hit && hit.snippet));
+        }
         const tags = r.dataset.tags ? r.dataset.tags.split(',') : [];
         const matchesTag = !activeTag || tags.includes(activeTag);
         r.style.display = matchesType && matchesStatus && matchesQuery && matchesTag ? 'block' : 'none';
@@ -232,7 +289,7 @@ function filterRecords() {
     document.querySelectorAll('.table-row').forEach(r => {
         const matchesType = activeType === 'all' || r.dataset.type === activeType;
         const matchesStatus = activeStatus === 'all' || r.dataset.status === activeStatus;
-        const matchesQuery = !query || r.textContent.toLowerCase().includes(query);
+        const matchesQuery = matchesSearch(r, query);
         const tags = r.dataset.tags ? r.dataset.tags.split(',') : [];
         const matchesTag = !activeTag || tags.includes(activeTag);
         r.style.display = matchesType && matchesStatus && matchesQuery && matchesTag ? 'table-row' : 'none';
@@ -279,6 +336,7 @@ document.querySelectorAll('.tag-link').forEach(tag => {
 loadFromUrl();
 setViewMode(viewMode);
 filterRecords();
+runSearch();
 sortRecords();
 
 // Keyboard navigation
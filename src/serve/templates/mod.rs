@@ -1,3 +1,4 @@
+use crate::models::RecordType;
 use minijinja::Environment;
 
 mod base;
@@ -36,5 +37,9 @@ pub fn create_environment() -> Environment<'static> {
     env.add_template("team.html", TEAM_TEMPLATE).unwrap();
     env.add_template("team_history.html", TEAM_HISTORY_TEMPLATE)
         .unwrap();
+
+    // ID prefixes for client-side record linkification (includes custom types)
+    let prefixes: Vec<_> = RecordType::all().iter().map(|t| t.prefix()).collect();
+    env.add_global("record_prefixes", prefixes.join("|"));
     env
 }
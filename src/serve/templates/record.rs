@@ -131,8 +131,25 @@ pub const RECORD_TEMPLATE: &str = r##"{% extends "base.html" %}
             {% endif %}
         </div>
 
+        {% if record.has_history %}
+        <!-- Tabs -->
+        <div class="flex gap-6 mt-4 border-b border-slate-700/50 text-sm font-medium">
+            <button type="button" class="record-tab pb-2 border-b-2 border-piper-light text-white" data-tab="content-panel">Record</button>
+            <button type="button" class="record-tab pb-2 border-b-2 border-transparent text-slate-400 hover:text-slate-200" data-tab="history-panel">History</button>
+        </div>
+
+        <div id="history-panel" class="mt-6 hidden">
+            <p id="history-status" class="text-slate-500 text-sm">Loading history…</p>
+            <div id="history-blame" class="hidden mb-8">
+                <h3 class="text-xs font-bold text-slate-500 uppercase tracking-widest mb-3 font-mono">Sections Last Changed</h3>
+                <table class="w-full text-sm"><tbody id="history-blame-rows"></tbody></table>
+            </div>
+            <ol id="history-versions" class="space-y-4 list-none"></ol>
+        </div>
+        {% endif %}
+
         <!-- Content with ToC -->
-        <div class="mt-6 pr-4">
+        <div id="content-panel" class="mt-6 pr-4">
             <nav id="toc" class="hidden lg:block w-56 mb-4 pl-8 border-l border-slate-700/50" style="float: right; margin-left: 3rem;">
                 <div class="sticky top-6">
                     <button id="toc-toggle" class="flex items-center gap-1.5 text-xs font-bold text-slate-500 uppercase tracking-widest mb-4 font-mono hover:text-slate-400 transition-colors">
@@ -159,11 +176,14 @@ pub const RECORD_TEMPLATE: &str = r##"{% extends "base.html" %}
             <a href="/records/{{ link.target }}" class="group block p-4 bg-slate-800 border border-slate-700 rounded-xl hover:border-piper-light/50 hover:bg-slate-700/50 transition-all hover:shadow-lg hover:-translate-y-0.5">
                 <div class="flex justify-between items-start mb-1">
                     <span class="font-mono text-xs text-piper-light font-medium">{{ link.target }}</span>
-                    <span class="text-[10px] uppercase font-bold text-slate-500 border border-slate-600 px-1 rounded">{{ link.type }}</span>
+                    <span class="text-[10px] uppercase font-bold text-slate-500 border border-slate-600 px-1 rounded">{{ link.label or link.type }}</span>
                 </div>
                 {% if link.title %}
                 <div class="font-semibold text-slate-200 group-hover:text-white">{{ link.title }}</div>
                 {% endif %}
+                {% if link.meta %}
+                <div class="mt-1 text-xs text-slate-400">{{ link.meta }}</div>
+                {% endif %}
             </a>
             {% endfor %}
         </div>
@@ -176,11 +196,14 @@ pub const RECORD_TEMPLATE: &str = r##"{% extends "base.html" %}
             <a href="/records/{{ link.source }}" class="group block p-4 bg-slate-800 border border-slate-700 rounded-xl hover:border-amber-500/50 hover:bg-slate-700/50 transition-all hover:shadow-lg hover:-translate-y-0.5">
                 <div class="flex justify-between items-start mb-1">
                     <span class="font-mono text-xs text-amber-400 font-medium">{{ link.source }}</span>
-                    <span class="text-[10px] uppercase font-bold text-slate-500 border border-slate-600 px-1 rounded">{{ link.type }}</span>
+                    <span class="text-[10px] uppercase font-bold text-slate-500 border border-slate-600 px-1 rounded">{{ link.label or link.type }}</span>
                 </div>
                 {% if link.title %}
                 <div class="font-semibold text-slate-200 group-hover:text-white">{{ link.title }}</div>
                 {% endif %}
+                {% if link.meta %}
+                <div class="mt-1 text-xs text-slate-400">{{ link.meta }}</div>
+                {% endif %}
             </a>
             {% endfor %}
         </div>
@@ -188,6 +211,46 @@ pub const RECORD_TEMPLATE: &str = r##"{% extends "base.html" %}
     </div>
     {% endif %}
 
+    {% if record.commits %}
+    <!-- Implemented by commits -->
+    <div class="bg-slate-800/30 border-t border-slate-700 p-8" id="commits">
+        <h3 class="text-xs font-bold text-slate-500 uppercase tracking-widest mb-6 font-mono">Implemented By Commits</h3>
+        <ul class="space-y-3 list-none">
+            {% for commit in record.commits %}
+            <li class="p-4 bg-slate-800 border border-slate-700 rounded-xl">
+                <div class="flex flex-wrap items-baseline gap-3">
+                    <span class="font-mono text-xs text-amber-400" title="{{ commit.commit }}">{{ commit.short }}</span>
+                    <span class="font-semibold text-slate-200">{{ commit.message }}</span>
+                    <span class="text-xs text-slate-500">{{ commit.author }} · {{ commit.date }}</span>
+                </div>
+                {% if commit.files %}
+                <details class="mt-2 text-xs text-slate-400">
+                    <summary class="cursor-pointer hover:text-slate-300">{{ commit.files | length }} file{% if commit.files | length != 1 %}s{% endif %}</summary>
+                    <ul class="mt-2 space-y-0.5 list-none font-mono">
+                        {% for file in commit.files %}<li>{{ file }}</li>{% endfor %}
+                    </ul>
+                </details>
+                {% endif %}
+            </li>
+            {% endfor %}
+        </ul>
+    </div>
+    {% endif %}
+
+    {% if record.other_records %}
+    <!-- Path picker -->
+    <form action="/graph" method="get" class="bg-slate-800/30 border-t border-slate-700 px-8 py-4 flex flex-wrap items-center gap-3 text-sm">
+        <input type="hidden" name="from" value="{{ record.id }}">
+        <label for="connectTo" class="text-xs font-bold text-slate-500 uppercase tracking-widest font-mono">How is this connected to…</label>
+        <input id="connectTo" name="to" list="connectRecords" required placeholder="Record ID" autocomplete="off"
+            class="bg-slate-800 border border-slate-700 rounded-lg px-3 py-1.5 text-slate-200 font-mono text-xs focus:outline-none focus:border-piper-light">
+        <datalist id="connectRecords">
+            {% for other in record.other_records %}<option value="{{ other.id }}">{{ other.title }}</option>{% endfor %}
+        </datalist>
+        <button type="submit" class="px-3 py-1.5 rounded-lg bg-piper-accent/30 text-piper-light text-xs font-semibold hover:bg-piper-accent hover:text-white transition-colors">Show path</button>
+    </form>
+    {% endif %}
+
     <!-- Footer -->
     <div class="bg-slate-900 p-4 border-t border-slate-800 flex justify-between items-center text-xs text-slate-500 font-mono">
         <a href="#" onclick="window.scrollTo({top:0,behavior:'smooth'});return false;" class="hover:text-slate-300 transition-colors cursor-pointer">{{ record.id }}</a>
@@ -197,14 +260,116 @@ pub const RECORD_TEMPLATE: &str = r##"{% extends "base.html" %}
 {% endblock %}
 
 {% block scripts %}
+{% if record.has_history %}
+<script>
+(function() {
+    const tabs = document.querySelectorAll('.record-tab');
+    let loaded = false;
+
+    function escapeHtml(text) {
+        const div = document.createElement('div');
+        div.textContent = text == null ? '' : String(text);
+        return div.innerHTML;
+    }
+
+    function changeLines(changes) {
+        const lines = [];
+        (changes.fields || []).forEach(f => lines.push(
+            `<span class="text-slate-400">${escapeHtml(f.field)}:</span> <span class="text-red-400">${escapeHtml(f.before ?? '(none)')}</span> → <span class="text-emerald-400">${escapeHtml(f.after ?? '(none)')}</span>`));
+        (changes.tags_added || []).forEach(t => lines.push(`<span class="text-slate-400">tag</span> <span class="text-emerald-400">+${escapeHtml(t)}</span>`));
+        (changes.tags_removed || []).forEach(t => lines.push(`<span class="text-slate-400">tag</span> <span class="text-red-400">-${escapeHtml(t)}</span>`));
+        (changes.links_added || []).forEach(l => lines.push(`<span class="text-slate-400">link</span> <span class="text-emerald-400">+${escapeHtml(l.link_type)} <a href="/records/${escapeHtml(l.to)}" class="underline">${escapeHtml(l.to)}</a></span>`));
+        (changes.links_removed || []).forEach(l => lines.push(`<span class="text-slate-400">link</span> <span class="text-red-400">-${escapeHtml(l.link_type)} ${escapeHtml(l.to)}</span>`));
+        (changes.sections || []).forEach(s => lines.push(`<span class="text-slate-400">section</span> ${escapeHtml(s.change)} <em>${escapeHtml(s.heading || '(intro)')}</em>`));
+        return lines;
+    }
+
+    function renderPatch(patch) {
+        return patch.split('\n').map(line => {
+            let cls = 'text-slate-400';
+            if (line.startsWith('+')) cls = 'text-emerald-400 bg-emerald-900/20';
+            else if (line.startsWith('-')) cls = 'text-red-400 bg-red-900/20';
+            else if (line.startsWith('@@')) cls = 'text-sky-400';
+            return `<span class="${cls} block">${escapeHtml(line) || ' '}</span>`;
+        }).join('');
+    }
+
+    function render(data) {
+        const status = document.getElementById('history-status');
+        if (!data.versions.length) {
+            status.textContent = 'This record has no committed history yet.';
+            return;
+        }
+        status.classList.add('hidden');
+
+        const blameRows = data.blame.map(b => `<tr class="border-b border-slate-800">
+            <td class="py-1.5 pr-4 text-slate-200">${escapeHtml(b.heading || '(intro)')}</td>
+            <td class="py-1.5 pr-4 font-mono text-xs text-amber-400">${b.commit ? escapeHtml(b.commit.slice(0, 7)) : 'uncommitted'}</td>
+            <td class="py-1.5 pr-4 text-slate-400">${escapeHtml(b.author || '')}</td>
+            <td class="py-1.5 text-slate-500">${b.date ? escapeHtml(b.date.slice(0, 10)) : ''}</td>
+        </tr>`).join('');
+        document.getElementById('history-blame-rows').innerHTML = blameRows;
+        document.getElementById('history-blame').classList.remove('hidden');
+
+        document.getElementById('history-versions').innerHTML = data.versions.map((v, i) => {
+            const previous = data.versions[i + 1];
+            const lines = v.changes ? changeLines(v.changes)
+                : [`<span class="text-emerald-400">created</span> as ${escapeHtml(v.id || '?')} (${escapeHtml(v.status || '?')})`];
+            if (previous && previous.path !== v.path) {
+                lines.unshift(`<span class="text-slate-400">renamed from</span> ${escapeHtml(previous.path)}`);
+            }
+            return `<li class="p-4 bg-slate-800/50 border border-slate-700 rounded-xl">
+                <div class="flex flex-wrap items-baseline gap-3 text-sm">
+                    <span class="font-mono text-xs text-amber-400">${escapeHtml(v.commit.slice(0, 7))}</span>
+                    <span class="text-slate-200 font-medium">${escapeHtml(v.message)}</span>
+                    <span class="text-slate-500 text-xs ml-auto">${escapeHtml(v.author)} · ${escapeHtml(v.date.slice(0, 10))}</span>
+                </div>
+                <ul class="mt-2 space-y-0.5 text-xs list-none">${lines.map(l => `<li>${l}</li>`).join('')}</ul>
+                ${v.patch ? `<details class="mt-3"><summary class="cursor-pointer text-xs text-slate-500 hover:text-slate-300">Diff</summary><pre class="mt-2 p-3 bg-slate-900 rounded-lg text-xs overflow-x-auto">${renderPatch(v.patch)}</pre></details>` : ''}
+            </li>`;
+        }).join('');
+    }
+
+    function show(panelId) {
+        tabs.forEach(tab => {
+            const active = tab.dataset.tab === panelId;
+            tab.classList.toggle('border-piper-light', active);
+            tab.classList.toggle('text-white', active);
+            tab.classList.toggle('border-transparent', !active);
+            tab.classList.toggle('text-slate-400', !active);
+            document.getElementById(tab.dataset.tab).classList.toggle('hidden', !active);
+        });
+        if (panelId === 'history-panel' && !loaded) {
+            loaded = true;
+            fetch('/api/records/{{ record.id }}/history')
+                .then(r => r.json())
+                .then(data => {
+                    if (data.error) throw new Error(data.error);
+                    render(data);
+                })
+                .catch(e => {
+                    document.getElementById('history-status').textContent = e.message;
+                });
+        }
+    }
+
+    tabs.forEach(tab => tab.addEventListener('click', () => {
+        show(tab.dataset.tab);
+        history.replaceState(null, '', tab.dataset.tab === 'history-panel' ? '#history' : location.pathname);
+    }));
+    if (location.hash === '#history') show('history-panel');
+})();
+</script>
+{% endif %}
 <script>
 (function() {
     const content = document.getElementById('content');
     const tocList = document.getElementById('toc-list');
     const toc = document.getElementById('toc');
 
-    // Find all h2 and h3 headings
-    const headings = content.querySelectorAll('h2, h3');
+    // Find all h2 and h3 headings (not those embedded from other records)
+    const headings = Array.from(content.querySelectorAll('h2, h3'))
+        .filter(heading => !heading.closest('.transclusion'));
     if (headings.length < 2) {
         toc.style.display = 'none';
         return;
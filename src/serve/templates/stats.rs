@@ -18,6 +18,10 @@ pub const STATS_TEMPLATE: &str = r##"{% extends "base.html" %}
         <div class="stat-title">Core Records</div>
         <div class="stat-value text-warning">{{ stats.core }}</div>
     </div>
+    <div class="stat">
+        <div class="stat-title">Components</div>
+        <div class="stat-value">{{ stats.components }}</div>
+    </div>
 </div>
 
 <h3 class="text-lg font-semibold mb-4">By Type</h3>
@@ -43,5 +47,47 @@ pub const STATS_TEMPLATE: &str = r##"{% extends "base.html" %}
     </a>
     {% endfor %}
 </div>
+
+{% if stats.central %}
+<h3 class="text-lg font-semibold mt-8 mb-4">Most Central</h3>
+<div class="overflow-x-auto mb-8">
+    <table class="table table-sm">
+        <thead>
+            <tr><th>Record</th><th class="text-right">In</th><th class="text-right">Out</th><th class="text-right">Betweenness</th><th class="text-right">PageRank</th></tr>
+        </thead>
+        <tbody>
+            {% for item in stats.central %}
+            <tr>
+                <td><a href="/records/{{ item.id }}" class="link link-hover"><span class="font-mono text-xs opacity-60">{{ item.id }}</span> {{ item.title }}</a></td>
+                <td class="text-right">{{ item.in_degree }}</td>
+                <td class="text-right">{{ item.out_degree }}</td>
+                <td class="text-right font-mono">{{ item.betweenness }}</td>
+                <td class="text-right font-mono">{{ item.pagerank }}</td>
+            </tr>
+            {% endfor %}
+        </tbody>
+    </table>
+</div>
+{% endif %}
+
+{% if stats.keystones %}
+<h3 class="text-lg font-semibold mb-4">Keystones</h3>
+<ul class="mb-8 space-y-2">
+    {% for item in stats.keystones %}
+    <li><a href="/records/{{ item.id }}" class="link link-hover"><span class="font-mono text-xs opacity-60">{{ item.id }}</span> {{ item.title }}</a>
+        <span class="text-sm opacity-60">removing it disconnects {{ item.disconnects }} record{% if item.disconnects != 1 %}s{% endif %}</span></li>
+    {% endfor %}
+</ul>
+{% endif %}
+
+{% if stats.core_suggestions %}
+<h3 class="text-lg font-semibold mb-4">Consider Marking Core</h3>
+<p class="text-sm opacity-60 mb-3">These records hold the graph together but don't have <code>core: true</code>.</p>
+<ul class="space-y-2">
+    {% for item in stats.core_suggestions %}
+    <li><a href="/records/{{ item.id }}" class="link link-hover"><span class="font-mono text-xs opacity-60">{{ item.id }}</span> {{ item.title }}</a></li>
+    {% endfor %}
+</ul>
+{% endif %}
 {% endblock %}
 "##;
@@ -1,4 +1,4 @@
-use assert_cmd::Command;
+use assert_cmd::{cargo::cargo_bin_cmd, Command};
 use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
@@ -9,8 +9,7 @@ fn setup_test_env() -> TempDir {
     let docs_dir = temp.path().join("docs");
 
     // Run dg init
-    Command::cargo_bin("dg")
-        .unwrap()
+    cargo_bin_cmd!("dg")
         .args(["-D", docs_dir.to_str().unwrap(), "init"])
         .assert()
         .success();
@@ -20,11 +19,24 @@ fn setup_test_env() -> TempDir {
 
 /// Helper to run dg command in test environment
 fn dg_cmd(temp: &TempDir) -> Command {
-    let mut cmd = Command::cargo_bin("dg").unwrap();
+    let mut cmd = cargo_bin_cmd!("dg");
     cmd.args(["-D", temp.path().join("docs").to_str().unwrap()]);
     cmd
 }
 
+/// Helper to build a git command in the test environment, as a test user
+fn git_cmd(temp: &TempDir) -> std::process::Command {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .current_dir(temp.path());
+    cmd
+}
+
+/// Helper to run git in the test environment
+fn git(temp: &TempDir, args: &[&str]) -> std::process::Output {
+    git_cmd(temp).args(args).output().unwrap()
+}
+
 // ============================================================================
 // Init Tests
 // ============================================================================
@@ -34,8 +46,7 @@ fn test_init_creates_directories() {
     let temp = TempDir::new().unwrap();
     let docs_dir = temp.path().join("docs");
 
-    Command::cargo_bin("dg")
-        .unwrap()
+    cargo_bin_cmd!("dg")
         .args(["-D", docs_dir.to_str().unwrap(), "init"])
         .assert()
         .success()
@@ -134,6 +145,39 @@ fn test_new_with_special_characters_in_title() {
     assert!(content.contains("ADR: Use Redis for Caching"));
 }
 
+#[test]
+fn test_new_custom_type_from_config() {
+    let temp = setup_test_env();
+    fs::write(
+        temp.path().join("docs/dg.toml"),
+        "[types.rfc]\nprefix = \"RFC\"\ndisplay_name = \"Request for Comments\"\ndefault_status = \"draft\"\n",
+    )
+    .unwrap();
+
+    dg_cmd(&temp)
+        .args(["new", "rfc", "Adopt Rust"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("RFC-001"));
+
+    let file = temp.path().join("docs/decisions/RFC-001-adopt-rust.md");
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.contains("type: rfc"));
+    assert!(content.contains("status: draft"));
+
+    dg_cmd(&temp)
+        .args(["new", "rfc", "Second"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("RFC-002"));
+
+    dg_cmd(&temp)
+        .args(["list", "--type", "rfc", "--format", "ids"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("RFC-001").and(predicate::str::contains("RFC-002")));
+}
+
 // ============================================================================
 // List Tests
 // ============================================================================
@@ -294,6 +338,131 @@ fn test_link_supersedes_creates_inverse() {
     assert!(content.contains("DEC-002"));
 }
 
+#[test]
+fn test_link_custom_type_with_inverse() {
+    let temp = setup_test_env();
+    fs::write(
+        temp.path().join("docs/dg.toml"),
+        "[links.blocks]\ninverse = \"blocked_by\"\nlabel = \"Blocks\"\n",
+    )
+    .unwrap();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "First"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Second"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["link", "DEC-001", "blocks", "DEC-002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("inverse blocked_by"));
+
+    let output = dg_cmd(&temp)
+        .args(["show", "DEC-001", "--json"])
+        .output()
+        .unwrap();
+    let shown: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(shown["links"]["blocks"], serde_json::json!(["DEC-002"]));
+    assert_eq!(shown["links"]["depends_on"], serde_json::json!([]));
+
+    let content = fs::read_to_string(temp.path().join("docs/decisions/DEC-002-second.md")).unwrap();
+    assert!(content.contains("blocked_by"));
+    assert!(content.contains("DEC-001"));
+
+    dg_cmd(&temp).arg("lint").assert().success();
+
+    // Removing only the inverse leaves the forward link dangling
+    fs::write(
+        temp.path().join("docs/decisions/DEC-002-second.md"),
+        content.replace("blocked_by", "relates_to"),
+    )
+    .unwrap();
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("doesn't have blocked_by back"));
+
+    dg_cmd(&temp)
+        .args(["link", "DEC-001", "undeclared", "DEC-002"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown link type"));
+}
+
+#[test]
+fn test_link_with_metadata() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "strategy", "Strategy"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Decision"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args([
+            "link",
+            "DEC-001",
+            "depends_on",
+            "STR-001",
+            "--note",
+            "Pricing follows the strategy",
+            "--since",
+            "2025-01-01",
+        ])
+        .assert()
+        .success();
+
+    let file = temp.path().join("docs/decisions/DEC-001-decision.md");
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.contains("id: STR-001"));
+    assert!(content.contains("note: Pricing follows the strategy"));
+
+    dg_cmd(&temp)
+        .args(["show", "DEC-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "since 2025-01-01: \"Pricing follows the strategy\"",
+        ));
+
+    dg_cmd(&temp)
+        .args(["show", "STR-001", "--links"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pricing follows the strategy"));
+
+    dg_cmd(&temp)
+        .args(["why", "DEC-001", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"since\": \"2025-01-01\""));
+
+    dg_cmd(&temp)
+        .args(["export"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"note\": \"Pricing follows the strategy\"",
+        ));
+
+    // Formatting keeps the extended form stable
+    dg_cmd(&temp).arg("fmt").assert().success();
+    dg_cmd(&temp).args(["fmt", "--check"]).assert().success();
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.contains("note: Pricing follows the strategy"));
+    dg_cmd(&temp).arg("lint").assert().success();
+}
+
 #[test]
 fn test_link_nonexistent_source() {
     let temp = setup_test_env();
@@ -385,67 +554,151 @@ fn test_search_by_title() {
 }
 
 #[test]
-fn test_search_no_results() {
-    let temp = setup_test_env();
-
-    dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
-        .assert()
-        .success();
-
-    dg_cmd(&temp)
-        .args(["search", "nonexistent"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("No records found"));
-}
-
-// ============================================================================
-// Status Tests
-// ============================================================================
-
-#[test]
-fn test_status_update() {
+fn test_search_ranks_and_highlights() {
     let temp = setup_test_env();
 
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["new", "decision", "Hiring Plan"])
         .assert()
         .success();
-
     dg_cmd(&temp)
-        .args(["status", "DEC-001", "accepted"])
+        .args(["new", "decision", "Retention Policies"])
         .assert()
         .success();
 
-    // Verify status changed
-    let file = temp.path().join("docs/decisions/DEC-001-test.md");
-    let content = fs::read_to_string(&file).unwrap();
-    assert!(content.contains("status: accepted"));
+    let file = temp.path().join("docs/decisions/DEC-001-hiring-plan.md");
+    let mut content = fs::read_to_string(&file).unwrap();
+    content.push_str("\nDepends on our data retention policy for candidate records.\n");
+    fs::write(&file, &content).unwrap();
+
+    // Title matches outrank body matches; stems and prefixes match
+    let output = dg_cmd(&temp)
+        .args(["search", "retention polic", "-c", "--format", "ids"])
+        .output()
+        .unwrap();
+    let ids = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(ids.lines().collect::<Vec<_>>(), vec!["DEC-002", "DEC-001"]);
+
+    // Typos still find the record, and body matches come with a snippet
+    let output = dg_cmd(&temp)
+        .args(["search", "retnetion", "-c", "--format", "json"])
+        .output()
+        .unwrap();
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let hiring = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["id"] == "DEC-001")
+        .expect("fuzzy match on the body");
+    assert!(hiring["snippet"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("data retention policy"));
+    assert!(hiring["score"].as_f64().unwrap() > 0.0);
 }
 
-// ============================================================================
-// Validate Tests
-// ============================================================================
-
 #[test]
-fn test_validate_clean() {
+fn test_query_language_in_list_and_search() {
     let temp = setup_test_env();
-
+    for title in ["Auth Tokens", "Legacy Sessions", "Security Review"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    let decisions = temp.path().join("docs/decisions");
+    let retag = |file: &str, tags: &str, extra: &str| {
+        let path = decisions.join(file);
+        let content = fs::read_to_string(&path).unwrap().replacen(
+            "tags: []",
+            &format!("tags: {}\n{}", tags, extra),
+            1,
+        );
+        fs::write(&path, content).unwrap();
+    };
+    retag("DEC-001-auth-tokens.md", "[auth]", "severity: high");
+    retag(
+        "DEC-002-legacy-sessions.md",
+        "[auth, legacy]",
+        "severity: low",
+    );
+    retag("DEC-003-security-review.md", "[security]", "");
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["link", "DEC-003", "depends_on", "DEC-001"])
         .assert()
         .success();
 
+    let ids = |args: &[&str]| {
+        let output = dg_cmd(&temp).args(args).output().unwrap();
+        assert!(output.status.success(), "{:?} failed", args);
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        ids(&[
+            "list",
+            "(tag:auth OR tag:security) -tag:legacy",
+            "-f",
+            "ids"
+        ]),
+        vec!["DEC-001", "DEC-003"]
+    );
+    assert_eq!(
+        ids(&[
+            "list",
+            "field.severity=high",
+            "--type",
+            "decision",
+            "-f",
+            "ids"
+        ]),
+        vec!["DEC-001"]
+    );
+    assert_eq!(
+        ids(&["search", "links:depends_on=DEC-001", "--format", "ids"]),
+        vec!["DEC-003"]
+    );
+    assert_eq!(
+        ids(&[
+            "search",
+            "\"legacy sessions\" OR has:depends_on",
+            "--format",
+            "ids"
+        ]),
+        vec!["DEC-002", "DEC-003"]
+    );
+    assert_eq!(
+        ids(&[
+            "search",
+            "created:>2000-01-01 updated:<30d -tag:auth",
+            "--format",
+            "ids"
+        ]),
+        vec!["DEC-003"]
+    );
+
+    // Parse errors point at the offending column
     dg_cmd(&temp)
-        .arg("validate")
+        .args(["list", "tag:auth (tag:legacy"])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("OK"));
+        .failure()
+        .stderr(predicate::str::contains("unclosed '(' (column 10)"))
+        .stderr(predicate::str::contains(
+            "  tag:auth (tag:legacy\n           ^",
+        ));
+    dg_cmd(&temp)
+        .args(["search", "status:done"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown status 'done'"));
 }
 
 #[test]
-fn test_validate_broken_link() {
+fn test_search_no_results() {
     let temp = setup_test_env();
 
     dg_cmd(&temp)
@@ -453,158 +706,1190 @@ fn test_validate_broken_link() {
         .assert()
         .success();
 
-    // Manually add broken link
-    let file = temp.path().join("docs/decisions/DEC-001-test.md");
-    let content = fs::read_to_string(&file).unwrap();
-    let modified = content.replace("depends_on: []", "depends_on: [NONEXISTENT-001]");
-    fs::write(&file, modified).unwrap();
-
     dg_cmd(&temp)
-        .arg("validate")
+        .args(["search", "nonexistent"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("broken"));
+        .stdout(predicate::str::contains("No records found"));
 }
 
 // ============================================================================
-// Format Tests
+// Query Tests
 // ============================================================================
 
 #[test]
-fn test_fmt_check_clean() {
+fn test_query_paths() {
     let temp = setup_test_env();
-
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["new", "strategy", "Go Upmarket"])
+        .assert()
+        .success();
+    for title in ["Enterprise SSO", "Audit Logs"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    dg_cmd(&temp)
+        .args(["link", "STR-001", "enables", "DEC-001"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["link", "DEC-001", "enables", "DEC-002"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["status", "DEC-002", "accepted"])
         .assert()
         .success();
 
-    // Format first
-    dg_cmd(&temp).arg("fmt").assert().success();
+    let output = dg_cmd(&temp)
+        .args([
+            "query",
+            "match (s:strategy)-[:enables*1..3]->(d:decision) return s, d",
+            "-f",
+            "ids",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "STR-001\tDEC-001\nSTR-001\tDEC-002\n"
+    );
+
+    let output = dg_cmd(&temp)
+        .args([
+            "query",
+            "match (s:strategy)-[:enables*1..3]->(d:decision {status:accepted}) return d",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(rows.as_array().unwrap().len(), 1);
+    assert_eq!(rows[0]["d"]["id"], "DEC-002");
+    assert_eq!(rows[0]["d"]["title"], "Audit Logs");
 
-    // Check should pass
-    dg_cmd(&temp).args(["fmt", "--check"]).assert().success();
+    dg_cmd(&temp)
+        .args(["query", "match (s:strategy)-[:enables]->(d) return x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown variable 'x' (column 43)"));
 }
 
 // ============================================================================
-// Lint Tests
+// Path Tests
 // ============================================================================
 
 #[test]
-fn test_lint_clean() {
+fn test_path_between_records() {
     let temp = setup_test_env();
-
+    for title in ["Pick Cloud", "Set Budget", "Hire SRE"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["new", "strategy", "Go Global"])
         .assert()
         .success();
+    for (from, link, to) in [
+        ("STR-001", "enables", "DEC-001"),
+        ("DEC-001", "depends_on", "DEC-002"),
+        ("DEC-003", "depends_on", "DEC-002"),
+    ] {
+        dg_cmd(&temp)
+            .args(["link", from, link, to])
+            .assert()
+            .success();
+    }
 
     dg_cmd(&temp)
-        .arg("lint")
+        .args(["path", "STR-001", "DEC-003"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("OK"));
-}
-
-#[test]
-fn test_lint_strict_missing_tags() {
-    let temp = setup_test_env();
-
+        .stdout(predicate::str::contains("←depends_on─"))
+        .stdout(predicate::str::contains("1 path"));
+
+    let output = dg_cmd(&temp)
+        .args(["path", "STR-001", "DEC-003", "--format", "json"])
+        .output()
+        .unwrap();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        result["paths"][0]["nodes"],
+        serde_json::json!(["STR-001", "DEC-001", "DEC-002", "DEC-003"])
+    );
+
+    // Following links only as declared, DEC-003 is out of reach
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["path", "STR-001", "DEC-003", "--directed"])
         .assert()
-        .success();
+        .success()
+        .stdout(predicate::str::contains("No path from STR-001 to DEC-003"));
+    dg_cmd(&temp)
+        .args(["path", "STR-001", "DEC-003", "--all", "--max-len", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("within 2 links"));
 
-    // Strict mode should fail on missing tags
     dg_cmd(&temp)
-        .args(["lint", "--strict"])
+        .args([
+            "path",
+            "STR-001",
+            "DEC-002",
+            "-l",
+            "enables",
+            "-l",
+            "depends_on",
+            "-f",
+            "dot",
+        ])
         .assert()
-        .failure()
-        .stdout(predicate::str::contains("missing required field"));
+        .success()
+        .stdout(predicate::str::contains("\"DEC-001\" -> \"DEC-002\""));
 }
 
 // ============================================================================
-// Stats Tests
+// Retarget Tests
 // ============================================================================
 
 #[test]
-fn test_stats() {
+fn test_stale_links_and_retarget() {
     let temp = setup_test_env();
-
+    for title in ["Use Postgres", "Use MySQL", "Use CockroachDB"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["new", "adr", "Schema Layout"])
         .assert()
         .success();
-    dg_cmd(&temp).args(["new", "adr", "ADR"]).assert().success();
+    for (from, link, to) in [
+        ("ADR-001", "depends_on", "DEC-001"),
+        ("DEC-002", "supersedes", "DEC-001"),
+        ("DEC-003", "supersedes", "DEC-002"),
+    ] {
+        dg_cmd(&temp)
+            .args(["link", from, link, to])
+            .assert()
+            .success();
+    }
+
+    // The chain resolves to the newest record
+    dg_cmd(&temp)
+        .args(["show", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("→ DEC-003"));
+    dg_cmd(&temp)
+        .args(["show", "DEC-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Now in effect: DEC-003"));
+    dg_cmd(&temp)
+        .args(["why", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("→ DEC-003]"));
+    dg_cmd(&temp)
+        .args(["lint"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+        "ADR-001: depends_on DEC-001, which is superseded by DEC-003 (use 'dg retarget ADR-001')",
+    ));
 
     dg_cmd(&temp)
-        .arg("stats")
+        .args(["retarget", "--dry-run"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Total records: 2"))
-        .stdout(predicate::str::contains("DEC"));
+        .stdout(predicate::str::contains(
+            "Would retarget ADR-001 depends_on DEC-001 → DEC-003",
+        ));
+    dg_cmd(&temp).args(["retarget"]).assert().success();
+
+    let adr = fs::read_dir(temp.path().join("docs/decisions"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.to_string_lossy().contains("ADR-001"))
+        .unwrap();
+    let content = fs::read_to_string(adr).unwrap();
+    assert!(content.contains("DEC-003"));
+    assert!(!content.contains("DEC-001"));
+    dg_cmd(&temp)
+        .args(["lint"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("superseded by").not());
 }
 
 // ============================================================================
-// Graph Tests
+// Cycles and Order Tests
 // ============================================================================
 
 #[test]
-fn test_graph_text() {
+fn test_link_cycles_and_order() {
     let temp = setup_test_env();
-
+    for title in ["Pick Cloud", "Set Budget", "Hire SRE"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["new", "strategy", "Go Global"])
         .assert()
         .success();
+    for (from, to) in [
+        ("STR-001", "DEC-001"),
+        ("STR-001", "DEC-003"),
+        ("DEC-001", "DEC-002"),
+        ("DEC-003", "DEC-002"),
+    ] {
+        dg_cmd(&temp)
+            .args(["link", from, "depends_on", to])
+            .assert()
+            .success();
+    }
+
+    let output = dg_cmd(&temp)
+        .args(["order", "STR-001", "--format", "json"])
+        .output()
+        .unwrap();
+    let order: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let layers: Vec<Vec<&str>> = order["layers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|layer| {
+            layer
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["id"].as_str().unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        layers,
+        vec![vec!["DEC-002"], vec!["DEC-001", "DEC-003"], vec!["STR-001"]]
+    );
+    assert_eq!(order["layers"][2][0]["after"][0], "DEC-001");
 
     dg_cmd(&temp)
-        .arg("graph")
+        .args(["order"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("DEC"));
-}
-
-#[test]
-fn test_graph_dot() {
-    let temp = setup_test_env();
+        .stdout(predicate::str::contains("Step 2 ‖ 2 in parallel"));
 
+    // Closing the loop is a lint error that names the whole cycle
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["link", "DEC-002", "depends_on", "STR-001"])
         .assert()
         .success();
-
     dg_cmd(&temp)
-        .args(["graph", "-f", "dot"])
+        .args(["lint"])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("digraph"))
-        .stdout(predicate::str::contains("DEC-001"));
-}
-
-#[test]
-fn test_graph_json() {
-    let temp = setup_test_env();
+        .failure()
+        .stdout(predicate::str::contains(
+            "DEC-001: depends_on cycle: DEC-001 → DEC-002 → STR-001 → DEC-001",
+        ));
+    dg_cmd(&temp)
+        .args(["order"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("they form a cycle"));
 
+    // Link types that aren't acyclic may loop freely
     dg_cmd(&temp)
-        .args(["new", "decision", "Test"])
+        .args(["unlink", "DEC-002", "depends_on", "STR-001"])
         .assert()
         .success();
-
     dg_cmd(&temp)
-        .args(["graph", "-f", "json"])
+        .args(["link", "DEC-002", "relates_to", "STR-001"])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("\"nodes\""))
-        .stdout(predicate::str::contains("\"edges\""));
+        .success();
+    dg_cmd(&temp)
+        .args(["lint"])
+        .assert()
+        .stdout(predicate::str::contains("cycle").not());
 }
 
 // ============================================================================
-// Export Tests
+// Status Tests
 // ============================================================================
 
 #[test]
-fn test_export_json() {
+fn test_status_update() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["status", "DEC-001", "accepted"])
+        .assert()
+        .success();
+
+    // Verify status changed
+    let file = temp.path().join("docs/decisions/DEC-001-test.md");
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.contains("status: accepted"));
+}
+
+const INCIDENT_WORKFLOW: &str = r#"
+[users.gilfoyle]
+roles = ["sre"]
+
+[users.jared]
+roles = ["pm"]
+
+[workflows.incident]
+statuses = ["open", "resolved", "closed"]
+
+[[workflows.incident.transitions]]
+from = "open"
+to = ["resolved"]
+
+[[workflows.incident.transitions]]
+from = "resolved"
+to = ["closed", "open"]
+roles = ["sre"]
+"#;
+
+#[test]
+fn test_status_workflow_enforced() {
+    let temp = setup_test_env();
+    fs::write(temp.path().join("docs/dg.toml"), INCIDENT_WORKFLOW).unwrap();
+
+    dg_cmd(&temp)
+        .args(["new", "incident", "Outage"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["status", "INC-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Legal next states: resolved"));
+
+    dg_cmd(&temp)
+        .args(["status", "INC-001", "superseded"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a valid status for incident"));
+
+    dg_cmd(&temp)
+        .args(["status", "INC-001", "closed"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("legal next states: resolved"));
+
+    dg_cmd(&temp)
+        .args(["resolve", "INC-001"])
+        .assert()
+        .success();
+
+    // resolved -> closed is limited to the sre role
+    dg_cmd(&temp)
+        .env("DG_USER", "jared")
+        .args(["status", "INC-001", "closed"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("requires role: sre"));
+
+    dg_cmd(&temp)
+        .env("DG_USER", "gilfoyle")
+        .args(["status", "INC-001", "closed"])
+        .assert()
+        .success();
+}
+
+// ============================================================================
+// History Tests
+// ============================================================================
+
+#[test]
+fn test_at_reads_records_from_git_history() {
+    let temp = setup_test_env();
+    let git = |args: &[&str], date: &str| {
+        git_cmd(&temp)
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .output()
+            .unwrap()
+    };
+    git(&["init", "-q"], "2025-01-10T12:00:00Z");
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Use Postgres"])
+        .assert()
+        .success();
+    git(&["add", "."], "2025-01-10T12:00:00Z");
+    git(&["commit", "-q", "-m", "Postgres"], "2025-01-10T12:00:00Z");
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Add Read Replicas"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["link", "DEC-002", "depends_on", "DEC-001"])
+        .assert()
+        .success();
+    git(&["add", "."], "2025-06-01T12:00:00Z");
+    git(&["commit", "-q", "-m", "Replicas"], "2025-06-01T12:00:00Z");
+
+    // Uncommitted edits don't leak into the past
+    dg_cmd(&temp)
+        .args(["new", "decision", "Uncommitted"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["--at", "2025-03-01", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Use Postgres"))
+        .stdout(predicate::str::contains("Add Read Replicas").not());
+    dg_cmd(&temp)
+        .args(["--at", "2025-03-01", "show", "DEC-002"])
+        .assert()
+        .failure();
+    dg_cmd(&temp)
+        .args(["--at", "HEAD", "why", "DEC-002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-001"));
+    dg_cmd(&temp)
+        .args(["--at", "HEAD", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Uncommitted").not());
+    dg_cmd(&temp)
+        .args(["--at", "2024-01-01", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No commits on or before 2024-01-01",
+        ));
+    dg_cmd(&temp)
+        .args(["--at", "HEAD", "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--at is only supported by"));
+}
+
+// ============================================================================
+// Diff Tests
+// ============================================================================
+
+#[test]
+fn test_diff_reports_semantic_changes() {
+    let temp = setup_test_env();
+    git(&temp, &["init", "-q"]);
+    for title in ["Use Postgres", "Add Read Replicas", "Use Redis"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    dg_cmd(&temp)
+        .args(["link", "DEC-002", "depends_on", "DEC-001"])
+        .assert()
+        .success();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Initial records"]);
+
+    dg_cmd(&temp)
+        .args(["status", "DEC-001", "accepted"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["unlink", "DEC-002", "depends_on", "DEC-001"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["link", "DEC-003", "relates_to", "DEC-001"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("status: proposed → accepted"))
+        .stdout(predicate::str::contains("links: -depends_on DEC-001"))
+        .stdout(predicate::str::contains("DEC-002 is now orphaned"));
+
+    let output = dg_cmd(&temp)
+        .args(["diff", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["base"], "HEAD");
+    assert_eq!(
+        json["diff"]["newly_orphaned"],
+        serde_json::json!(["DEC-002"])
+    );
+    assert!(json["diff"]["edges_added"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["from"] == "DEC-003" && e["to"] == "DEC-001"));
+
+    dg_cmd(&temp)
+        .args(["diff", "--format", "markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## Decision record changes"))
+        .stdout(predicate::str::contains(
+            "- status: `proposed` → `accepted`",
+        ));
+
+    // Comparing two refs ignores the working tree
+    dg_cmd(&temp)
+        .args(["diff", "--head", "HEAD"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No record changes"));
+}
+
+// ============================================================================
+// Log and Blame Tests
+// ============================================================================
+
+#[test]
+fn test_log_and_blame_follow_renames() {
+    let temp = setup_test_env();
+    let git = |args: &[&str]| {
+        git_cmd(&temp)
+            .args([
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@example.com",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    git(&["init", "-q"]);
+    dg_cmd(&temp)
+        .args(["new", "decision", "Use Postgres"])
+        .assert()
+        .success();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Propose Postgres"]);
+
+    dg_cmd(&temp)
+        .args(["status", "DEC-001", "accepted"])
+        .assert()
+        .success();
+    let path = std::fs::read_dir(temp.path().join("docs/decisions"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.extension().is_some_and(|e| e == "md"))
+        .unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        format!("{}\n## Operations\n\nRun backups.\n", content),
+    )
+    .unwrap();
+    git(&["commit", "-q", "-am", "Accept Postgres"]);
+
+    dg_cmd(&temp)
+        .args(["--force", "retype", "DEC-001", "adr"])
+        .assert()
+        .success();
+    git(&["add", "-A"]);
+    git(&["commit", "-q", "-m", "Make it an ADR"]);
+
+    dg_cmd(&temp)
+        .args(["log", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Make it an ADR"))
+        .stdout(predicate::str::contains("id: DEC-001 → ADR-001"))
+        .stdout(predicate::str::contains("status: proposed → accepted"))
+        .stdout(predicate::str::contains("created as DEC-001 (proposed)"));
+
+    let output = dg_cmd(&temp)
+        .args(["log", "ADR-001", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let versions = json["versions"].as_array().unwrap();
+    assert_eq!(versions.len(), 3);
+    assert_eq!(versions[0]["author"], "Alice");
+    assert!(versions[2]["path"].as_str().unwrap().contains("DEC-001"));
+
+    let output = dg_cmd(&temp)
+        .args(["blame", "ADR-001", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let sections = json["sections"].as_array().unwrap();
+    let message = |heading: &str| {
+        sections
+            .iter()
+            .find(|s| s["heading"] == heading)
+            .map(|s| s["message"].clone())
+            .unwrap()
+    };
+    assert_eq!(message("Operations"), "Accept Postgres");
+    assert_eq!(message("Setting"), "Propose Postgres");
+}
+
+// ============================================================================
+// Merge Driver Tests
+// ============================================================================
+
+#[test]
+fn test_merge_driver_unions_concurrent_links() {
+    let temp = setup_test_env();
+    // Git runs the driver as `dg`, so put this build first on PATH
+    let bin_dir = std::path::Path::new(env!("CARGO_BIN_EXE_dg"))
+        .parent()
+        .unwrap();
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let git = |args: &[&str]| {
+        git_cmd(&temp)
+            .args(args)
+            .env("PATH", &path)
+            .output()
+            .unwrap()
+    };
+    git(&["init", "-q", "-b", "main"]);
+    dg_cmd(&temp)
+        .args(["init", "--merge-driver"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Registered"));
+    let attributes = fs::read_to_string(temp.path().join(".gitattributes")).unwrap();
+    assert!(attributes.contains("docs/decisions/**/*.md merge=dg"));
+
+    for title in ["Use Postgres", "Add Replicas", "Use Redis"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Records"]);
+
+    git(&["checkout", "-q", "-b", "replicas"]);
+    dg_cmd(&temp)
+        .args(["link", "DEC-001", "enables", "DEC-002"])
+        .assert()
+        .success();
+    git(&["commit", "-q", "-am", "Replicas"]);
+
+    git(&["checkout", "-q", "main"]);
+    dg_cmd(&temp)
+        .args(["link", "DEC-001", "relates_to", "DEC-003"])
+        .assert()
+        .success();
+    git(&["commit", "-q", "-am", "Redis"]);
+
+    let merge = git(&["merge", "-q", "--no-edit", "replicas"]);
+    assert!(
+        merge.status.success(),
+        "{}",
+        String::from_utf8_lossy(&merge.stdout)
+    );
+    dg_cmd(&temp)
+        .args(["show", "DEC-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-002"))
+        .stdout(predicate::str::contains("DEC-003"));
+
+    // Different statuses on both sides are left for a person to resolve
+    git(&["checkout", "-q", "-b", "reject"]);
+    dg_cmd(&temp)
+        .args(["status", "DEC-003", "rejected"])
+        .assert()
+        .success();
+    git(&["commit", "-q", "-am", "Reject Redis"]);
+    git(&["checkout", "-q", "main"]);
+    dg_cmd(&temp)
+        .args(["status", "DEC-003", "accepted"])
+        .assert()
+        .success();
+    git(&["commit", "-q", "-am", "Accept Redis"]);
+
+    let merge = git(&["merge", "-q", "--no-edit", "reject"]);
+    assert!(!merge.status.success());
+    let record = fs::read_dir(temp.path().join("docs/decisions"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.to_string_lossy().contains("DEC-003"))
+        .unwrap();
+    let content = fs::read_to_string(record).unwrap();
+    assert!(content
+        .contains("<<<<<<< ours\nstatus: accepted\n=======\nstatus: rejected\n>>>>>>> theirs"));
+}
+
+// ============================================================================
+// Validate Tests
+// ============================================================================
+
+#[test]
+fn test_validate_clean() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK"));
+}
+
+#[test]
+fn test_validate_broken_link() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    // Manually add broken link
+    let file = temp.path().join("docs/decisions/DEC-001-test.md");
+    let content = fs::read_to_string(&file).unwrap();
+    let modified = content.replace("depends_on: []", "depends_on: [NONEXISTENT-001]");
+    fs::write(&file, modified).unwrap();
+
+    dg_cmd(&temp)
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("broken"));
+}
+
+// ============================================================================
+// Format Tests
+// ============================================================================
+
+#[test]
+fn test_fmt_check_clean() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    // Format first
+    dg_cmd(&temp).arg("fmt").assert().success();
+
+    // Check should pass
+    dg_cmd(&temp).args(["fmt", "--check"]).assert().success();
+}
+
+#[test]
+fn test_fmt_convert_toml_preserved_on_save() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "First"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Second"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["fmt", "--convert", "toml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Converted"));
+
+    let file = temp.path().join("docs/decisions/DEC-001-first.md");
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.starts_with("+++\ntype = \"decision\"\nid = \"DEC-001\"\n"));
+    dg_cmd(&temp).args(["fmt", "--check"]).assert().success();
+
+    // Rewrites through Record::save keep the TOML syntax
+    dg_cmd(&temp)
+        .args(["link", "DEC-001", "depends_on", "DEC-002"])
+        .assert()
+        .success();
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.starts_with("+++\n"));
+    assert!(content.contains("[links]\ndepends_on = [\"DEC-002\"]"));
+
+    dg_cmd(&temp)
+        .args(["fmt", "--convert", "yaml"])
+        .assert()
+        .success();
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.starts_with("---\ntype: decision\n"));
+    assert!(content.contains("depends_on: [DEC-002]"));
+
+    dg_cmd(&temp)
+        .args(["fmt", "--convert", "json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown frontmatter format"));
+}
+
+// ============================================================================
+// Lint Tests
+// ============================================================================
+
+#[test]
+fn test_lint_clean() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK"));
+}
+
+#[test]
+fn test_lint_strict_missing_tags() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    // Strict mode should fail on missing tags
+    dg_cmd(&temp)
+        .args(["lint", "--strict"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("missing required field"));
+}
+
+#[test]
+fn test_lint_workflow_status_and_transition() {
+    let temp = setup_test_env();
+    let config = format!("{}\n[git]\nbase = \"main\"\n", INCIDENT_WORKFLOW);
+    fs::write(temp.path().join("docs/dg.toml"), config).unwrap();
+
+    dg_cmd(&temp)
+        .args(["new", "incident", "Outage"])
+        .assert()
+        .success();
+    dg_cmd(&temp).arg("lint").assert().success();
+
+    git(&temp, &["init", "-q", "-b", "main"]);
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Add incident"]);
+
+    // open -> closed skips the resolved state
+    let file = temp.path().join("docs/decisions/INC-001-outage.md");
+    let content = fs::read_to_string(&file).unwrap();
+    fs::write(&file, content.replace("status: open", "status: closed")).unwrap();
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "cannot move from 'open' to 'closed'",
+        ));
+
+    // A status outside the workflow is reported even without history
+    fs::write(&file, content.replace("status: open", "status: accepted")).unwrap();
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("not a valid status for incident"));
+
+    // Once committed on a branch, as in CI, the move is still checked
+    // against where the branch forked from the base ref
+    git(&temp, &["checkout", "-q", "-b", "close"]);
+    fs::write(&file, content.replace("status: open", "status: closed")).unwrap();
+    git(&temp, &["commit", "-q", "-am", "Close incident"]);
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "cannot move from 'open' to 'closed'",
+        ));
+}
+
+// ============================================================================
+// Stats Tests
+// ============================================================================
+
+#[test]
+fn test_stats() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+    dg_cmd(&temp).args(["new", "adr", "ADR"]).assert().success();
+
+    dg_cmd(&temp)
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total records: 2"))
+        .stdout(predicate::str::contains("DEC"));
+}
+
+#[test]
+fn test_stats_centrality() {
+    let temp = setup_test_env();
+    for title in ["Hub", "Spoke A", "Spoke B", "Spoke C"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    for from in ["DEC-002", "DEC-003", "DEC-004"] {
+        dg_cmd(&temp)
+            .args(["link", from, "depends_on", "DEC-001"])
+            .assert()
+            .success();
+    }
+
+    let output = dg_cmd(&temp)
+        .args(["stats", "--format", "json"])
+        .output()
+        .unwrap();
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["total_records"], 4);
+    assert_eq!(stats["centrality"][0]["id"], "DEC-001");
+    assert_eq!(stats["centrality"][0]["in_degree"], 3);
+    assert_eq!(stats["articulation_points"], serde_json::json!(["DEC-001"]));
+    assert_eq!(stats["components"].as_array().unwrap().len(), 1);
+    assert_eq!(stats["core_suggestions"], serde_json::json!(["DEC-001"]));
+
+    dg_cmd(&temp)
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "removing it disconnects 2 records",
+        ))
+        .stdout(predicate::str::contains("Consider marking core"));
+}
+
+// ============================================================================
+// Graph Tests
+// ============================================================================
+
+#[test]
+fn test_graph_text() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .arg("graph")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC"));
+}
+
+#[test]
+fn test_graph_dot() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["graph", "-f", "dot"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("digraph"))
+        .stdout(predicate::str::contains("DEC-001"));
+}
+
+#[test]
+fn test_graph_json() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Test"])
+        .assert()
+        .success();
+
+    dg_cmd(&temp)
+        .args(["graph", "-f", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"nodes\""))
+        .stdout(predicate::str::contains("\"edges\""));
+}
+
+#[test]
+fn test_body_references_become_mentions_edges() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "strategy", "Expansion"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Pricing"])
+        .assert()
+        .success();
+
+    let file = temp.path().join("docs/decisions/DEC-001-pricing.md");
+    let mut content = fs::read_to_string(&file).unwrap();
+    content.push_str("\nAs part of [[STR-001|the expansion]], see also STR-001.\n");
+    fs::write(&file, &content).unwrap();
+
+    dg_cmd(&temp)
+        .args(["graph", "-f", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"type\": \"mentions\""));
+
+    dg_cmd(&temp)
+        .args(["show", "STR-001", "--links"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mentions <- DEC-001"));
+
+    dg_cmd(&temp)
+        .args(["impact", "STR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mentioned"))
+        .stdout(predicate::str::contains("DEC-001"));
+
+    // A reference to a missing record is a lint warning, not an edge
+    content.push_str("\nRetired in favour of [[STR-099]].\n");
+    fs::write(&file, &content).unwrap();
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[[STR-099]]"));
+}
+
+#[test]
+fn test_section_references_and_transclusion_cycles() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "policy", "Retention"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Storage"])
+        .assert()
+        .success();
+
+    let policy = temp.path().join("docs/decisions/POL-001-retention.md");
+    let mut policy_content = fs::read_to_string(&policy).unwrap();
+    policy_content.push_str("\n## Retention Rules\n\nKeep logs for 30 days.\n");
+    fs::write(&policy, &policy_content).unwrap();
+
+    let decision = temp.path().join("docs/decisions/DEC-001-storage.md");
+    let mut decision_content = fs::read_to_string(&decision).unwrap();
+    decision_content.push_str(
+        "\n## Constraints\n\n![[POL-001#retention-rules]]\n\nSee [[POL-001#Deletion Schedule]].\n",
+    );
+    fs::write(&decision, &decision_content).unwrap();
+
+    // A missing heading is a warning; the embed itself is fine
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[[POL-001#deletion-schedule]] points to a heading that doesn't exist",
+        ))
+        .stdout(predicate::str::contains("retention-rules").not());
+
+    // Embedding back into the section that embeds us is a cycle
+    policy_content.push_str("\n![[DEC-001#constraints]]\n");
+    fs::write(&policy, &policy_content).unwrap();
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "transclusion cycle: DEC-001 → POL-001#retention-rules → DEC-001#constraints",
+        ));
+}
+
+#[test]
+fn test_bare_id_references_opt_in() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "strategy", "Expansion"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Pricing"])
+        .assert()
+        .success();
+
+    let file = temp.path().join("docs/decisions/DEC-001-pricing.md");
+    let mut content = fs::read_to_string(&file).unwrap();
+    content.push_str("\nAs part of STR-001.\n");
+    fs::write(&file, &content).unwrap();
+
+    dg_cmd(&temp)
+        .args(["impact", "STR-001", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"mentioned_in\": []"));
+
+    fs::write(
+        temp.path().join("docs/dg.toml"),
+        "[references]\nbare_ids = true\n",
+    )
+    .unwrap();
+    dg_cmd(&temp)
+        .args(["impact", "STR-001", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"DEC-001\""));
+}
+
+// ============================================================================
+// Export Tests
+// ============================================================================
+
+#[test]
+fn test_export_json() {
     let temp = setup_test_env();
 
     dg_cmd(&temp)
@@ -640,6 +1925,39 @@ fn test_reindex() {
 
     let index = temp.path().join("docs/.index.json");
     assert!(index.exists());
+    assert!(temp.path().join("docs/.cache/records.json").exists());
+}
+
+#[test]
+fn test_cached_records_pick_up_edits() {
+    let temp = setup_test_env();
+
+    dg_cmd(&temp)
+        .args(["new", "decision", "Cache Me"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache Me"));
+
+    // Same length, different text: only the content hash tells them apart
+    let file = temp.path().join("docs/decisions/DEC-001-cache-me.md");
+    let content = fs::read_to_string(&file).unwrap();
+    fs::write(&file, content.replace("Cache Me", "Cache Us")).unwrap();
+    dg_cmd(&temp)
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache Us"));
+
+    fs::remove_file(&file).unwrap();
+    dg_cmd(&temp)
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache Us").not());
 }
 
 // ============================================================================
@@ -648,7 +1966,7 @@ fn test_reindex() {
 
 /// Helper to run dg command in a sample directory
 fn dg_sample_cmd(sample_dir: &str) -> Command {
-    let mut cmd = Command::cargo_bin("dg").unwrap();
+    let mut cmd = cargo_bin_cmd!("dg");
     let docs_path = format!("samples/{}/docs", sample_dir);
     cmd.args(["-D", &docs_path]);
     cmd
@@ -717,3 +2035,500 @@ fn test_sample_microsoft_stats() {
         .success()
         .stdout(predicate::str::contains("Total records"));
 }
+
+// ============================================================================
+// Finalize Tests
+// ============================================================================
+
+#[test]
+fn test_finalize_skips_branch_ids_and_rebases_duplicates() {
+    let temp = setup_test_env();
+    git(&temp, &["init", "-q", "-b", "main"]);
+    dg_cmd(&temp)
+        .args(["new", "decision", "Use Postgres"])
+        .assert()
+        .success();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Postgres"]);
+
+    git(&temp, &["checkout", "-q", "-b", "redis"]);
+    dg_cmd(&temp)
+        .args(["new", "decision", "Use Redis", "--draft"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["finalize", "--base", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-002"));
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Redis"]);
+
+    // A second branch mints DEC-002 without knowing about the first
+    git(&temp, &["checkout", "-q", "main"]);
+    git(&temp, &["checkout", "-q", "-b", "queue"]);
+    dg_cmd(&temp)
+        .args(["new", "decision", "Use RabbitMQ"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "decision", "Queue Consumers", "--draft"])
+        .assert()
+        .success();
+    // DEC-002 is taken on the redis branch, so the draft skips it
+    dg_cmd(&temp)
+        .args(["finalize", "--base", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-003"))
+        .stdout(predicate::str::contains("duplicate").not());
+    dg_cmd(&temp)
+        .args(["link", "DEC-003", "depends_on", "DEC-002"])
+        .assert()
+        .success();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Queue"]);
+
+    // Once the redis branch lands, the queue branch holds a duplicate
+    git(&temp, &["checkout", "-q", "main"]);
+    git(&temp, &["merge", "-q", "redis"]);
+    git(&temp, &["checkout", "-q", "queue"]);
+    dg_cmd(&temp)
+        .args(["finalize", "--base", "main", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("duplicate permanent ID"))
+        .stdout(predicate::str::contains("DEC-002-use-redis.md on main"))
+        .stdout(predicate::str::contains("DEC-002-use-rabbitmq.md"))
+        .stdout(predicate::str::contains("--rebase-ids"));
+
+    dg_cmd(&temp)
+        .args(["finalize", "--base", "main", "--rebase-ids"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-004"));
+    let decisions = temp.path().join("docs/decisions");
+    assert!(decisions.join("DEC-004-use-rabbitmq.md").exists());
+    assert!(!decisions.join("DEC-002-use-rabbitmq.md").exists());
+    dg_cmd(&temp)
+        .args(["show", "DEC-003"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-004"));
+
+    git(&temp, &["add", "-A"]);
+    git(&temp, &["commit", "-q", "-m", "Renumber"]);
+    // Only the derived index conflicts; it is rebuilt on the next write
+    let merge = git(&temp, &["merge", "-q", "--no-edit", "-X", "ours", "main"]);
+    assert!(merge.status.success());
+    dg_cmd(&temp)
+        .args(["finalize", "--base", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("duplicate").not());
+    dg_cmd(&temp)
+        .args(["show", "DEC-002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Use Redis"));
+}
+
+#[test]
+fn test_finalize_rebase_ids_without_base_rewrites_local_references() {
+    let temp = setup_test_env();
+    for title in ["Use Postgres", "Use Redis", "Use Sqlite"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    dg_cmd(&temp)
+        .args(["link", "DEC-002", "depends_on", "DEC-003"])
+        .assert()
+        .success();
+    let decisions = temp.path().join("docs/decisions");
+    let redis = decisions.join("DEC-002-use-redis.md");
+    let content = fs::read_to_string(&redis).unwrap();
+    fs::write(&redis, format!("{}\nSee ticket DEC-0031.\n", content)).unwrap();
+
+    // Another branch's DEC-001 lands under the same ID, with no base ref
+    let sqlite = fs::read_to_string(decisions.join("DEC-003-use-sqlite.md")).unwrap();
+    fs::remove_file(decisions.join("DEC-003-use-sqlite.md")).unwrap();
+    fs::write(
+        decisions.join("DEC-001-use-sqlite.md"),
+        sqlite.replace("id: DEC-003", "id: DEC-001"),
+    )
+    .unwrap();
+    let content = fs::read_to_string(&redis).unwrap();
+    fs::write(&redis, content.replace("- DEC-003", "- DEC-001")).unwrap();
+
+    dg_cmd(&temp)
+        .args(["finalize", "--rebase-ids"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-001-use-sqlite.md → DEC-003"))
+        .stdout(predicate::str::contains("Review:").not());
+    assert!(decisions.join("DEC-003-use-sqlite.md").exists());
+    let postgres = fs::read_to_string(decisions.join("DEC-001-use-postgres.md")).unwrap();
+    assert!(postgres.contains("id: DEC-001"));
+    let content = fs::read_to_string(&redis).unwrap();
+    assert!(content.contains("- DEC-003"));
+    assert!(content.contains("ticket DEC-0031."));
+}
+
+#[test]
+fn test_finalize_rebase_ids_covers_nested_records() {
+    let temp = setup_test_env();
+    for title in ["Use Postgres", "Use Redis", "Use Sqlite"] {
+        dg_cmd(&temp)
+            .args(["new", "decision", title])
+            .assert()
+            .success();
+    }
+    dg_cmd(&temp)
+        .args(["link", "DEC-002", "depends_on", "DEC-003"])
+        .assert()
+        .success();
+
+    // A nested record reuses DEC-001, and another nested record links to it
+    let decisions = temp.path().join("docs/decisions");
+    let nested = decisions.join("sub");
+    fs::create_dir(&nested).unwrap();
+    let redis = fs::read_to_string(decisions.join("DEC-002-use-redis.md")).unwrap();
+    fs::remove_file(decisions.join("DEC-002-use-redis.md")).unwrap();
+    fs::write(
+        nested.join("DEC-002-use-redis.md"),
+        redis.replace("- DEC-003", "- DEC-001"),
+    )
+    .unwrap();
+    let sqlite = fs::read_to_string(decisions.join("DEC-003-use-sqlite.md")).unwrap();
+    fs::remove_file(decisions.join("DEC-003-use-sqlite.md")).unwrap();
+    fs::write(
+        nested.join("DEC-001-use-sqlite.md"),
+        sqlite.replace("id: DEC-003", "id: DEC-001"),
+    )
+    .unwrap();
+
+    dg_cmd(&temp)
+        .args(["finalize", "--rebase-ids"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DEC-001-use-sqlite.md → DEC-003"));
+    assert!(nested.join("DEC-003-use-sqlite.md").exists());
+    assert!(!nested.join("DEC-001-use-sqlite.md").exists());
+    let redis = fs::read_to_string(nested.join("DEC-002-use-redis.md")).unwrap();
+    assert!(redis.contains("- DEC-003"));
+    dg_cmd(&temp)
+        .args(["show", "DEC-003"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Use Sqlite"));
+}
+
+// ============================================================================
+// Commit Trailer Tests
+// ============================================================================
+
+#[test]
+fn test_commits_follow_decision_trailers() {
+    let temp = setup_test_env();
+    git(&temp, &["init", "-q"]);
+    dg_cmd(&temp)
+        .args(["new", "adr", "Cache sessions in Redis"])
+        .assert()
+        .success();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Record the cache decision"]);
+
+    fs::create_dir(temp.path().join("src")).unwrap();
+    fs::write(temp.path().join("src/cache.rs"), "// Redis client\n").unwrap();
+    git(&temp, &["add", "."]);
+    git(
+        &temp,
+        &[
+            "commit",
+            "-q",
+            "-m",
+            "Adopt Redis as the session cache library\n\nDecision: ADR-001",
+        ],
+    );
+    fs::write(temp.path().join("src/db.rs"), "// Postgres client\n").unwrap();
+    git(&temp, &["add", "."]);
+    git(
+        &temp,
+        &["commit", "-q", "-m", "Switch the database library to sqlx"],
+    );
+
+    dg_cmd(&temp)
+        .args(["commits", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Adopt Redis as the session cache"))
+        .stdout(predicate::str::contains("src/cache.rs"))
+        .stdout(predicate::str::contains("sqlx").not());
+    dg_cmd(&temp)
+        .args(["show", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Implemented by commits"))
+        .stdout(predicate::str::contains("Adopt Redis"));
+
+    // The trailer marks the first commit as covered
+    dg_cmd(&temp)
+        .current_dir(temp.path())
+        .args(["suggest", "--since", "1 year ago"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Switch the database library"))
+        .stdout(predicate::str::contains("Adopt Redis").not());
+}
+
+// ============================================================================
+// Code Reference Tests
+// ============================================================================
+
+#[test]
+fn test_refs_impact_and_lint_follow_code_annotations() {
+    let temp = setup_test_env();
+    git(&temp, &["init", "-q"]);
+    for title in ["Cache sessions in Redis", "Cache sessions in Memcached"] {
+        dg_cmd(&temp).args(["new", "adr", title]).assert().success();
+    }
+    fs::create_dir(temp.path().join("src")).unwrap();
+    fs::write(
+        temp.path().join("src/cache.rs"),
+        "// @decision ADR-001\nfn connect() {}\n\n// Sessions expire per ADR-001\nfn expire() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/queue.py"),
+        "# @implements ADR-009\ndef publish(): pass\n",
+    )
+    .unwrap();
+    // Build output that git ignores is not scanned
+    fs::write(temp.path().join(".gitignore"), "generated/\n").unwrap();
+    fs::create_dir(temp.path().join("generated")).unwrap();
+    fs::write(
+        temp.path().join("generated/cache.rs"),
+        "// @decision ADR-001\n// @decision ADR-404\n",
+    )
+    .unwrap();
+
+    dg_cmd(&temp)
+        .args(["refs", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("src/cache.rs"))
+        .stdout(predicate::str::contains("// @decision ADR-001"))
+        .stdout(predicate::str::contains("Sessions expire per ADR-001"))
+        .stdout(predicate::str::contains(
+            "2 reference(s) in 1 file(s), 1 annotated",
+        ));
+
+    let output = dg_cmd(&temp)
+        .args(["impact", "ADR-001", "-f", "json"])
+        .output()
+        .unwrap();
+    let impact: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        impact["implemented_in"],
+        serde_json::json!(["src/cache.rs"])
+    );
+
+    dg_cmd(&temp)
+        .args(["link", "ADR-002", "supersedes", "ADR-001"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["status", "ADR-001", "superseded"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .arg("lint")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "src/cache.rs:1: @decision ADR-001, which is superseded by ADR-002",
+        ))
+        .stdout(predicate::str::contains(
+            "src/queue.py:1: @implements ADR-009 points to a record that doesn't exist",
+        ))
+        .stdout(predicate::str::contains("src/cache.rs:4").not())
+        .stdout(predicate::str::contains("generated/").not());
+}
+
+// ============================================================================
+// Governance Tests
+// ============================================================================
+
+#[test]
+fn test_governs_and_diff_report_governing_records() {
+    let temp = setup_test_env();
+    git(&temp, &["init", "-q", "-b", "main"]);
+    dg_cmd(&temp)
+        .args(["new", "adr", "Charge cards through Stripe"])
+        .assert()
+        .success();
+    dg_cmd(&temp)
+        .args(["new", "policy", "Schema changes need review"])
+        .assert()
+        .success();
+    let docs = temp.path().join("docs");
+    let mut config = fs::read_to_string(docs.join("dg.toml")).unwrap_or_default();
+    config.push_str("\n[governance]\n\"src/payments/**\" = [\"ADR-001\"]\n");
+    fs::write(docs.join("dg.toml"), config).unwrap();
+    fs::write(
+        docs.join("GOVERNANCE"),
+        "# Migrations\n*.sql POL-001\nsrc/payments/ POL-001\n",
+    )
+    .unwrap();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Governance"]);
+
+    dg_cmd(&temp)
+        .current_dir(temp.path())
+        .args(["governs", "src/payments/charge.rs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "ADR-001 Charge cards through Stripe",
+        ))
+        .stdout(predicate::str::contains("POL-001"))
+        .stdout(predicate::str::contains("GOVERNANCE:3"));
+    dg_cmd(&temp)
+        .current_dir(temp.path())
+        .args(["governs", "README.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No records govern README.md"));
+
+    fs::create_dir_all(temp.path().join("src/payments")).unwrap();
+    fs::create_dir_all(temp.path().join("db")).unwrap();
+    fs::write(
+        temp.path().join("src/payments/charge.rs"),
+        "fn charge() {}\n",
+    )
+    .unwrap();
+    fs::write(temp.path().join("db/schema.sql"), "create table t ();\n").unwrap();
+    fs::write(temp.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+
+    let output = dg_cmd(&temp)
+        .args(["diff", "--base", "main", "-f", "json"])
+        .output()
+        .unwrap();
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let governing = diff["governing"].as_array().unwrap();
+    assert_eq!(governing.len(), 2);
+    assert_eq!(governing[0]["id"], "ADR-001");
+    assert_eq!(
+        governing[0]["paths"],
+        serde_json::json!(["src/payments/charge.rs"])
+    );
+    assert_eq!(governing[1]["id"], "POL-001");
+    assert_eq!(
+        governing[1]["paths"],
+        serde_json::json!(["db/schema.sql", "src/payments/charge.rs"])
+    );
+
+    dg_cmd(&temp)
+        .args(["diff", "--base", "main", "-f", "markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("### Governing records"))
+        .stdout(predicate::str::contains(
+            "- **ADR-001** Charge cards through Stripe (`src/payments/charge.rs`)",
+        ));
+}
+
+// ============================================================================
+// Suggest Tests
+// ============================================================================
+
+#[test]
+fn test_suggest_scores_diffs_clusters_and_creates_drafts() {
+    let temp = setup_test_env();
+    git(&temp, &["init", "-q"]);
+    fs::write(temp.path().join("README.md"), "# App\n").unwrap();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Initial commit"]);
+
+    // A new top-level directory and a manifest change, then follow-up work in it
+    fs::create_dir(temp.path().join("worker")).unwrap();
+    fs::write(temp.path().join("worker/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[dependencies]\nlapin = \"2\"\n",
+    )
+    .unwrap();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Start background worker"]);
+    fs::write(temp.path().join("worker/jobs.rs"), "fn run() {}\n").unwrap();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Run jobs from the worker"]);
+    fs::write(temp.path().join("README.md"), "# App\n\nTypo fixed.\n").unwrap();
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Tweak readme wording"]);
+
+    let output = dg_cmd(&temp)
+        .current_dir(temp.path())
+        .args(["suggest", "--since", "1 year ago", "-f", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let suggestions = json.as_array().unwrap();
+    let worker = suggestions
+        .iter()
+        .find(|s| s["message"] == "Start background worker")
+        .expect("worker commit suggested");
+    assert_eq!(worker["suggested_type"], "ADR");
+    assert_eq!(worker["commits"].as_array().unwrap().len(), 2);
+    let kinds: Vec<_> = worker["signals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["kind"].as_str().unwrap())
+        .collect();
+    assert!(kinds.contains(&"dependency_manifest"));
+    assert!(kinds.contains(&"new_directory"));
+    assert!(!suggestions
+        .iter()
+        .any(|s| s["message"] == "Tweak readme wording"));
+    // The root commit creates docs/ but is not a decision
+    assert_eq!(suggestions.len(), 1);
+
+    let drafts = || -> Vec<std::path::PathBuf> {
+        fs::read_dir(temp.path().join("docs/decisions"))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.to_string_lossy().contains("ADR-NEW-"))
+            .collect()
+    };
+    dg_cmd(&temp)
+        .current_dir(temp.path())
+        .args(["suggest", "--since", "1 year ago", "--create-drafts"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("ADR-NEW-"));
+    let written = drafts();
+    assert_eq!(written.len(), 1);
+    assert!(written[0]
+        .to_string_lossy()
+        .ends_with("-start-background-worker.md"));
+    let content = fs::read_to_string(&written[0]).unwrap();
+    assert!(content.contains("suggested_from:"));
+    assert!(content.contains("# Start background worker"));
+    assert!(content.contains("from 2 commits"));
+    assert!(content.contains("Run jobs from the worker"));
+    assert!(content.contains("`worker/jobs.rs` (+1 -0)"));
+    assert!(content.contains("`Cargo.toml`"));
+
+    // Commits already drafted are not drafted again
+    dg_cmd(&temp)
+        .current_dir(temp.path())
+        .args(["suggest", "--since", "1 year ago", "--create-drafts"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("already drafted"));
+    assert_eq!(drafts().len(), 1);
+}
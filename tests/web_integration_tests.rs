@@ -21,9 +21,7 @@ fn sample_docs_path() -> PathBuf {
 }
 
 /// Spawn a test server and return the process handle and base URL
-fn spawn_test_server(port: u16) -> Child {
-    let docs_path = sample_docs_path();
-
+fn spawn_test_server(port: u16, docs_path: &std::path::Path) -> Child {
     Command::new(env!("CARGO_BIN_EXE_dg"))
         .args([
             "--docs-dir",
@@ -38,6 +36,18 @@ fn spawn_test_server(port: u16) -> Child {
         .expect("Failed to start server")
 }
 
+/// Run git in a test directory as a test user, expecting success
+fn git(temp: &tempfile::TempDir, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(temp.path())
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
 /// Wait for the server to be ready by polling the health endpoint
 async fn wait_for_server(base_url: &str, timeout: Duration) -> bool {
     let client = reqwest::Client::new();
@@ -62,8 +72,13 @@ struct TestServer {
 
 impl TestServer {
     async fn new() -> Self {
+        Self::with_docs(&sample_docs_path()).await
+    }
+
+    /// A server for another docs directory
+    async fn with_docs(docs_path: &std::path::Path) -> Self {
         let port = get_available_port();
-        let process = spawn_test_server(port);
+        let process = spawn_test_server(port, docs_path);
         let base_url = format!("http://127.0.0.1:{}", port);
 
         // Wait for server to be ready
@@ -355,6 +370,15 @@ async fn test_stats_page_returns_200() {
     assert_eq!(response.status(), 200);
 }
 
+#[tokio::test]
+async fn test_stats_page_shows_centrality() {
+    let server = TestServer::new().await;
+    let html = server.get_text("/stats").await;
+    assert!(html.contains("Most Central"));
+    assert!(html.contains("Keystones"));
+    assert!(html.contains("removing it disconnects"));
+}
+
 #[tokio::test]
 async fn test_stats_page_shows_counts() {
     let server = TestServer::new().await;
@@ -396,6 +420,77 @@ async fn test_api_records_contains_data() {
     assert!(first.get("type").is_some());
 }
 
+#[tokio::test]
+async fn test_api_records_full_text_query() {
+    let server = TestServer::new().await;
+    let records: Vec<serde_json::Value> = server.get_json("/api/records?q=peter%20gregory").await;
+
+    assert!(!records.is_empty());
+    // The record with the name in its title ranks first
+    assert_eq!(records[0]["id"], "DEC-001");
+    assert!(records[0]["score"].as_f64().unwrap() > 0.0);
+    assert!(records.iter().any(|r| r["snippet_html"]
+        .as_str()
+        .is_some_and(|s| s.contains("<mark>"))));
+
+    let none: Vec<serde_json::Value> = server.get_json("/api/records?q=zzzqqq").await;
+    assert!(none.is_empty());
+}
+
+#[tokio::test]
+async fn test_api_records_query_language() {
+    let server = TestServer::new().await;
+    let records: Vec<serde_json::Value> = server
+        .get_json("/api/records?q=type%3Adecision%20-status%3Aaccepted")
+        .await;
+    assert!(!records.is_empty());
+    assert!(records
+        .iter()
+        .all(|r| r["type"] == "DEC" && r["status"] != "accepted"));
+
+    let response = server.get("/api/records?q=tag%3Aa%20(b").await;
+    assert_eq!(response.status(), 400);
+    let error: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(error["error"], "unclosed '('");
+    assert_eq!(error["column"], 7);
+}
+
+#[tokio::test]
+async fn test_api_query_paths() {
+    let server = TestServer::new().await;
+    let rows: Vec<serde_json::Value> = server
+        .get_json("/api/query?q=match%20(d:DEC-001)-%5B:enables%5D-%3E(x)%20return%20x")
+        .await;
+    let ids: Vec<_> = rows
+        .iter()
+        .map(|r| r["x"]["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["ADR-001", "STR-001"]);
+
+    let response = server.get("/api/query?q=match%20(d)%20return").await;
+    assert_eq!(response.status(), 400);
+    let error: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(error["error"], "expected a variable to return");
+}
+
+#[tokio::test]
+async fn test_api_path_between_records() {
+    let server = TestServer::new().await;
+    let result: serde_json::Value = server.get_json("/api/path?from=ADR-001&to=ADR-003").await;
+    let paths = result["paths"].as_array().unwrap();
+    assert!(!paths.is_empty());
+    for path in paths {
+        assert_eq!(path["nodes"][0], "ADR-001");
+        assert_eq!(path["nodes"].as_array().unwrap().last().unwrap(), "ADR-003");
+    }
+
+    let response = server.get("/api/path?from=ADR-001&to=NOPE-999").await;
+    assert_eq!(response.status(), 404);
+
+    let html = server.get_text("/records/ADR-001").await;
+    assert!(html.contains("How is this connected to"));
+}
+
 #[tokio::test]
 async fn test_api_single_record_returns_json() {
     let server = TestServer::new().await;
@@ -418,6 +513,134 @@ async fn test_api_record_raw_returns_markdown() {
     assert!(text.contains("type: decision"));
 }
 
+#[tokio::test]
+async fn test_api_graph_at_revision() {
+    let server = TestServer::new().await;
+    let graph: serde_json::Value = server.get_json("/api/graph?at=HEAD").await;
+    assert!(!graph["nodes"].as_array().unwrap().is_empty());
+    assert!(graph["nodes"][0].get("type_name").is_some());
+
+    let response = server.get("/api/graph?at=no-such-revision").await;
+    assert_eq!(response.status(), 400);
+
+    let html = server.get_text("/graph").await;
+    assert!(html.contains("timeRange"));
+}
+
+#[tokio::test]
+async fn test_api_record_history() {
+    let server = TestServer::new().await;
+    let history: serde_json::Value = server.get_json("/api/records/ADR-001/history").await;
+    assert_eq!(history["id"], "ADR-001");
+    let versions = history["versions"].as_array().unwrap();
+    assert!(!versions.is_empty());
+    assert!(versions[0]["commit"].as_str().unwrap().len() >= 7);
+    assert!(versions[0]["path"].as_str().unwrap().ends_with(".md"));
+    assert!(!history["blame"].as_array().unwrap().is_empty());
+
+    let response = server.get("/api/records/NOPE-999/history").await;
+    assert_eq!(response.status(), 404);
+
+    let html = server.get_text("/records/ADR-001").await;
+    assert!(html.contains("history-panel"));
+}
+
+#[tokio::test]
+async fn test_record_page_lists_implementing_commits() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let docs = temp.path().join("docs");
+    let dg = |args: &[&str]| {
+        let status = Command::new(env!("CARGO_BIN_EXE_dg"))
+            .arg("--docs-dir")
+            .arg(&docs)
+            .args(args)
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&temp, &["init", "-q"]);
+    dg(&["init"]);
+    dg(&["new", "adr", "Cache sessions in Redis"]);
+    git(&temp, &["add", "."]);
+    git(&temp, &["commit", "-q", "-m", "Record the cache decision"]);
+    std::fs::create_dir(temp.path().join("src")).unwrap();
+    std::fs::write(temp.path().join("src/cache.rs"), "// Redis client\n").unwrap();
+    git(&temp, &["add", "."]);
+    git(
+        &temp,
+        &[
+            "commit",
+            "-q",
+            "-m",
+            "Add the Redis session cache\n\nDecision: ADR-001",
+        ],
+    );
+
+    let server = TestServer::with_docs(&docs).await;
+    let html = server.get_text("/records/ADR-001").await;
+    assert!(html.contains("Implemented By Commits"));
+    assert!(html.contains("Add the Redis session cache"));
+    assert!(html.contains("cache.rs"));
+}
+
+#[tokio::test]
+async fn test_save_enforces_workflow_and_roles() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let docs = temp.path().join("docs");
+    let dg = |args: &[&str]| {
+        let status = Command::new(env!("CARGO_BIN_EXE_dg"))
+            .arg("--docs-dir")
+            .arg(&docs)
+            .args(args)
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    dg(&["init"]);
+    dg(&["new", "incident", "Checkout outage"]);
+    let mut config = std::fs::read_to_string(docs.join("dg.toml")).unwrap_or_default();
+    config.push_str(
+        "\n[workflows.incident]\nstatuses = [\"open\", \"resolved\", \"closed\"]\n\n\
+         [[workflows.incident.transitions]]\nfrom = \"open\"\nto = [\"resolved\"]\n\n\
+         [[workflows.incident.transitions]]\nfrom = \"resolved\"\nto = [\"closed\"]\n\
+         roles = [\"sre\"]\n",
+    );
+    std::fs::write(docs.join("dg.toml"), config).unwrap();
+
+    let server = TestServer::with_docs(&docs).await;
+    let raw: serde_json::Value = server.get_json("/api/records/INC-001/raw").await;
+    let raw = raw["content"].as_str().unwrap().to_string();
+    let save = |status: &str| {
+        let content = raw.replace("status: open", &format!("status: {}", status));
+        let url = format!("{}/api/records/INC-001", server.base_url);
+        async move {
+            reqwest::Client::new()
+                .put(url)
+                .json(&serde_json::json!({ "content": content }))
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+
+    // Skipping a step is refused
+    assert_eq!(
+        save("closed").await.status(),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+    assert!(save("resolved").await.status().is_success());
+    // Only sre may close, and the server can't tell who is editing
+    let response = save("closed").await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body["error"],
+        "this transition requires role sre; use `dg status`"
+    );
+}
+
 #[tokio::test]
 async fn test_api_graph_returns_json() {
     let server = TestServer::new().await;
@@ -430,6 +653,48 @@ async fn test_api_graph_returns_json() {
     assert!(graph.get("edges").is_some() || graph.get("links").is_some());
 }
 
+#[tokio::test]
+async fn test_api_render_links_record_references() {
+    let server = TestServer::new().await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/render", server.base_url))
+        .json(&serde_json::json!({
+            "markdown": "Builds on [[DEC-001|the first decision]] but not [[DEC-999]]."
+        }))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let html = body["html"].as_str().unwrap();
+    assert!(html.contains(r#"href="/records/DEC-001""#));
+    assert!(html.contains(">the first decision</a>"));
+    assert!(html.contains("record-ref-missing"));
+}
+
+#[tokio::test]
+async fn test_api_render_transcludes_sections() {
+    let server = TestServer::new().await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/render", server.base_url))
+        .json(&serde_json::json!({
+            "markdown": "Per [[DEC-001#alternatives]]:\n\n![[DEC-001#decision]]\n\n![[DEC-001#no-such-heading]]\n"
+        }))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let html = body["html"].as_str().unwrap();
+    assert!(html.contains(r#"href="/records/DEC-001#alternatives""#));
+    assert!(html.contains("DEC-001 › Alternatives</a>"));
+    assert!(html.contains(r#"<div class="transclusion "#));
+    assert!(html.contains(r#"data-source="DEC-001#decision""#));
+    assert!(html.contains("transclusion-error"));
+}
+
 // ============================================================================
 // Static Assets Tests
 // ============================================================================